@@ -0,0 +1,135 @@
+//! Derive macros for blacklight.
+//!
+//! These are re-exported by the `blacklight` crate, and should be used
+//! through it rather than depended on directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{ quote, quote_spanned };
+use syn::{
+  parse_macro_input,
+  spanned::Spanned,
+  Attribute,
+  Data,
+  DeriveInput,
+  Fields,
+  LitStr,
+};
+
+/**
+ * Derive `StructMappedDataType` for a struct with named fields.
+ *
+ * Each field must be a host-shareable data type, or a struct which itself
+ * derives `StructMappedDataType` (mapped to `Struct<T>` in the shader).
+ * The struct and its fields may be renamed in the generated wgsl with
+ * `#[blacklight(rename = "...")]`.
+ */
+#[proc_macro_derive(StructMappedDataType, attributes(blacklight))]
+pub fn derive_struct_mapped_data_type(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+  match derive_struct_mapped(&input) {
+    Ok(tokens) => tokens.into(),
+    Err(err) => err.to_compile_error().into(),
+  }
+}
+
+fn derive_struct_mapped(input: &DeriveInput) -> syn::Result<TokenStream2> {
+  let ident = &input.ident;
+  if !input.generics.params.is_empty() {
+    return Err(syn::Error::new(input.generics.span(),
+      "StructMappedDataType cannot be derived for generic structs"));
+  }
+  let fields = match &input.data {
+    Data::Struct(data) => match &data.fields {
+      Fields::Named(fields) => &fields.named,
+      _ => return Err(syn::Error::new(ident.span(),
+        "StructMappedDataType can only be derived for structs with named fields")),
+    },
+    _ => return Err(syn::Error::new(ident.span(),
+      "StructMappedDataType can only be derived for structs")),
+  };
+
+  let struct_name = match parse_rename(&input.attrs)? {
+    Some(name) => name,
+    None => LitStr::new(&ident.to_string(), ident.span()),
+  };
+
+  let visits = fields.iter()
+    .map(|field| {
+      let field_ident = field.ident.as_ref().unwrap();
+      let field_name = match parse_rename(&field.attrs)? {
+        Some(name) => name,
+        None => LitStr::new(&field_ident.to_string(), field_ident.span()),
+      };
+      // Span the field type so that unsupported types are reported there.
+      let ty = &field.ty;
+      let field_data_type = quote_spanned! { ty.span() =>
+        <#ty as ::blacklight::data_type::StructFieldDataType>
+      };
+      Ok(quote! {
+        fv.visit_field::<#field_data_type::DataType, _, _>(
+          #field_name,
+          |s| #field_data_type::to_data_type(s.#field_ident),
+          |s, v| s.#field_ident = #field_data_type::from_data_type(v),
+        );
+      })
+    })
+    .collect::<syn::Result<Vec<_>>>()?;
+
+  Ok(quote! {
+    impl ::blacklight::data_type::StructMappedDataType for #ident {
+      const NAME: &'static str = #struct_name;
+      fn visit_fields<FV>(fv: &mut FV)
+        where FV: ::blacklight::data_type::StructFieldVisitor<Self>
+      {
+        #(#visits)*
+      }
+    }
+    impl ::blacklight::data_type::StructFieldDataType for #ident {
+      type DataType = ::blacklight::data_type::Struct<#ident>;
+      fn to_data_type(self) -> Self::DataType {
+        self.into()
+      }
+      fn from_data_type(data_type: Self::DataType) -> Self {
+        *data_type.data()
+      }
+    }
+  })
+}
+
+/** Parse a `#[blacklight(rename = "...")]` attribute, if present. */
+fn parse_rename(attrs: &[Attribute]) -> syn::Result<Option<LitStr>> {
+  let mut rename = None;
+  for attr in attrs.iter().filter(|attr| attr.path().is_ident("blacklight")) {
+    attr.parse_nested_meta(|meta| {
+      if meta.path.is_ident("rename") {
+        if rename.is_some() {
+          return Err(meta.error("duplicate rename"));
+        }
+        let name: LitStr = meta.value()?.parse()?;
+        if !is_wgsl_identifier(&name.value()) {
+          return Err(syn::Error::new(name.span(),
+            "rename must be a valid wgsl identifier"));
+        }
+        rename = Some(name);
+        Ok(())
+      } else {
+        Err(meta.error("unsupported blacklight attribute"))
+      }
+    })?;
+  }
+  Ok(rename)
+}
+
+/** Check that a name is a plausible wgsl identifier. */
+fn is_wgsl_identifier(name: &str) -> bool {
+  let mut chars = name.chars();
+  let valid_start = match chars.next() {
+    Some(c) => c == '_' || c.is_ascii_alphabetic(),
+    None => false,
+  };
+  valid_start
+    && name != "_"
+    && !name.starts_with("__")
+    && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
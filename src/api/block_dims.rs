@@ -1,3 +1,8 @@
+use crate::api::{
+  data_type::EntryPointArgDataType,
+  handle::ConstantHandle,
+};
+
 
 /**
  * Represents possible block dimensions.
@@ -8,3 +13,103 @@ pub enum BlockDims {
   TwoD([u32; 2]),
   ThreeD([u32; 3]),
 }
+impl BlockDims {
+  /** Get the dimensions as an `[x, y, z]` triple, padding with 1s. */
+  pub fn to_xyz(&self) -> [u32; 3] {
+    match *self {
+      BlockDims::OneD(x) => [x, 1, 1],
+      BlockDims::TwoD([x, y]) => [x, y, 1],
+      BlockDims::ThreeD(xyz) => xyz,
+    }
+  }
+}
+
+/**
+ * A dimension of an entry point's workgroup size.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WorkgroupDim {
+  /** A fixed size. */
+  Fixed(u32),
+
+  /** The size held by a module-scope constant or override, by name. */
+  Named(String),
+}
+
+/**
+ * Types which can serve as a single dimension of a workgroup size: a `u32`
+ * or a handle to a `u32` constant or override.  Other variable bindings,
+ * such as let bindings, are not module-scope constants:
+ *
+ * ```compile_fail
+ * # use blacklight::{
+ * #   block_dims::WorkgroupSizeDim,
+ * #   handle::VariableBindingHandle,
+ * #   variable_attributes::VariableRead,
+ * # };
+ * fn dim(binding: VariableBindingHandle<'_, u32, VariableRead>)
+ *   -> impl WorkgroupSizeDim + '_
+ * {
+ *   binding
+ * }
+ * ```
+ */
+pub trait WorkgroupSizeDim {
+  fn to_workgroup_dim(&self) -> WorkgroupDim;
+}
+impl WorkgroupSizeDim for u32 {
+  fn to_workgroup_dim(&self) -> WorkgroupDim {
+    WorkgroupDim::Fixed(*self)
+  }
+}
+impl<'sh> WorkgroupSizeDim for ConstantHandle<'sh, u32> {
+  fn to_workgroup_dim(&self) -> WorkgroupDim {
+    WorkgroupDim::Named(self.name().as_str().to_string())
+  }
+}
+impl<D: WorkgroupSizeDim> WorkgroupSizeDim for &D {
+  fn to_workgroup_dim(&self) -> WorkgroupDim {
+    (*self).to_workgroup_dim()
+  }
+}
+
+/**
+ * Types which can serve as the workgroup size of an entry point taking
+ * an `ARG` id: the block dims themselves, or a tuple of dimensions.
+ */
+pub trait WorkgroupSize<ARG: EntryPointArgDataType> {
+  fn to_workgroup_dims(&self) -> Vec<WorkgroupDim>;
+}
+impl<D: WorkgroupSizeDim> WorkgroupSize<u32> for D {
+  fn to_workgroup_dims(&self) -> Vec<WorkgroupDim> {
+    vec![self.to_workgroup_dim()]
+  }
+}
+impl WorkgroupSize<[u32; 2]> for [u32; 2] {
+  fn to_workgroup_dims(&self) -> Vec<WorkgroupDim> {
+    self.iter().map(WorkgroupSizeDim::to_workgroup_dim).collect()
+  }
+}
+impl<D0, D1> WorkgroupSize<[u32; 2]> for (D0, D1)
+  where D0: WorkgroupSizeDim, D1: WorkgroupSizeDim
+{
+  fn to_workgroup_dims(&self) -> Vec<WorkgroupDim> {
+    vec![self.0.to_workgroup_dim(), self.1.to_workgroup_dim()]
+  }
+}
+impl WorkgroupSize<[u32; 3]> for [u32; 3] {
+  fn to_workgroup_dims(&self) -> Vec<WorkgroupDim> {
+    self.iter().map(WorkgroupSizeDim::to_workgroup_dim).collect()
+  }
+}
+impl<D0, D1, D2> WorkgroupSize<[u32; 3]> for (D0, D1, D2)
+  where D0: WorkgroupSizeDim, D1: WorkgroupSizeDim, D2: WorkgroupSizeDim
+{
+  fn to_workgroup_dims(&self) -> Vec<WorkgroupDim> {
+    vec![
+      self.0.to_workgroup_dim(),
+      self.1.to_workgroup_dim(),
+      self.2.to_workgroup_dim(),
+    ]
+  }
+}
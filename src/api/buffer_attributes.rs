@@ -34,12 +34,33 @@ impl BufferDispositionRepr {
       BufferDispositionRepr::ReadWrite => "read_write",
     }
   }
+
+  /**
+   * Get the wgsl access mode for a storage buffer with this disposition.
+   * WGSL has no write-only storage buffers, so writes need `read_write`.
+   */
+  pub fn wgsl_access_mode(&self) -> &'static str {
+    match self {
+      BufferDispositionRepr::Read => "read",
+      BufferDispositionRepr::Write => "read_write",
+      BufferDispositionRepr::ReadWrite => "read_write",
+    }
+  }
+
+  /** Check whether a buffer with this disposition is bound read-only. */
+  pub fn is_read_only(&self) -> bool {
+    match self {
+      BufferDispositionRepr::Read => true,
+      BufferDispositionRepr::Write => false,
+      BufferDispositionRepr::ReadWrite => false,
+    }
+  }
 }
 
 /**
  * Buffer memory space.
  */
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BufferMemorySpaceRepr { Storage, Uniform }
 impl BufferMemorySpaceRepr {
   /** Get the string representation of the buffer memory space. */
@@ -0,0 +1,32 @@
+use std::marker::PhantomData;
+use crate::{
+  api::data_type::HostShareableDataType,
+  model::IdentifierModel,
+};
+
+/**
+ * Represents a typed buffer binding within a shader, used to bind a buffer
+ * of the same data type to the compiled shader.
+ *
+ * Obtained from the binding's handle with `BufferBindingHandle::binding`.
+ */
+pub struct BufferBinding<DT: HostShareableDataType> {
+  name: IdentifierModel,
+  _phantom: PhantomData<DT>,
+}
+impl<DT: HostShareableDataType> BufferBinding<DT> {
+  /** Create a new buffer binding. */
+  pub(crate) fn new(name: IdentifierModel) -> Self {
+    BufferBinding { name, _phantom: PhantomData }
+  }
+
+  /** Get the name of the buffer binding. */
+  pub fn name(&self) -> &str {
+    self.name.as_str()
+  }
+}
+impl<DT: HostShareableDataType> Clone for BufferBinding<DT> {
+  fn clone(&self) -> Self {
+    BufferBinding { name: self.name.clone(), _phantom: PhantomData }
+  }
+}
@@ -1,29 +1,125 @@
 use std::marker::PhantomData;
 use crate::{
   api::{
-    data_type::{ ExprDataType, ProcResultType },
-    handle::{ ExprHandle, LvalueHandle, VariableBindingHandle },
+    builder::SwitchBuilder,
+    data_type::{
+      ArgTupleDataType,
+      ArgTupleHandleMap,
+      ExprDataType,
+      ExprScalarIntegralDataType,
+      ProcResultType,
+    },
+    handle::{
+      ExprHandle,
+      FunctionHandle,
+      LvalueHandle,
+      VariableBindingHandle,
+    },
     variable_attributes::{
       VariableMutability,
       VariableRead,
       VariableReadWrite,
     },
+    BlacklightError,
   },
-  data_type::HostShareableDataType,
+  data_type::ExprLoadableDataType,
   model::{
     AssignStmtModel,
+    CallStmtModel,
     CodeBlockModel,
+    ContinuingModel,
     ExprStmtModel,
+    ExpressionModel,
+    ForStmtModel,
+    IdentifierExprModel,
     IdentifierModel,
     IfElseStmtModel,
+    LoopStmtModel,
+    LvalueModel,
+    PhonyAssignStmtModel,
     ReturnStmtModel,
     StatementModel,
+    SwitchStmtModel,
     VarDeclStmtModel,
     VariableBindingDisposition,
     VariableBindingModel,
+    WhileStmtModel,
   },
 };
 
+/**
+ * Where a code block sits relative to enclosing loops, which determines
+ * the control flow statements allowed within it.
+ */
+#[derive(Clone, Copy, Debug, Default)]
+struct BlockScope {
+  // Within a loop body, where break and continue are allowed.
+  in_loop: bool,
+
+  // Within a continuing block, where break, continue and return are not.
+  in_continuing: bool,
+
+  // Within a switch clause, where break leaves the switch.
+  in_switch: bool,
+
+  // The root block of a `loop` statement's body.
+  is_loop_body: bool,
+
+  // The root block of a continuing block.
+  is_continuing: bool,
+}
+impl BlockScope {
+  /** The scope of a nested block such as an if-statement's block. */
+  fn nested(self) -> Self {
+    BlockScope {
+      is_loop_body: false,
+      is_continuing: false,
+      ..self
+    }
+  }
+
+  /** The scope of a loop body. */
+  fn loop_body(is_loop_body: bool) -> Self {
+    BlockScope {
+      in_loop: true,
+      in_continuing: false,
+      in_switch: false,
+      is_loop_body,
+      is_continuing: false,
+    }
+  }
+
+  /** The scope of a continuing block. */
+  fn continuing() -> Self {
+    BlockScope {
+      in_loop: true,
+      in_continuing: true,
+      in_switch: false,
+      is_loop_body: false,
+      is_continuing: true,
+    }
+  }
+
+  /** The scope of a switch clause. */
+  fn switch_clause(self) -> Self {
+    BlockScope {
+      in_switch: true,
+      ..self.nested()
+    }
+  }
+}
+
+/**
+ * The scope of a for statement's loop variable, passed along with it to
+ * the condition and update closures of `add_for_statement`.
+ *
+ * Ties the loop variable's lifetime `'lp` within the enclosing block's
+ * lifetime `'cb`, so that expressions from the enclosing block may be
+ * combined with the loop variable, but the loop variable may not escape.
+ */
+pub struct LoopScope<'lp, 'cb: 'lp> {
+  _phantom: PhantomData<&'lp &'cb ()>,
+}
 
 /**
  * A builder helper for defining shader entrypoints.
@@ -32,6 +128,10 @@ pub struct CodeBlockBuilder<'cb, 'sh: 'cb, ReturnT>
   where ReturnT: ProcResultType
 {
   statements: Vec<StatementModel>,
+  errors: Vec<BlacklightError>,
+  scope: BlockScope,
+  continuing: Option<ContinuingModel>,
+  break_if: Option<Box<ExpressionModel>>,
   _phantom: PhantomData<&'cb &'sh ReturnT>,
 }
 impl<'cb, 'sh: 'cb, ReturnT> CodeBlockBuilder<'cb, 'sh, ReturnT>
@@ -39,23 +139,77 @@ impl<'cb, 'sh: 'cb, ReturnT> CodeBlockBuilder<'cb, 'sh, ReturnT>
 {
   /** Create a new shader entrypoint builder for the given shader builder. */
   pub(crate) fn new() -> Self {
+    Self::new_in_scope(BlockScope::default())
+  }
+
+  fn new_in_scope(scope: BlockScope) -> Self {
     CodeBlockBuilder {
       statements: Vec::new(),
+      errors: Vec::new(),
+      scope,
+      continuing: None,
+      break_if: None,
       _phantom: PhantomData,
     }
   }
 
+  /**
+   * Build a nested code block.  Nested blocks may use handles from this
+   * block, as their builders are bounded by this block's lifetime.
+   */
   fn build_sub_code_block<B>(&mut self, builder_func: B) -> CodeBlockModel
-    where B: FnOnce(&mut CodeBlockBuilder<'sh, 'sh, ReturnT>),
+    where B: FnOnce(&mut CodeBlockBuilder<'cb, 'cb, ReturnT>),
+  {
+    self.build_sub_code_block_in(self.scope.nested(), builder_func).0
+  }
+
+  /**
+   * Build a nested code block in the given scope, also returning the
+   * continuing block defined within it, if any.
+   */
+  fn build_sub_code_block_in<B>(&mut self, scope: BlockScope, builder_func: B)
+    -> (CodeBlockModel, Option<ContinuingModel>)
+    where B: FnOnce(&mut CodeBlockBuilder<'cb, 'cb, ReturnT>),
   {
-    let mut code_block_builder = CodeBlockBuilder::new();
+    let mut code_block_builder = CodeBlockBuilder::new_in_scope(scope);
     builder_func(&mut code_block_builder);
-    code_block_builder.build()
+    let continuing = code_block_builder.continuing.take();
+    let (code_block_model, errors) = code_block_builder.build();
+    self.errors.extend(errors);
+    (code_block_model, continuing)
   }
 
-  /** Build an entrypoint from the definition provided. */
-  pub(crate) fn build(self) -> CodeBlockModel {
-    CodeBlockModel::new(self.statements)
+  /** Build the body of a switch clause. */
+  pub(super) fn build_switch_clause<B>(&mut self, builder_func: B)
+    -> CodeBlockModel
+    where B: FnOnce(&mut CodeBlockBuilder<'cb, 'cb, ReturnT>),
+  {
+    self.build_sub_code_block_in(self.scope.switch_clause(), builder_func).0
+  }
+
+  /** Report a statement added where wgsl does not allow it. */
+  fn report_misplaced(&mut self, statement: &str, reason: &str) {
+    self.errors.push(BlacklightError::MisplacedStatement {
+      statement: statement.to_string(),
+      reason: reason.to_string(),
+    });
+  }
+
+  /**
+   * Build a code block from the definition provided, along with the errors
+   * reported while defining it.
+   */
+  pub(crate) fn build(self) -> (CodeBlockModel, Vec<BlacklightError>) {
+    (CodeBlockModel::new(self.statements), self.errors)
+  }
+
+  /**
+   * Report an error, such as one returned by a `try_*` method.
+   *
+   * Reported errors are returned from `Project::define_shader`.
+   */
+  pub fn report_error(&mut self, error: BlacklightError) {
+    self.errors.push(error);
   }
 
   /**
@@ -72,6 +226,34 @@ impl<'cb, 'sh: 'cb, ReturnT> CodeBlockBuilder<'cb, 'sh, ReturnT>
     self.statements.push(StatementModel::Expr(expr_stmt_model));
   }
 
+  /**
+   * Add a call statement, calling a function returning void.
+   * Functions returning a value may have their result discarded with
+   * `add_phony_assignment_statement`.
+   */
+  pub fn add_call_statement<ARG>(&mut self,
+    function: &FunctionHandle<'cb, ARG, ()>,
+    args: ARG::HandleTuple,
+  )
+    where ARG: ArgTupleDataType + ArgTupleHandleMap<'cb>,
+  {
+    let call_stmt_model = CallStmtModel::new(function.call_model(args));
+    self.statements.push(StatementModel::Call(call_stmt_model));
+  }
+
+  /**
+   * Add a phony assignment statement (`_ = expr;`), evaluating an
+   * expression and discarding its value.
+   */
+  pub fn add_phony_assignment_statement<DT>(&mut self,
+    expr: ExprHandle<'cb, DT>,
+  )
+    where DT: ExprDataType,
+  {
+    let phony_assign_stmt_model = PhonyAssignStmtModel::new(expr.model);
+    self.statements.push(StatementModel::PhonyAssign(phony_assign_stmt_model));
+  }
+
   /**
    * Add a return statement.
    * Only available in codeblocks in functions with a non-void return.
@@ -84,6 +266,9 @@ impl<'cb, 'sh: 'cb, ReturnT> CodeBlockBuilder<'cb, 'sh, ReturnT>
   pub fn add_return_statement(&mut self, expr: ExprHandle<'cb, ReturnT>)
     where ReturnT: ExprDataType
   {
+    if self.scope.in_continuing {
+      self.report_misplaced("return", "within a continuing block");
+    }
     let return_stmt_model = ReturnStmtModel::new(Some(expr.model));
     self.statements.push(StatementModel::Return(return_stmt_model));
   }
@@ -105,6 +290,12 @@ impl<'cb, 'sh: 'cb, ReturnT> CodeBlockBuilder<'cb, 'sh, ReturnT>
     where DT: ExprDataType,
           MUT: VariableMutability
   {
+    if DT::repr().contains_atomic() {
+      self.errors.push(BlacklightError::MisplacedAtomic {
+        name: name.to_string(),
+        memory_space: "function".to_string(),
+      });
+    }
     let identifier_model = IdentifierModel::new(name);
     let var_binding_model = VariableBindingModel::new(
       identifier_model.clone(),
@@ -136,20 +327,26 @@ impl<'cb, 'sh: 'cb, ReturnT> CodeBlockBuilder<'cb, 'sh, ReturnT>
     name: &str,
     expr: ExprHandle<'cb, DT>,
   ) -> VariableBindingHandle<'cb, DT, VariableRead>
-    where DT: HostShareableDataType
+    where DT: ExprDataType
   {
     self.add_decl_statement(VariableBindingDisposition::Let, name, expr)
   }
 
   /**
    * Add a new assignment statement.
+   *
+   * Atomics are assigned with the atomic builtins instead.  Assigning a
+   * struct or array holding atomics is reported as an error.
    */
   pub fn add_assignment_statement<DT>(&mut self,
     lvalue: &LvalueHandle<'cb, DT>,
     expr: ExprHandle<'cb, DT>,
   )
-    where DT: HostShareableDataType
+    where DT: ExprLoadableDataType
   {
+    if DT::repr().contains_atomic() {
+      self.report_misplaced("assignment", "on values holding atomics");
+    }
     let assign_stmt_model = AssignStmtModel::new(
       lvalue.model().clone(),
       expr.model
@@ -165,8 +362,8 @@ impl<'cb, 'sh: 'cb, ReturnT> CodeBlockBuilder<'cb, 'sh, ReturnT>
     if_builder: IfB,
     else_builder: ElseB,
   ) where
-    IfB: for <'if_cb> FnOnce(&mut CodeBlockBuilder<'if_cb, 'sh, ReturnT>),
-    ElseB: for <'else_cb> FnOnce(&mut CodeBlockBuilder<'else_cb, 'sh, ReturnT>),
+    IfB: for <'if_cb> FnOnce(&mut CodeBlockBuilder<'if_cb, 'cb, ReturnT>),
+    ElseB: for <'else_cb> FnOnce(&mut CodeBlockBuilder<'else_cb, 'cb, ReturnT>),
   {
     let if_block = self.build_sub_code_block(if_builder);
     let else_block = self.build_sub_code_block(else_builder);
@@ -182,13 +379,222 @@ impl<'cb, 'sh: 'cb, ReturnT> CodeBlockBuilder<'cb, 'sh, ReturnT>
     condition: ExprHandle<'cb, bool>,
     if_builder: IfB,
   ) where
-    IfB: for <'if_cb> FnOnce(&mut CodeBlockBuilder<'if_cb, 'sh, ReturnT>)
+    IfB: for <'if_cb> FnOnce(&mut CodeBlockBuilder<'if_cb, 'cb, ReturnT>)
   {
     let if_block = self.build_sub_code_block(if_builder);
 
     let if_else_stmt = IfElseStmtModel::new(condition.model, if_block, None);
     self.statements.push(StatementModel::IfElse(if_else_stmt));
   }
+
+  /**
+   * Add a loop statement, which runs its body until broken out of.
+   *
+   * The body may end each iteration with a continuing block, see
+   * `add_continuing_block`.
+   */
+  pub fn add_loop_statement<BodyB>(&mut self, body_builder: BodyB)
+    where BodyB: for <'lp> FnOnce(&mut CodeBlockBuilder<'lp, 'cb, ReturnT>)
+  {
+    let (body, continuing) =
+      self.build_sub_code_block_in(BlockScope::loop_body(true), body_builder);
+    let loop_stmt = LoopStmtModel::new(body, continuing);
+    self.statements.push(StatementModel::Loop(loop_stmt));
+  }
+
+  /**
+   * Add a while statement, which runs its body while the condition holds.
+   */
+  pub fn add_while_statement<BodyB>(&mut self,
+    condition: ExprHandle<'cb, bool>,
+    body_builder: BodyB,
+  ) where
+    BodyB: for <'lp> FnOnce(&mut CodeBlockBuilder<'lp, 'cb, ReturnT>)
+  {
+    let (body, _) =
+      self.build_sub_code_block_in(BlockScope::loop_body(false), body_builder);
+    let while_stmt = WhileStmtModel::new(condition.model, body);
+    self.statements.push(StatementModel::While(while_stmt));
+  }
+
+  /**
+   * Add a for statement, declaring a loop variable initialized to `init`.
+   *
+   * Before each iteration the loop variable is checked with the condition
+   * produced by `condition_fn`, and after each iteration it is assigned the
+   * value produced by `update_fn`.  The loop variable is also passed to the
+   * body, and is only in scope within the for statement:
+   *
+   * ```compile_fail
+   * # use blacklight::{ builder::{ literal, CodeBlockBuilder }, handle::ExprHandle };
+   * fn escape<'cb, 'sh>(cbb: &mut CodeBlockBuilder<'cb, 'sh, ()>)
+   *   -> Option<ExprHandle<'cb, u32>>
+   * {
+   *   let mut escaped = None;
+   *   cbb.add_for_statement("i", literal(0u32),
+   *     |_, i| {
+   *       escaped = Some(i.clone());
+   *       i.lt(&literal(4u32))
+   *     },
+   *     |_, i| i + literal(1u32),
+   *     |_, _| {},
+   *   );
+   *   escaped
+   * }
+   * ```
+   */
+  pub fn add_for_statement<DT, CondF, UpdF, BodyB>(&mut self,
+    name: &str,
+    init: ExprHandle<'cb, DT>,
+    condition_fn: CondF,
+    update_fn: UpdF,
+    body_builder: BodyB,
+  ) where
+    DT: ExprLoadableDataType,
+    CondF: for <'lp> FnOnce(LoopScope<'lp, 'cb>, ExprHandle<'lp, DT>)
+      -> ExprHandle<'lp, bool>,
+    UpdF: for <'lp> FnOnce(LoopScope<'lp, 'cb>, ExprHandle<'lp, DT>)
+      -> ExprHandle<'lp, DT>,
+    BodyB: for <'lp> FnOnce(&mut CodeBlockBuilder<'lp, 'cb, ReturnT>, ExprHandle<'lp, DT>)
+  {
+    let identifier_model = IdentifierModel::new(name);
+    let loop_var = || {
+      let ident_expr_model =
+        IdentifierExprModel::new(identifier_model.clone(), DT::repr());
+      ExprHandle::new(Box::new(ExpressionModel::Identifier(ident_expr_model)))
+    };
+    let init_model = VariableBindingModel::new(
+      identifier_model.clone(),
+      VariableBindingDisposition::Var,
+      DT::repr(),
+      Some(init.model),
+    );
+    let condition = condition_fn(LoopScope { _phantom: PhantomData }, loop_var());
+    let update = AssignStmtModel::new(
+      LvalueModel::new_variable(identifier_model.clone(), DT::repr()),
+      update_fn(LoopScope { _phantom: PhantomData }, loop_var()).model,
+    );
+    let body_var = loop_var();
+    let (body, _) = self.build_sub_code_block_in(
+      BlockScope::loop_body(false),
+      move |builder| body_builder(builder, body_var)
+    );
+    let for_stmt = ForStmtModel::new(init_model, condition.model, update, body);
+    self.statements.push(StatementModel::For(for_stmt));
+  }
+
+  /**
+   * Define the continuing block of a loop statement, run at the end of
+   * each iteration of the loop, including iterations ended by `continue`.
+   *
+   * Only available directly within the body of `add_loop_statement`,
+   * and at most once per loop.  The continuing block may end the loop with
+   * `add_break_if_statement`.
+   */
+  pub fn add_continuing_block<ContB>(&mut self, continuing_builder: ContB)
+    where ContB: for <'ct> FnOnce(&mut CodeBlockBuilder<'ct, 'cb, ReturnT>)
+  {
+    if !self.scope.is_loop_body {
+      self.report_misplaced("continuing", "outside of a loop body");
+      return;
+    }
+    if self.continuing.is_some() {
+      self.report_misplaced("continuing", "after another continuing block");
+      return;
+    }
+    let mut builder = CodeBlockBuilder::new_in_scope(BlockScope::continuing());
+    continuing_builder(&mut builder);
+    let break_if = builder.break_if.take();
+    let (block, errors) = builder.build();
+    self.errors.extend(errors);
+    self.continuing = Some(ContinuingModel::new(block, break_if));
+  }
+
+  /**
+   * Add a switch statement, selecting a clause by the selector's value.
+   *
+   * Clauses are defined on the `SwitchBuilder` passed to `switch_builder`.
+   * A default clause is required, and each case value may only select one
+   * clause.
+   */
+  pub fn add_switch_statement<DT, SwB>(&mut self,
+    selector: ExprHandle<'cb, DT>,
+    switch_builder: SwB,
+  ) where
+    DT: ExprScalarIntegralDataType,
+    SwB: FnOnce(&mut SwitchBuilder<'_, 'cb, 'sh, DT, ReturnT>)
+  {
+    let mut builder = SwitchBuilder::new(self);
+    switch_builder(&mut builder);
+    let clauses = builder.build();
+    let switch_stmt = SwitchStmtModel::new(selector.model, clauses);
+    self.statements.push(StatementModel::Switch(switch_stmt));
+  }
+
+  /**
+   * Add a break statement, leaving the innermost enclosing loop or
+   * switch statement.
+   */
+  pub fn add_break_statement(&mut self) {
+    if self.scope.in_switch {
+      // Leaves the switch, which is allowed anywhere.
+    } else if !self.scope.in_loop {
+      self.report_misplaced("break", "outside of a loop or switch");
+    } else if self.scope.in_continuing {
+      self.report_misplaced("break", "within a continuing block");
+    }
+    self.statements.push(StatementModel::Break);
+  }
+
+  /** Add a continue statement, skipping to the next loop iteration. */
+  pub fn add_continue_statement(&mut self) {
+    if !self.scope.in_loop {
+      self.report_misplaced("continue", "outside of a loop");
+    } else if self.scope.in_continuing {
+      self.report_misplaced("continue", "within a continuing block");
+    }
+    self.statements.push(StatementModel::Continue);
+  }
+
+  /**
+   * Add a `workgroupBarrier()` statement, waiting for all invocations of
+   * the workgroup and making their workgroup variable writes visible.
+   *
+   * Barriers must be reached in uniform control flow, which is checked
+   * when the shader is built.
+   */
+  pub fn add_workgroup_barrier_statement(&mut self) {
+    self.statements.push(StatementModel::WorkgroupBarrier);
+  }
+
+  /**
+   * Add a `storageBarrier()` statement, waiting for all invocations of the
+   * workgroup and making their storage buffer writes visible.
+   *
+   * Barriers must be reached in uniform control flow, which is checked
+   * when the shader is built.
+   */
+  pub fn add_storage_barrier_statement(&mut self) {
+    self.statements.push(StatementModel::StorageBarrier);
+  }
+
+  /**
+   * End a continuing block with a condition on which to leave the loop.
+   *
+   * Only available directly within a continuing block, and at most once.
+   * The condition is checked after all other statements of the block.
+   */
+  pub fn add_break_if_statement(&mut self, condition: ExprHandle<'cb, bool>) {
+    if !self.scope.is_continuing {
+      self.report_misplaced("break if", "outside of a continuing block");
+      return;
+    }
+    if self.break_if.is_some() {
+      self.report_misplaced("break if", "after another break if");
+      return;
+    }
+    self.break_if = Some(condition.model);
+  }
 }
 
 /**
@@ -197,6 +603,9 @@ impl<'cb, 'sh: 'cb, ReturnT> CodeBlockBuilder<'cb, 'sh, ReturnT>
 impl<'cb, 'sh: 'cb> CodeBlockBuilder<'cb, 'sh, ()> {
   /** Add a new bare return statement. */
   pub fn add_bare_return_statement(&mut self) {
+    if self.scope.in_continuing {
+      self.report_misplaced("return", "within a continuing block");
+    }
     let return_stmt_model = ReturnStmtModel::new(None);
     self.statements.push(StatementModel::Return(return_stmt_model));
   }
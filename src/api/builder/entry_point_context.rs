@@ -0,0 +1,58 @@
+use std::marker::PhantomData;
+use crate::{
+  api::{
+    data_type::{ EntryPointArgDataType, ExprDataType },
+    handle::ExprHandle,
+  },
+  model::{ ComputeBuiltin, ExpressionModel, IdentifierExprModel, IdentifierModel },
+};
+
+/**
+ * The builtin values of a compute entry point, passed to its builder.
+ *
+ * The ids are truncated to the dimensions `ARG` of the entry point.  Only
+ * the builtins read by the entry point are declared as its parameters.
+ */
+pub struct EntryPointContext<'sh, ARG: EntryPointArgDataType> {
+  _phantom: PhantomData<&'sh ARG>,
+}
+impl<'sh, ARG: EntryPointArgDataType> EntryPointContext<'sh, ARG> {
+  /** Create a new entry point context. */
+  pub(crate) fn new() -> Self {
+    EntryPointContext { _phantom: PhantomData }
+  }
+
+  /** Get the id of the invocation within the dispatch. */
+  pub fn global_invocation_id(&self) -> ExprHandle<'sh, ARG> {
+    builtin_expr(ComputeBuiltin::GlobalInvocationId)
+  }
+
+  /** Get the id of the invocation within its workgroup. */
+  pub fn local_invocation_id(&self) -> ExprHandle<'sh, ARG> {
+    builtin_expr(ComputeBuiltin::LocalInvocationId)
+  }
+
+  /** Get the linear index of the invocation within its workgroup. */
+  pub fn local_invocation_index(&self) -> ExprHandle<'sh, u32> {
+    builtin_expr(ComputeBuiltin::LocalInvocationIndex)
+  }
+
+  /** Get the id of the invocation's workgroup within the dispatch. */
+  pub fn workgroup_id(&self) -> ExprHandle<'sh, ARG> {
+    builtin_expr(ComputeBuiltin::WorkgroupId)
+  }
+
+  /** Get the number of workgroups dispatched. */
+  pub fn num_workgroups(&self) -> ExprHandle<'sh, ARG> {
+    builtin_expr(ComputeBuiltin::NumWorkgroups)
+  }
+}
+
+// Build an expression reading the binding of a builtin.
+fn builtin_expr<'sh, DT: ExprDataType>(builtin: ComputeBuiltin)
+  -> ExprHandle<'sh, DT>
+{
+  let ident_model = IdentifierModel::new(builtin.name());
+  let ident_expr_model = IdentifierExprModel::new(ident_model, DT::repr());
+  ExprHandle::new(Box::new(ExpressionModel::Identifier(ident_expr_model)))
+}
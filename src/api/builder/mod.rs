@@ -1,19 +1,32 @@
 mod code_block_builder;
+mod entry_point_context;
+mod override_values_builder;
+mod shader_bindings_builder;
 mod shader_builder;
+mod switch_builder;
 
 use std::vec;
 
 pub use self::{
-  code_block_builder::CodeBlockBuilder,
+  code_block_builder::{ CodeBlockBuilder, LoopScope },
+  entry_point_context::EntryPointContext,
+  override_values_builder::OverrideValuesBuilder,
+  shader_bindings_builder::ShaderBindingsBuilder,
   shader_builder::ShaderBuilder,
+  switch_builder::SwitchBuilder,
 };
 
 use crate::{
   api::{
-    data_type::{ ExprVectorNumericDataType, LiteralDataType },
+    data_type::{ ExprDataType, ExprVectorNumericDataType, LiteralDataType },
     handle::ExprHandle,
   },
-  model::{ ExpressionModel, LiteralExprModel, VecConstructorExprModel },
+  model::{
+    ExpressionModel,
+    LiteralExprModel,
+    VecConstructorExprModel,
+    ZeroValueExprModel,
+  },
 };
 
 /**
@@ -27,6 +40,36 @@ pub fn literal<'cb, DT>(value: DT) -> ExprHandle<'cb, DT>
   ExprHandle::new(Box::new(ExpressionModel::Literal(literal_expr_model)))
 }
 
+/**
+ * Create a new zero value expression, e.g. `Particle()`, holding a value
+ * of the type with all of its components zero.
+ */
+pub fn zero_value<'cb, DT>() -> ExprHandle<'cb, DT>
+  where DT: ExprDataType
+{
+  let zero_value_expr_model = ZeroValueExprModel::new(DT::repr());
+  ExprHandle::new(Box::new(ExpressionModel::ZeroValue(zero_value_expr_model)))
+}
+
+/**
+ * Values which can initialize a constant or private variable: literal
+ * values, and expressions built from literals, zero values, constants and
+ * const-evaluable builtins.
+ */
+pub trait ConstInitializer<'sh, DT: ExprDataType> {
+  fn make_handle(self) -> ExprHandle<'sh, DT>;
+}
+impl<'sh, DT: LiteralDataType> ConstInitializer<'sh, DT> for DT {
+  fn make_handle(self) -> ExprHandle<'sh, DT> {
+    literal(self)
+  }
+}
+impl<'sh, DT: ExprDataType> ConstInitializer<'sh, DT> for ExprHandle<'sh, DT> {
+  fn make_handle(self) -> ExprHandle<'sh, DT> {
+    self
+  }
+}
+
 pub trait VecTransformTo<'cb, DT: ExprVectorNumericDataType> {
   fn make_handle(self) -> ExprHandle<'cb, DT>;
 }
@@ -42,6 +85,7 @@ pub fn mkvec<'cb, DT, DT2>(v: DT2) -> ExprHandle<'cb, DT>
   v.make_handle()
 }
 
+#[allow(clippy::vec_box)]
 fn make_handle_impl<'cb, DT>(
   model_vec: Vec<Box<ExpressionModel>>,
 ) -> ExprHandle<'cb, DT>
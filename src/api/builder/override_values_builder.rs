@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use crate::{
+  api::{
+    data_type::OverrideDataType,
+    BlacklightError,
+  },
+  model::ShaderModel,
+};
+
+/**
+ * A builder helper for setting the values of a shader's pipeline
+ * overrides when it is compiled.
+ */
+pub struct OverrideValuesBuilder<'a> {
+  model: &'a ShaderModel,
+  values: HashMap<String, f64>,
+  errors: Vec<BlacklightError>,
+}
+impl<'a> OverrideValuesBuilder<'a> {
+  /** Create a new override values builder for the given shader. */
+  pub(crate) fn new(model: &'a ShaderModel) -> Self {
+    OverrideValuesBuilder {
+      model,
+      values: HashMap::new(),
+      errors: Vec::new(),
+    }
+  }
+
+  /**
+   * Set the value of the override with the given name.
+   *
+   * Errors in setting values are reported from
+   * `Shader::compile_with_overrides`.  See `try_set` to handle them
+   * directly.
+   */
+  pub fn set<DT>(&mut self, name: &str, value: DT)
+    where DT: OverrideDataType
+  {
+    if let Err(error) = self.try_set(name, value) {
+      self.errors.push(error);
+    }
+  }
+
+  /**
+   * Set the value of the override with the given name, returning any
+   * error.
+   *
+   * Fails if there is no such override, if it was already set, or if it
+   * was declared with a different data type.
+   */
+  pub fn try_set<DT>(&mut self, name: &str, value: DT)
+    -> Result<(), BlacklightError>
+    where DT: OverrideDataType
+  {
+    let maybe_override = self.model.overrides().iter()
+      .find(|override_model| override_model.name().as_str() == name);
+    let override_model = match maybe_override {
+      Some(override_model) => override_model,
+      None => return Err(BlacklightError::UnknownOverride {
+        name: name.to_string(),
+      }),
+    };
+    let data_type = override_model.binding().data_type();
+    if data_type != &DT::repr() {
+      return Err(BlacklightError::OverrideTypeMismatch {
+        name: name.to_string(),
+        override_type: data_type.wgsl_source(),
+        value_type: DT::repr().wgsl_source(),
+      });
+    }
+    if self.values.contains_key(name) {
+      return Err(BlacklightError::OverrideAlreadySet {
+        name: name.to_string(),
+      });
+    }
+    self.values.insert(name.to_string(), value.to_pipeline_constant());
+    Ok(())
+  }
+
+  /**
+   * Get the values set, by override name.
+   *
+   * Fails with the errors reported while setting values, and an error for
+   * each override without a default value left unset.
+   */
+  pub(crate) fn build(mut self) -> Result<HashMap<String, f64>, BlacklightError> {
+    for override_model in self.model.overrides() {
+      let name = override_model.name().as_str();
+      if !override_model.has_default() && !self.values.contains_key(name) {
+        self.errors.push(BlacklightError::UnsetOverride {
+          name: name.to_string(),
+        });
+      }
+    }
+    match BlacklightError::from_errors(self.errors) {
+      Some(error) => Err(error),
+      None => Ok(self.values),
+    }
+  }
+}
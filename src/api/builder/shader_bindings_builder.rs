@@ -0,0 +1,162 @@
+use crate::{
+  api::{
+    data_type::HostShareableDataType,
+    BlacklightError,
+    BufferBinding,
+    GpuBuffer,
+    ShaderBindings,
+  },
+  model::{ BufferBindingModel, ShaderModel, UNIFORMS_GROUP, UNIFORMS_INDEX },
+  pipeline::UniformsLayout,
+};
+
+/**
+ * A builder helper for binding buffers to a compiled shader's buffer bindings.
+ */
+pub struct ShaderBindingsBuilder<'a> {
+  model: &'a ShaderModel,
+  bound_buffers: Vec<(&'a BufferBindingModel, &'a wgpu::Buffer, u32)>,
+  errors: Vec<BlacklightError>,
+}
+impl<'a> ShaderBindingsBuilder<'a> {
+  /** Create a new bindings builder for the given shader. */
+  pub(crate) fn new(model: &'a ShaderModel) -> Self {
+    ShaderBindingsBuilder {
+      model,
+      bound_buffers: Vec::new(),
+      errors: Vec::new(),
+    }
+  }
+
+  /**
+   * Bind a buffer to a buffer binding of the same data type.
+   *
+   * Errors in binding are reported from `CompiledShader::define_bindings`.
+   * See `try_bind_buffer` to handle them directly.
+   *
+   * A buffer of a different data type than the binding is rejected when
+   * compiling:
+   *
+   * ```compile_fail
+   * # use blacklight::{ BufferBinding, GpuBuffer, builder::ShaderBindingsBuilder };
+   * fn bind<'a>(
+   *   bb: &mut ShaderBindingsBuilder<'a>,
+   *   binding: &BufferBinding<f32>,
+   *   buffer: &'a GpuBuffer<'_, u32>,
+   * ) {
+   *   bb.bind_buffer(binding, buffer);
+   * }
+   * ```
+   */
+  pub fn bind_buffer<DT>(&mut self,
+    binding: &BufferBinding<DT>,
+    buffer: &'a GpuBuffer<'_, DT>,
+  )
+    where DT: HostShareableDataType
+  {
+    if let Err(error) = self.try_bind_buffer(binding, buffer) {
+      self.errors.push(error);
+    }
+  }
+
+  /**
+   * Bind a buffer to a buffer binding of the same data type, returning
+   * any error.
+   *
+   * Fails if the binding belongs to another shader, if it was already
+   * bound, or if the buffer holds more elements than a `u32` can count.
+   */
+  pub fn try_bind_buffer<DT>(&mut self,
+    binding: &BufferBinding<DT>,
+    buffer: &'a GpuBuffer<'_, DT>,
+  ) -> Result<(), BlacklightError>
+    where DT: HostShareableDataType
+  {
+    // Bindings of another shader may share a name with a binding of this
+    // one, so the data type must match as well.
+    let maybe_binding = self.model.buffer_bindings().iter()
+      .find(|model| {
+        model.name().as_str() == binding.name()
+          && model.data_type() == &DT::repr()
+      });
+    let binding_model = match maybe_binding {
+      Some(binding_model) => binding_model,
+      None => return Err(BlacklightError::UnknownBufferBinding {
+        binding: binding.name().to_string(),
+      }),
+    };
+    let already_bound = self.bound_buffers.iter()
+      .any(|(bound, _, _)| bound.name() == binding_model.name());
+    if already_bound {
+      return Err(BlacklightError::BufferBindingAlreadyBound {
+        binding: binding.name().to_string(),
+      });
+    }
+    let len = match u32::try_from(buffer.len()) {
+      Ok(len) => len,
+      Err(_) => return Err(BlacklightError::BufferBindingTooLong {
+        binding: binding.name().to_string(),
+        len: buffer.len(),
+      }),
+    };
+    self.bound_buffers.push((binding_model, buffer.buffer(), len));
+    Ok(())
+  }
+
+  /**
+   * Create the bind groups from the bound buffers, along with a uniforms
+   * buffer holding their lengths.
+   *
+   * Fails with the errors reported while binding, and an error for each
+   * of the shader's buffer bindings left unbound.
+   */
+  pub(crate) fn build(mut self,
+    device: &wgpu::Device,
+    bind_group_layouts: &[wgpu::BindGroupLayout],
+    uniforms_layout: &UniformsLayout,
+  ) -> Result<ShaderBindings, BlacklightError> {
+    // Collect the buffer lengths in binding order, matching the fields
+    // of the generated lengths struct.
+    let mut lengths = Vec::new();
+    for binding in self.model.buffer_bindings() {
+      let maybe_bound = self.bound_buffers.iter()
+        .find(|(bound, _, _)| bound.name() == binding.name());
+      match maybe_bound {
+        Some((_, _, length)) => lengths.push(*length),
+        None => self.errors.push(BlacklightError::UnboundBufferBinding {
+          binding: binding.name().as_str().to_string(),
+        }),
+      }
+    }
+    if let Some(error) = BlacklightError::from_errors(self.errors) {
+      return Err(error);
+    }
+
+    let uniforms_buffer = uniforms_layout.create_buffer(device, &lengths);
+
+    let mut group_entries: Vec<Vec<wgpu::BindGroupEntry>> =
+      bind_group_layouts.iter().map(|_| Vec::new()).collect();
+    group_entries[UNIFORMS_GROUP as usize].push(wgpu::BindGroupEntry {
+      binding: UNIFORMS_INDEX,
+      resource: uniforms_buffer.as_entire_binding(),
+    });
+    for (binding, buffer, _) in &self.bound_buffers {
+      group_entries[binding.group() as usize].push(wgpu::BindGroupEntry {
+        binding: binding.index(),
+        resource: buffer.as_entire_binding(),
+      });
+    }
+
+    let bind_groups = group_entries.iter()
+      .zip(bind_group_layouts)
+      .map(|(entries, layout)| {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+          label: None,
+          layout,
+          entries,
+        })
+      })
+      .collect();
+    Ok(ShaderBindings::new(bind_groups, uniforms_buffer, lengths))
+  }
+}
@@ -4,14 +4,17 @@ use std::{
 };
 use crate::{
   api::{
+    block_dims::WorkgroupSize,
     buffer_attributes::{ BufferRead, BufferReadWrite, BufferWrite },
-    builder::CodeBlockBuilder,
+    builder::{ CodeBlockBuilder, ConstInitializer, EntryPointContext },
     data_type::{
       ArgTupleDataType,
       ArgTupleHandleMap,
+      DataTypeRepr,
       EntryPointArgDataType,
+      ExprDataType,
       HostShareableDataType,
-      LiteralDataType,
+      OverrideDataType,
       Struct,
       StructDataTypeRepr,
       StructMappedDataType,
@@ -19,11 +22,13 @@ use crate::{
     },
     handle::{
       BufferBindingHandle,
+      ConstantHandle,
       ExprHandle,
       FunctionHandle,
       VariableBindingHandle,
     },
-    variable_attributes::VariableRead,
+    variable_attributes::VariableReadWrite,
+    BlacklightError,
     EntryPoint,
     Project,
     Shader,
@@ -36,13 +41,19 @@ use crate::{
     EntryPointModel,
     ExpressionModel,
     FunctionModel,
-    IdentifierExprModel,
     IdentifierModel,
     LiteralExprModel,
+    OverrideModel,
     ShaderModel,
+    LENGTHS_STRUCT_NAME,
+    UNIFORMS_GROUP,
+    UNIFORMS_INDEX,
+    UNIFORMS_STRUCT_NAME,
+    user_uniforms_expr,
     VariableBindingModel,
     VariableBindingDisposition,
-  }
+  },
+  validator::{ check_barrier_uniformity, check_const_expr },
 };
 
 /**
@@ -55,9 +66,13 @@ pub struct ShaderBuilder<'sh, 'pr: 'sh, UDT>
   uniform_struct_data_type: StructDataTypeRepr,
   functions: Vec<FunctionModel>,
   buffer_bindings: Vec<BufferBindingModel>,
+  workgroup_variables: Vec<VariableBindingModel>,
+  private_variables: Vec<VariableBindingModel>,
   const_definitions: Vec<VariableBindingModel>,
+  overrides: Vec<OverrideModel>,
   entrypoints: Vec<EntryPointModel>,
   used_buffer_bindings: HashSet<(u32, u32)>,
+  errors: Vec<BlacklightError>,
   _phantom: PhantomData<& 'sh UDT>
 }
 impl<'sh, 'pr: 'sh, UDT> ShaderBuilder<'sh, 'pr, UDT>
@@ -69,10 +84,14 @@ impl<'sh, 'pr: 'sh, UDT> ShaderBuilder<'sh, 'pr, UDT>
       _project: project,
       uniform_struct_data_type: Struct::<UDT>::make_struct_repr(),
       buffer_bindings: Vec::new(),
+      workgroup_variables: Vec::new(),
+      private_variables: Vec::new(),
       const_definitions: Vec::new(),
+      overrides: Vec::new(),
       functions: Vec::new(),
       entrypoints: Vec::new(),
       used_buffer_bindings: HashSet::new(),
+      errors: Vec::new(),
       _phantom: PhantomData,
     }
   }
@@ -83,24 +102,178 @@ impl<'sh, 'pr: 'sh, UDT> ShaderBuilder<'sh, 'pr, UDT>
   {
     let mut code_block_builder = CodeBlockBuilder::new();
     builder_func(&mut code_block_builder);
-    code_block_builder.build()
+    let (code_block_model, errors) = code_block_builder.build();
+    self.errors.extend(errors);
+    code_block_model
+  }
+
+  /**
+   * Report an error, such as one returned by a `try_*` method.
+   *
+   * Reported errors are returned from `Project::define_shader`.
+   */
+  pub fn report_error(&mut self, error: BlacklightError) {
+    self.errors.push(error);
+  }
+
+  /** Get an expression reading the shader's uniforms. */
+  pub fn uniforms(&self) -> ExprHandle<'sh, Struct<UDT>> {
+    let model = user_uniforms_expr(Struct::<UDT>::make_repr());
+    ExprHandle::new(Box::new(model))
   }
 
-  /** Define a new constant. */
-  pub fn define_constant<DT>(&mut self, name: &'static str, value: DT)
-    -> VariableBindingHandle<'sh, DT, VariableRead>
-    where DT: LiteralDataType
+  /**
+   * Define a new constant, from a literal value or a const-expression,
+   * e.g. `tile_w.read() * tile_h.read()`.
+   *
+   * Const-expressions may only use literals, previously defined constants
+   * and const-evaluable builtins.  Errors in the definition are reported
+   * from `Project::define_shader`.  See `try_define_constant` to handle
+   * them directly.
+   */
+  pub fn define_constant<DT>(&mut self,
+    name: &'static str,
+    value: impl ConstInitializer<'sh, DT>,
+  ) -> ConstantHandle<'sh, DT>
+    where DT: ExprDataType
   {
+    match self.try_define_constant(name, value) {
+      Ok(handle) => handle,
+      Err(error) => {
+        self.errors.push(error);
+        ConstantHandle::new(IdentifierModel::new(name))
+      },
+    }
+  }
+
+  /** Define a new constant, returning any error. */
+  pub fn try_define_constant<DT>(&mut self,
+    name: &'static str,
+    value: impl ConstInitializer<'sh, DT>,
+  ) -> Result<ConstantHandle<'sh, DT>, BlacklightError>
+    where DT: ExprDataType
+  {
+    let value_model = value.make_handle().model;
+    if let Some(reason) = check_const_expr(&value_model, &self.const_definitions) {
+      return Err(BlacklightError::NonConstExpression {
+        name: name.to_string(),
+        reason,
+      });
+    }
     let identifier_model = IdentifierModel::new(name);
-    let literal_value = value.to_literal_data_value();
-    let literal_expr_model = LiteralExprModel::new(literal_value);
     let const_definition_model = VariableBindingModel::new(
       identifier_model.clone(),
       VariableBindingDisposition::Const,
       DT::repr(),
-      Some(Box::new(ExpressionModel::Literal(literal_expr_model)))
+      Some(value_model),
     );
     self.const_definitions.push(const_definition_model);
+    Ok(ConstantHandle::new(identifier_model))
+  }
+
+  /**
+   * Define a new pipeline-overridable constant.
+   *
+   * Its value is set when the shader is compiled, see
+   * `Shader::compile_with_overrides`, and otherwise takes the default
+   * value.  Overrides of `u32` may also serve as workgroup sizes.
+   *
+   * An `id` is the key of the value in the pipeline constants, in place
+   * of the name.  Ids must be unique within the shader.
+   */
+  pub fn define_override<DT>(&mut self,
+    name: &'static str,
+    default: Option<DT>,
+    id: Option<u16>,
+  ) -> ConstantHandle<'sh, DT>
+    where DT: OverrideDataType
+  {
+    let identifier_model = IdentifierModel::new(name);
+    if let Some(id) = id {
+      let existing = self.overrides.iter()
+        .find(|override_model| override_model.id() == Some(id));
+      if let Some(existing) = existing {
+        self.errors.push(BlacklightError::DuplicateOverrideId {
+          name: name.to_string(),
+          existing_name: existing.name().as_str().to_string(),
+          id,
+        });
+      }
+    }
+    let default_model = default.map(|default| {
+      let literal_expr_model =
+        LiteralExprModel::new(default.to_literal_data_value());
+      Box::new(ExpressionModel::Literal(literal_expr_model))
+    });
+    let binding_model = VariableBindingModel::new(
+      identifier_model.clone(),
+      VariableBindingDisposition::Override,
+      DT::repr(),
+      default_model,
+    );
+    self.overrides.push(OverrideModel::new(binding_model, id));
+    ConstantHandle::new(identifier_model)
+  }
+
+  /**
+   * Define a new workgroup variable, shared by the invocations of each
+   * workgroup and zero-initialized at the start of each dispatch.
+   *
+   * Writes by other invocations are only visible after a workgroup
+   * barrier, see `CodeBlockBuilder::add_workgroup_barrier_statement`.
+   */
+  pub fn define_workgroup_variable<DT>(&mut self, name: &'static str)
+    -> VariableBindingHandle<'sh, DT, VariableReadWrite>
+    where DT: ExprDataType
+  {
+    let identifier_model = IdentifierModel::new(name);
+    let workgroup_variable_model = VariableBindingModel::new(
+      identifier_model.clone(),
+      VariableBindingDisposition::Workgroup,
+      DT::repr(),
+      None,
+    );
+    self.workgroup_variables.push(workgroup_variable_model);
+    VariableBindingHandle::new(identifier_model)
+  }
+
+  /**
+   * Define a new private variable: a module-scope variable holding a
+   * separate value for each invocation, which any function or entry point
+   * may read and assign.
+   *
+   * The initial value is a literal value or a const-expression, as for
+   * `define_constant`.  Struct-typed variables may start from
+   * `zero_value()`.  Errors in the initial value are reported from
+   * `Project::define_shader`.
+   */
+  pub fn define_private_variable<DT>(&mut self,
+    name: &'static str,
+    initial: impl ConstInitializer<'sh, DT>,
+  ) -> VariableBindingHandle<'sh, DT, VariableReadWrite>
+    where DT: ExprDataType
+  {
+    if DT::repr().contains_atomic() {
+      self.errors.push(BlacklightError::MisplacedAtomic {
+        name: name.to_string(),
+        memory_space: "private".to_string(),
+      });
+    }
+    let initial_model = initial.make_handle().model;
+    if let Some(reason) = check_const_expr(&initial_model, &self.const_definitions) {
+      self.errors.push(BlacklightError::NonConstExpression {
+        name: name.to_string(),
+        reason,
+      });
+    }
+    let identifier_model = IdentifierModel::new(name);
+    let private_variable_model = VariableBindingModel::new(
+      identifier_model.clone(),
+      VariableBindingDisposition::Private,
+      DT::repr(),
+      Some(initial_model),
+    );
+    self.private_variables.push(private_variable_model);
     VariableBindingHandle::new(identifier_model)
   }
 
@@ -133,34 +306,43 @@ impl<'sh, 'pr: 'sh, UDT> ShaderBuilder<'sh, 'pr, UDT>
   }
 
 
-  /** Define a new linear shader entrypoint. */
+  /**
+   * Define a new linear shader entrypoint.
+   *
+   * The builder is passed a context exposing the entry point's builtins,
+   * e.g. `ctx.global_invocation_id()`.  The workgroup size has the
+   * dimensions `ARG` of the ids, and may be given by `u32` constants or
+   * overrides, e.g. `(tile_width, 4)` for ids of `[u32; 2]`.
+   */
   pub fn define_entrypoint<ARG, EPB>(&mut self,
     name: &'static str,
-    block_dims: ARG,
+    workgroup_size: impl WorkgroupSize<ARG>,
     builder_func: EPB
   ) -> EntryPoint<ARG>
   where
-    EPB: FnOnce(&mut CodeBlockBuilder<'sh, 'sh, ()>, ExprHandle<'sh, ARG>),
+    EPB: FnOnce(&mut CodeBlockBuilder<'sh, 'sh, ()>, EntryPointContext<'sh, ARG>),
     ARG: EntryPointArgDataType
   {
-    let ident_model = IdentifierModel::new("global_id");
-    let ident_expr_model = IdentifierExprModel::new(ident_model, ARG::repr());
-    let arg_expr =
-      ExprHandle::new(Box::new(ExpressionModel::Identifier(ident_expr_model)));
+    let context = EntryPointContext::new();
     let code_block_model = self.build_sub_code_block(move |builder| {
-      builder_func(builder, arg_expr);
+      builder_func(builder, context);
     });
     let entry_point_model =
       EntryPointModel::new(
         name.into(),
-        block_dims.to_block_dims(),
+        workgroup_size.to_workgroup_dims(),
         code_block_model
       );
     self.entrypoints.push(entry_point_model.clone());
     EntryPoint::new(entry_point_model)
   }
 
-  /** Define a read-only buffer binding. */
+  /**
+   * Define a read-only buffer binding.
+   *
+   * Errors in the definition are reported from `Project::define_shader`.
+   * See `try_define_read_buffer_binding` to handle them directly.
+   */
   pub fn define_read_buffer_binding<DT>(&mut self,
     name: &str,
     group: u32,
@@ -171,7 +353,23 @@ impl<'sh, 'pr: 'sh, UDT> ShaderBuilder<'sh, 'pr, UDT>
     self.define_buffer_binding(name, group, index)
   }
 
-  /** Define a write-only buffer binding. */
+  /** Define a read-only buffer binding, returning any error. */
+  pub fn try_define_read_buffer_binding<DT>(&mut self,
+    name: &str,
+    group: u32,
+    index: u32
+  ) -> Result<BufferBindingHandle<'sh, DT, BufferRead>, BlacklightError>
+    where DT: HostShareableDataType,
+  {
+    self.try_define_buffer_binding(name, group, index)
+  }
+
+  /**
+   * Define a write-only buffer binding.
+   *
+   * Errors in the definition are reported from `Project::define_shader`.
+   * See `try_define_write_buffer_binding` to handle them directly.
+   */
   pub fn define_write_buffer_binding<DT>(&mut self,
     name: &str,
     group: u32,
@@ -182,7 +380,23 @@ impl<'sh, 'pr: 'sh, UDT> ShaderBuilder<'sh, 'pr, UDT>
     self.define_buffer_binding(name, group, index)
   }
 
-  /** Define a read-write buffer binding. */
+  /** Define a write-only buffer binding, returning any error. */
+  pub fn try_define_write_buffer_binding<DT>(&mut self,
+    name: &str,
+    group: u32,
+    index: u32
+  ) -> Result<BufferBindingHandle<'sh, DT, BufferWrite>, BlacklightError>
+    where DT: HostShareableDataType,
+  {
+    self.try_define_buffer_binding(name, group, index)
+  }
+
+  /**
+   * Define a read-write buffer binding.
+   *
+   * Errors in the definition are reported from `Project::define_shader`.
+   * See `try_define_read_write_buffer_binding` to handle them directly.
+   */
   pub fn define_read_write_buffer_binding<DT>(&mut self,
     name: &str,
     group: u32,
@@ -193,7 +407,23 @@ impl<'sh, 'pr: 'sh, UDT> ShaderBuilder<'sh, 'pr, UDT>
     self.define_buffer_binding(name, group, index)
   }
 
-  /** Define a buffer binding. */
+  /** Define a read-write buffer binding, returning any error. */
+  pub fn try_define_read_write_buffer_binding<DT>(&mut self,
+    name: &str,
+    group: u32,
+    index: u32
+  ) -> Result<BufferBindingHandle<'sh, DT, BufferReadWrite>, BlacklightError>
+    where DT: HostShareableDataType,
+  {
+    self.try_define_buffer_binding(name, group, index)
+  }
+
+  /**
+   * Define a buffer binding, reporting any error.
+   *
+   * The handle is returned even on error, so that the rest of the shader
+   * can be defined and any further errors collected.
+   */
   fn define_buffer_binding<DT, DISP>(&mut self,
     name: &str,
     group: u32,
@@ -202,13 +432,59 @@ impl<'sh, 'pr: 'sh, UDT> ShaderBuilder<'sh, 'pr, UDT>
   where DT: HostShareableDataType,
         DISP: BufferDisposition
   {
+    match self.try_define_buffer_binding(name, group, index) {
+      Ok(handle) => handle,
+      Err(error) => {
+        self.errors.push(error);
+        BufferBindingHandle::new(IdentifierModel::new(name))
+      },
+    }
+  }
+
+  /** Define a buffer binding. */
+  fn try_define_buffer_binding<DT, DISP>(&mut self,
+    name: &str,
+    group: u32,
+    index: u32
+  ) -> Result<BufferBindingHandle<'sh, DT, DISP>, BlacklightError>
+  where DT: HostShareableDataType,
+        DISP: BufferDisposition
+  {
+    if (group, index) == (UNIFORMS_GROUP, UNIFORMS_INDEX) {
+      return Err(BlacklightError::ReservedBufferBinding {
+        binding: name.to_string(),
+        group,
+        index,
+      });
+    }
     if self.used_buffer_bindings.contains(&(group, index)) {
-      panic!("Buffer binding with group {} and index {} already defined.",
-             group, index);
+      let existing = self.buffer_bindings.iter()
+        .find(|binding| (binding.group(), binding.index()) == (group, index))
+        .unwrap();
+      return Err(BlacklightError::DuplicateBufferBinding {
+        binding: name.to_string(),
+        existing_binding: existing.name().as_str().to_string(),
+        group,
+        index,
+      });
+    }
+    if self.buffer_bindings.iter().any(|binding| binding.name().as_str() == name) {
+      return Err(BlacklightError::DuplicateBufferBindingName {
+        binding: name.to_string(),
+      });
     }
+    // Atomics need read-write access.
     let dt_repr = DT::repr();
+    if dt_repr.contains_atomic() && DISP::REPR.is_read_only() {
+      return Err(BlacklightError::MisplacedAtomic {
+        name: name.to_string(),
+        memory_space: "read-only storage".to_string(),
+      });
+    }
+    // Buffers of runtime-sized structs hold a single struct, as wgsl
+    // does not allow arrays of them.
+    let is_singleton = dt_repr.is_runtime_sized();
     let identifier_model = IdentifierModel::new(name);
-    // Ensure that the 
     let buffer_binding_model = BufferBindingModel::new(
       identifier_model.clone(),
       BufferMemorySpaceRepr::Storage,
@@ -216,25 +492,108 @@ impl<'sh, 'pr: 'sh, UDT> ShaderBuilder<'sh, 'pr, UDT>
       group,
       index,
       dt_repr,
-      /* is_singleton */ false
+      is_singleton,
     );
     self.buffer_bindings.push(buffer_binding_model);
     self.used_buffer_bindings.insert((group, index));
-    BufferBindingHandle::new(identifier_model)
+    Ok(BufferBindingHandle::new(identifier_model))
   }
 
-  /** Build the shader from the definitions provided. */
-  pub(crate) fn build(self) -> Shader<UDT> {
+  /**
+   * Build the shader from the definitions provided.
+   *
+   * Returns all errors reported while defining the shader.
+   */
+  pub(crate) fn build(mut self) -> Result<Shader<UDT>, BlacklightError> {
+    let struct_data_types = self.collect_struct_data_types();
+    self.errors.extend(Self::check_runtime_arrays(&struct_data_types));
+    if Struct::<UDT>::repr().contains_atomic() {
+      self.errors.push(BlacklightError::MisplacedAtomic {
+        name: UDT::NAME.to_string(),
+        memory_space: "uniform".to_string(),
+      });
+    }
     let shader_model =
       ShaderModel::new(
-        self.collect_struct_data_types(),
+        struct_data_types,
         Struct::<UDT>::make_struct_repr(),
         self.buffer_bindings,
+        self.workgroup_variables,
+        self.private_variables,
         self.const_definitions,
+        self.overrides,
         self.functions,
         self.entrypoints
       );
-    Shader::new(shader_model)
+    self.errors.extend(Self::check_uniform_arrays(&shader_model));
+    self.errors.extend(Self::check_struct_layouts(&shader_model));
+    self.errors.extend(check_barrier_uniformity(&shader_model));
+    match BlacklightError::from_errors(self.errors) {
+      Some(error) => Err(error),
+      None => Ok(Shader::new(shader_model)),
+    }
+  }
+
+  /** Check that runtime-sized array fields only end structs. */
+  fn check_runtime_arrays(struct_data_types: &[StructDataTypeRepr])
+    -> Vec<BlacklightError>
+  {
+    struct_data_types.iter()
+      .flat_map(|struct_data_type| {
+        let fields = struct_data_type.fields();
+        let leading_fields = &fields[..fields.len().saturating_sub(1)];
+        leading_fields.iter()
+          .filter(|field| field.data_type().is_runtime_sized())
+          .map(|field| BlacklightError::MisplacedRuntimeArray {
+            struct_name: struct_data_type.name().as_str().to_string(),
+            field: field.name().to_string(),
+          })
+      })
+      .collect()
+  }
+
+  /**
+   * Check that the array fields of structs used in the uniforms have
+   * strides which wgsl allows in the uniform space.
+   */
+  fn check_uniform_arrays(shader_model: &ShaderModel) -> Vec<BlacklightError> {
+    shader_model.uniform_struct_data_types().iter()
+      .flat_map(|uniform_struct| {
+        uniform_struct.fields().iter()
+          .filter_map(|field| {
+            let stride = match field.data_type() {
+              DataTypeRepr::Array(array) => array.invalid_uniform_stride()?,
+              _ => return None,
+            };
+            Some(BlacklightError::InvalidUniformArrayStride {
+              struct_name: uniform_struct.name().as_str().to_string(),
+              field: field.name().to_string(),
+              stride,
+            })
+          })
+          .collect::<Vec<_>>()
+      })
+      .collect()
+  }
+
+  /**
+   * Check that structs used in both uniform and storage buffers have the
+   * same layout in both, as wgsl allows only one definition of a struct.
+   */
+  fn check_struct_layouts(shader_model: &ShaderModel) -> Vec<BlacklightError> {
+    let uniform_structs = shader_model.uniform_struct_data_types();
+    shader_model.storage_struct_data_types().into_iter()
+      .filter(|storage_struct| {
+        let is_uniform = uniform_structs.iter()
+          .any(|uniform_struct| uniform_struct.name() == storage_struct.name());
+        let uniform_layout = storage_struct.layout(BufferMemorySpaceRepr::Uniform);
+        let storage_layout = storage_struct.layout(BufferMemorySpaceRepr::Storage);
+        is_uniform && uniform_layout != storage_layout
+      })
+      .map(|storage_struct| BlacklightError::StructLayoutMismatch {
+        struct_name: storage_struct.name().as_str().to_string(),
+      })
+      .collect()
   }
 
   /**
@@ -248,6 +607,8 @@ impl<'sh, 'pr: 'sh, UDT> ShaderBuilder<'sh, 'pr, UDT>
   }
 
   fn collect_struct_data_types_into(&self, collector: &mut DataTypeCollector) {
+    collector.exclude_name(UNIFORMS_STRUCT_NAME);
+    collector.exclude_name(LENGTHS_STRUCT_NAME);
     collector.add_struct_data_type(self.uniform_struct_data_type.clone());
     for function in &self.functions {
       function.collect_struct_data_types_into(collector);
@@ -255,5 +616,16 @@ impl<'sh, 'pr: 'sh, UDT> ShaderBuilder<'sh, 'pr, UDT>
     for buffer_binding in &self.buffer_bindings {
       buffer_binding.collect_struct_data_types_into(collector);
     }
+    for const_definition in &self.const_definitions {
+      collector.add_data_type(const_definition.data_type().clone());
+      const_definition.collect_struct_data_types_into(collector);
+    }
+    for workgroup_variable in &self.workgroup_variables {
+      collector.add_data_type(workgroup_variable.data_type().clone());
+    }
+    for private_variable in &self.private_variables {
+      collector.add_data_type(private_variable.data_type().clone());
+      private_variable.collect_struct_data_types_into(collector);
+    }
   }
 }
@@ -0,0 +1,81 @@
+use crate::{
+  api::{
+    builder::CodeBlockBuilder,
+    data_type::{ ExprScalarIntegralDataType, ProcResultType },
+    BlacklightError,
+  },
+  model::SwitchClauseModel,
+};
+
+/**
+ * Builder for the clauses of a switch statement.
+ */
+pub struct SwitchBuilder<'a, 'cb, 'sh: 'cb, DT, ReturnT>
+  where DT: ExprScalarIntegralDataType,
+        ReturnT: ProcResultType,
+{
+  parent: &'a mut CodeBlockBuilder<'cb, 'sh, ReturnT>,
+  clauses: Vec<SwitchClauseModel>,
+  case_values: Vec<DT>,
+  has_default: bool,
+}
+impl<'a, 'cb, 'sh: 'cb, DT, ReturnT> SwitchBuilder<'a, 'cb, 'sh, DT, ReturnT>
+  where DT: ExprScalarIntegralDataType,
+        ReturnT: ProcResultType,
+{
+  pub(crate) fn new(parent: &'a mut CodeBlockBuilder<'cb, 'sh, ReturnT>)
+    -> Self
+  {
+    SwitchBuilder {
+      parent,
+      clauses: Vec::new(),
+      case_values: Vec::new(),
+      has_default: false,
+    }
+  }
+
+  /**
+   * Add a clause selected by any of the given case values.
+   */
+  pub fn add_case<B>(&mut self, values: &[DT], body_builder: B)
+    where B: for <'cs> FnOnce(&mut CodeBlockBuilder<'cs, 'cb, ReturnT>)
+  {
+    for value in values {
+      if self.case_values.contains(value) {
+        self.parent.report_error(BlacklightError::DuplicateSwitchCase {
+          case: value.to_string(),
+        });
+      } else {
+        self.case_values.push(*value);
+      }
+    }
+    let body = self.parent.build_switch_clause(body_builder);
+    let case_values = values.iter()
+      .map(|value| value.to_literal_data_value())
+      .collect();
+    self.clauses.push(SwitchClauseModel::new(case_values, false, body));
+  }
+
+  /**
+   * Add the default clause, selected when no case value matches.
+   */
+  pub fn add_default<B>(&mut self, body_builder: B)
+    where B: for <'cs> FnOnce(&mut CodeBlockBuilder<'cs, 'cb, ReturnT>)
+  {
+    if self.has_default {
+      self.parent.report_error(BlacklightError::DuplicateSwitchCase {
+        case: "default".to_string(),
+      });
+    }
+    self.has_default = true;
+    let body = self.parent.build_switch_clause(body_builder);
+    self.clauses.push(SwitchClauseModel::new(Vec::new(), true, body));
+  }
+
+  pub(crate) fn build(self) -> Vec<SwitchClauseModel> {
+    if !self.has_default {
+      self.parent.report_error(BlacklightError::MissingSwitchDefault);
+    }
+    self.clauses
+  }
+}
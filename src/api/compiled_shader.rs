@@ -0,0 +1,233 @@
+use std::{
+  borrow::Cow,
+  collections::HashMap,
+  fmt,
+};
+use crate::{
+  api::{
+    builder::ShaderBindingsBuilder,
+    BlacklightError,
+    data_type::{ EntryPointArgDataType, StructMappedDataType },
+    EntryPoint,
+    Project,
+    Shader,
+    ShaderBindings,
+  },
+  model::resolve_workgroup_sizes,
+  pipeline::{ PipelineLayoutSet, UniformsLayout },
+  printer::generate_pipeline_wgsl,
+};
+
+/**
+ * A shader compiled for a project's device.
+ *
+ * Holds the wgpu shader module generated from the shader's wgsl, along with
+ * a compute pipeline for each of the shader's entry points.  All pipelines
+ * share a single pipeline layout generated from the shader's buffer bindings.
+ * The values of the shader's overrides are fixed when it is compiled.
+ *
+ * Each set of bindings owns a buffer backing the implicit uniforms binding,
+ * holding the lengths of the bound buffers.  The user uniforms are copied
+ * into it as part of each dispatch.
+ */
+pub struct CompiledShader<'pr, UDT>
+  where UDT: StructMappedDataType
+{
+  project: &'pr Project,
+  shader: Shader<UDT>,
+  module: wgpu::ShaderModule,
+  layouts: PipelineLayoutSet,
+  uniforms_layout: UniformsLayout,
+  pipelines: Vec<(String, wgpu::ComputePipeline)>,
+}
+impl<'pr, UDT> CompiledShader<'pr, UDT>
+  where UDT: StructMappedDataType
+{
+  /**
+   * Compile the given shader for the project's device, with the values of
+   * its overrides given by name.
+   *
+   * Fails if an override used as a workgroup size is not a non-zero `u32`.
+   */
+  pub(crate) fn new(project: &'pr Project,
+    shader: Shader<UDT>,
+    override_values: &HashMap<String, f64>,
+  ) -> Result<Self, BlacklightError> {
+    let device = project.device();
+    let overrides = shader.model().overrides();
+    let workgroup_sizes =
+      resolve_workgroup_sizes(shader.model(), override_values)?;
+    let wgsl = generate_pipeline_wgsl(shader.model(), &workgroup_sizes);
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+      label: None,
+      source: wgpu::ShaderSource::Wgsl(Cow::Owned(wgsl)),
+    });
+    let constants = overrides.iter()
+      .filter_map(|override_model| {
+        let value = override_values.get(override_model.name().as_str())?;
+        Some((override_model.pipeline_constant_key(), *value))
+      })
+      .collect::<HashMap<String, f64>>();
+    let layouts = PipelineLayoutSet::new(device, shader.model());
+    let uniforms_layout = UniformsLayout::new(shader.model());
+    let pipelines = shader.model().entrypoints().iter()
+      .map(|entrypoint| {
+        let pipeline = device.create_compute_pipeline(
+          &wgpu::ComputePipelineDescriptor {
+            label: Some(entrypoint.name()),
+            layout: Some(layouts.pipeline_layout()),
+            module: &module,
+            entry_point: entrypoint.name(),
+            compilation_options: wgpu::PipelineCompilationOptions {
+              constants: &constants,
+              ..Default::default()
+            },
+            cache: None,
+          }
+        );
+        (entrypoint.name().to_string(), pipeline)
+      })
+      .collect();
+    Ok(CompiledShader {
+      project,
+      shader,
+      module,
+      layouts,
+      uniforms_layout,
+      pipelines,
+    })
+  }
+
+  /** Get the shader this was compiled from. */
+  pub fn shader(&self) -> &Shader<UDT> {
+    &self.shader
+  }
+
+  /** Get the compiled shader module. */
+  pub fn module(&self) -> &wgpu::ShaderModule {
+    &self.module
+  }
+
+  /** Get the bind group layouts of the shader, indexed by group. */
+  pub fn bind_group_layouts(&self) -> &[wgpu::BindGroupLayout] {
+    self.layouts.bind_group_layouts()
+  }
+
+  /**
+   * Get the bind group layout for a group.
+   *
+   * Panics if the group is beyond the highest group used by the shader.
+   */
+  pub fn bind_group_layout(&self, group: u32) -> &wgpu::BindGroupLayout {
+    &self.layouts.bind_group_layouts()[group as usize]
+  }
+
+  /** Get the pipeline layout shared by all of the shader's pipelines. */
+  pub fn pipeline_layout(&self) -> &wgpu::PipelineLayout {
+    self.layouts.pipeline_layout()
+  }
+
+  /**
+   * Get the compute pipeline for an entry point.
+   *
+   * Panics if the entry point was not defined by this shader.
+   */
+  pub fn pipeline<ARG>(&self, entry_point: &EntryPoint<ARG>)
+    -> &wgpu::ComputePipeline
+    where ARG: EntryPointArgDataType
+  {
+    let maybe_pipeline = self.pipelines.iter()
+      .find(|(name, _)| name == entry_point.name());
+    match maybe_pipeline {
+      Some((_, pipeline)) => pipeline,
+      None => panic!("Entry point '{}' not found in shader",
+                     entry_point.name()),
+    }
+  }
+
+  /**
+   * Bind buffers to the shader's buffer bindings.
+   *
+   * Every buffer binding must be bound exactly once, see
+   * `ShaderBindingsBuilder`.  Errors in binding are returned together.
+   */
+  pub fn define_bindings<'a, DFN>(&'a self, definer_fn: DFN)
+    -> Result<ShaderBindings, BlacklightError>
+    where DFN: FnOnce(&mut ShaderBindingsBuilder<'a>)
+  {
+    let mut bindings_builder = ShaderBindingsBuilder::new(self.shader.model());
+    definer_fn(&mut bindings_builder);
+    bindings_builder.build(
+      self.project.device(),
+      self.bind_group_layouts(),
+      &self.uniforms_layout,
+    )
+  }
+
+  /**
+   * Record a dispatch of an entry point into a command encoder.
+   *
+   * The workgroup counts have the same dimensions as the entry point.
+   *
+   * The uniforms are copied into the bindings' uniforms buffer by commands
+   * recorded ahead of the dispatch, so each of several dispatches recorded
+   * into the same encoder sees its own uniforms.  The copy is skipped when
+   * the uniforms are unchanged since the last dispatch with the bindings,
+   * so encoders must be submitted in the order they were recorded.
+   */
+  pub fn encode_dispatch<ARG>(&self,
+    encoder: &mut wgpu::CommandEncoder,
+    entry_point: &EntryPoint<ARG>,
+    uniforms: &UDT,
+    bindings: &ShaderBindings,
+    workgroups: ARG,
+  )
+    where ARG: EntryPointArgDataType
+  {
+    self.uniforms_layout.encode_update(
+      self.project.device(),
+      encoder,
+      bindings,
+      uniforms,
+    );
+    let [x, y, z] = workgroups.to_block_dims().to_xyz();
+    let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+      label: Some(entry_point.name()),
+      timestamp_writes: None,
+    });
+    pass.set_pipeline(self.pipeline(entry_point));
+    for (group, bind_group) in bindings.bind_groups().iter().enumerate() {
+      pass.set_bind_group(group as u32, bind_group, &[]);
+    }
+    pass.dispatch_workgroups(x, y, z);
+  }
+
+  /**
+   * Dispatch an entry point on the project's queue.
+   *
+   * See `encode_dispatch` for how the arguments are interpreted.
+   */
+  pub fn dispatch<ARG>(&self,
+    entry_point: &EntryPoint<ARG>,
+    uniforms: &UDT,
+    bindings: &ShaderBindings,
+    workgroups: ARG,
+  )
+    where ARG: EntryPointArgDataType
+  {
+    let mut encoder = self.project.device().create_command_encoder(
+      &wgpu::CommandEncoderDescriptor { label: Some(entry_point.name()) }
+    );
+    self.encode_dispatch(&mut encoder, entry_point, uniforms, bindings, workgroups);
+    self.project.queue().submit(Some(encoder.finish()));
+  }
+}
+impl<'pr, UDT> fmt::Debug for CompiledShader<'pr, UDT>
+  where UDT: StructMappedDataType
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("CompiledShader")
+      .field("shader", &self.shader)
+      .finish()
+  }
+}
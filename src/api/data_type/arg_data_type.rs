@@ -61,15 +61,13 @@ pub trait ArgTupleHandleVisitor<'a> {
 }
 
 //////////////////////////////////////////////////////////////////////
-/// Implementations for ArgTupleHandleMap for tuples of argument types.
+// Implementations for ArgTupleHandleMap for tuples of argument types.
 
 impl<'a> ArgTupleHandleMap<'a> for () {
   type HandleTuple = ();
   type NameTuple = ();
 
-  fn make_handle_tuple(_names: &Self::NameTuple) -> Self::HandleTuple {
-    ()
-  }
+  fn make_handle_tuple(_names: &Self::NameTuple) -> Self::HandleTuple {}
   fn make_names_vector(_names: &Self::NameTuple) -> Vec<IdentifierModel> {
     Vec::new()
   }
@@ -0,0 +1,194 @@
+use std::marker::PhantomData;
+use crate::api::{
+  buffer_attributes::BufferMemorySpaceRepr,
+  data_type::{
+    ArrayDataTypeRepr,
+    DataTypeRepr,
+    ExprDataType,
+    ExprIndexableDataType,
+    ExprLoadableDataType,
+    HostShareableDataType,
+    LiteralDataType,
+    LiteralDataValue,
+    StructFieldDataType,
+  },
+};
+
+/**
+ * A fixed-size array of `N` host-shareable elements, mapped to
+ * `array<T, N>`.
+ *
+ * Rust arrays of scalars already map to wgsl vectors, so arrays are
+ * wrapped in this type instead, much as structs are wrapped in `Struct<T>`.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Array<T: HostShareableDataType, const N: usize> {
+  // The array elements.
+  elements: [T; N],
+}
+impl<T: HostShareableDataType, const N: usize> Array<T, N> {
+  /** Create a new array from its elements. */
+  pub fn new(elements: [T; N]) -> Self {
+    Array { elements }
+  }
+
+  /** Get the representation of the array type. */
+  fn array_repr() -> ArrayDataTypeRepr {
+    ArrayDataTypeRepr::new(T::repr(), Some(N))
+  }
+
+  /** Get the elements. */
+  pub fn elements(&self) -> &[T; N] {
+    &self.elements
+  }
+
+  /** Get the elements mutably. */
+  pub fn elements_mut(&mut self) -> &mut [T; N] {
+    &mut self.elements
+  }
+}
+impl<T, const N: usize> Default for Array<T, N>
+  where T: HostShareableDataType + Default
+{
+  fn default() -> Self {
+    Array { elements: [T::default(); N] }
+  }
+}
+impl<T: HostShareableDataType, const N: usize> From<[T; N]> for Array<T, N> {
+  fn from(elements: [T; N]) -> Self {
+    Array { elements }
+  }
+}
+impl<T: HostShareableDataType, const N: usize> ExprDataType for Array<T, N> {
+  fn repr() -> DataTypeRepr {
+    DataTypeRepr::new_array(Self::array_repr())
+  }
+}
+impl<T: HostShareableDataType, const N: usize> ExprIndexableDataType for Array<T, N> {
+  type Element = T;
+}
+impl<T: HostShareableDataType, const N: usize> HostShareableDataType for Array<T, N> {
+  fn alignment() -> usize {
+    T::alignment()
+  }
+
+  fn size() -> usize {
+    N * T::array_stride()
+  }
+
+  fn write_bytes_in(&self,
+    memory_space: BufferMemorySpaceRepr,
+    bytes: &mut [u8],
+  ) {
+    let stride = Self::array_repr().stride(memory_space);
+    for (i, element) in self.elements.iter().enumerate() {
+      element.write_bytes_in(memory_space, &mut bytes[i * stride ..]);
+    }
+  }
+
+  fn read_bytes_in(&mut self,
+    memory_space: BufferMemorySpaceRepr,
+    bytes: &[u8],
+  ) {
+    let stride = Self::array_repr().stride(memory_space);
+    for (i, element) in self.elements.iter_mut().enumerate() {
+      element.read_bytes_in(memory_space, &bytes[i * stride ..]);
+    }
+  }
+}
+impl<T, const N: usize> ExprLoadableDataType for Array<T, N>
+  where T: ExprLoadableDataType
+{
+}
+impl<T: HostShareableDataType, const N: usize> StructFieldDataType for Array<T, N> {
+  type DataType = Array<T, N>;
+  fn to_data_type(self) -> Self::DataType {
+    self
+  }
+  fn from_data_type(data_type: Self::DataType) -> Self {
+    data_type
+  }
+}
+impl<T, const N: usize> LiteralDataType for Array<T, N>
+  where T: HostShareableDataType + LiteralDataType
+{
+  fn to_literal_data_value(&self) -> LiteralDataValue {
+    LiteralDataValue::Array(
+      Self::array_repr(),
+      self.elements.iter().map(|element| element.to_literal_data_value()).collect(),
+    )
+  }
+}
+
+/**
+ * A runtime-sized array of host-shareable elements, mapped to `array<T>`.
+ *
+ * Runtime-sized arrays may only be the last field of a struct held by a
+ * storage buffer binding.  Such buffers hold a single struct rather than
+ * an array of them, see `BufferBindingHandle::singleton`.  The elements
+ * live only in the buffer, so the host value of this type is empty, and
+ * the elements are accessed with `GpuBuffer::write_runtime_array` and
+ * `GpuBuffer::read_runtime_array`.
+ */
+#[derive(Debug, PartialEq)]
+pub struct RuntimeArray<T: HostShareableDataType> {
+  _phantom: PhantomData<T>,
+}
+impl<T: HostShareableDataType> RuntimeArray<T> {
+  /** Create a new runtime-sized array placeholder. */
+  pub fn new() -> Self {
+    RuntimeArray { _phantom: PhantomData }
+  }
+}
+impl<T: HostShareableDataType> Clone for RuntimeArray<T> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+impl<T: HostShareableDataType> Copy for RuntimeArray<T> {
+}
+impl<T: HostShareableDataType> Default for RuntimeArray<T> {
+  fn default() -> Self {
+    RuntimeArray::new()
+  }
+}
+impl<T: HostShareableDataType> ExprDataType for RuntimeArray<T> {
+  fn repr() -> DataTypeRepr {
+    DataTypeRepr::new_array(ArrayDataTypeRepr::new(T::repr(), None))
+  }
+}
+impl<T: HostShareableDataType> ExprIndexableDataType for RuntimeArray<T> {
+  type Element = T;
+}
+impl<T: HostShareableDataType> HostShareableDataType for RuntimeArray<T> {
+  fn alignment() -> usize {
+    T::alignment()
+  }
+
+  fn size() -> usize {
+    0
+  }
+
+  fn write_bytes_in(&self,
+    _memory_space: BufferMemorySpaceRepr,
+    _bytes: &mut [u8],
+  ) {
+  }
+
+  fn read_bytes_in(&mut self,
+    _memory_space: BufferMemorySpaceRepr,
+    _bytes: &[u8],
+  ) {
+  }
+}
+impl<T: ExprLoadableDataType> ExprLoadableDataType for RuntimeArray<T> {
+}
+impl<T: HostShareableDataType> StructFieldDataType for RuntimeArray<T> {
+  type DataType = RuntimeArray<T>;
+  fn to_data_type(self) -> Self::DataType {
+    self
+  }
+  fn from_data_type(data_type: Self::DataType) -> Self {
+    data_type
+  }
+}
@@ -0,0 +1,135 @@
+use std::marker::PhantomData;
+use crate::api::{
+  buffer_attributes::BufferMemorySpaceRepr,
+  data_type::{
+    DataTypeRepr,
+    ExprDataType,
+    ExprScalarIntegralDataType,
+    HostShareableDataType,
+    StructFieldDataType,
+  },
+};
+
+/**
+ * Scalar data types that can be held atomically, i.e. `i32` and `u32`.
+ */
+pub trait ExprAtomicScalarDataType:
+  ExprScalarIntegralDataType + HostShareableDataType
+{
+  /** The representation of `atomic<T>`. */
+  fn atomic_repr() -> DataTypeRepr;
+
+  /** The representation of `__atomic_compare_exchange_result<T>`. */
+  fn compare_exchange_result_repr() -> DataTypeRepr;
+}
+impl ExprAtomicScalarDataType for i32 {
+  fn atomic_repr() -> DataTypeRepr {
+    DataTypeRepr::new_atomic_i32()
+  }
+  fn compare_exchange_result_repr() -> DataTypeRepr {
+    DataTypeRepr::new_atomic_compare_exchange_result_i32()
+  }
+}
+impl ExprAtomicScalarDataType for u32 {
+  fn atomic_repr() -> DataTypeRepr {
+    DataTypeRepr::new_atomic_u32()
+  }
+  fn compare_exchange_result_repr() -> DataTypeRepr {
+    DataTypeRepr::new_atomic_compare_exchange_result_u32()
+  }
+}
+
+/**
+ * An atomically accessed scalar, mapped to `atomic<T>`.
+ *
+ * Atomics may only live in read-write storage buffers, either as buffer
+ * elements or as struct fields, and in workgroup variables.  Atomics held
+ * anywhere else are reported as errors from `Project::define_shader`.
+ * In shaders they are accessed only through the atomic builtins, e.g.
+ * `BufferBindingHandle::atomic_add`, never read or assigned directly, as
+ * they are not `ExprLoadableDataType`.  On the host they are laid out as
+ * plain `T`.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Atomic<T: ExprAtomicScalarDataType> {
+  // The value held.
+  value: T,
+}
+impl<T: ExprAtomicScalarDataType> Atomic<T> {
+  /** Create a new atomic holding a value. */
+  pub fn new(value: T) -> Self {
+    Atomic { value }
+  }
+
+  /** Get the value held. */
+  pub fn value(&self) -> T {
+    self.value
+  }
+}
+impl<T: ExprAtomicScalarDataType> From<T> for Atomic<T> {
+  fn from(value: T) -> Self {
+    Atomic { value }
+  }
+}
+impl<T: ExprAtomicScalarDataType> ExprDataType for Atomic<T> {
+  fn repr() -> DataTypeRepr {
+    T::atomic_repr()
+  }
+}
+impl<T: ExprAtomicScalarDataType> HostShareableDataType for Atomic<T> {
+  fn alignment() -> usize {
+    T::alignment()
+  }
+
+  fn size() -> usize {
+    T::size()
+  }
+
+  fn write_bytes_in(&self,
+    memory_space: BufferMemorySpaceRepr,
+    bytes: &mut [u8],
+  ) {
+    self.value.write_bytes_in(memory_space, bytes);
+  }
+
+  fn read_bytes_in(&mut self,
+    memory_space: BufferMemorySpaceRepr,
+    bytes: &[u8],
+  ) {
+    self.value.read_bytes_in(memory_space, bytes);
+  }
+}
+impl<T: ExprAtomicScalarDataType> StructFieldDataType for Atomic<T> {
+  type DataType = Atomic<T>;
+  fn to_data_type(self) -> Self::DataType {
+    self
+  }
+  fn from_data_type(data_type: Self::DataType) -> Self {
+    data_type
+  }
+}
+
+/**
+ * The result of `atomicCompareExchangeWeak`, mapped to the predeclared
+ * `__atomic_compare_exchange_result<T>`.
+ *
+ * The type has no host value and cannot be named in wgsl, so its
+ * declarations are printed with the type left inferred.  Its fields are
+ * read with `old_value()` and `exchanged()`.
+ */
+#[derive(Debug, PartialEq)]
+pub struct AtomicCompareExchangeResult<T: ExprAtomicScalarDataType> {
+  _phantom: PhantomData<T>,
+}
+impl<T: ExprAtomicScalarDataType> Clone for AtomicCompareExchangeResult<T> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+impl<T: ExprAtomicScalarDataType> Copy for AtomicCompareExchangeResult<T> {
+}
+impl<T: ExprAtomicScalarDataType> ExprDataType for AtomicCompareExchangeResult<T> {
+  fn repr() -> DataTypeRepr {
+    T::compare_exchange_result_repr()
+  }
+}
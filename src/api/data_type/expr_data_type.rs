@@ -1,4 +1,10 @@
-use crate::api::data_type::{ DataTypeRepr, Struct, StructMappedDataType };
+use std::fmt;
+use crate::api::data_type::{
+  DataTypeRepr,
+  LiteralDataType,
+  Struct,
+  StructMappedDataType,
+};
 
 /**
  * Data types that can be the result of expressions.
@@ -8,12 +14,35 @@ pub trait ExprDataType: 'static + Sized + Clone + Copy {
   fn repr() -> DataTypeRepr;
 }
 
+/** Void expressions, such as calls to `atomicStore`, have the unit type. */
+impl ExprDataType for () {
+  fn repr() -> DataTypeRepr {
+    DataTypeRepr::new_void()
+  }
+}
+
 impl ExprDataType for bool {
   fn repr() -> DataTypeRepr {
     DataTypeRepr::new_bool()
   }
 }
 
+impl ExprDataType for [bool; 2] {
+  fn repr() -> DataTypeRepr {
+    DataTypeRepr::new_vec2_bool()
+  }
+}
+impl ExprDataType for [bool; 3] {
+  fn repr() -> DataTypeRepr {
+    DataTypeRepr::new_vec3_bool()
+  }
+}
+impl ExprDataType for [bool; 4] {
+  fn repr() -> DataTypeRepr {
+    DataTypeRepr::new_vec4_bool()
+  }
+}
+
 impl ExprDataType for i32 {
   fn repr() -> DataTypeRepr {
     DataTypeRepr::new_i32()
@@ -77,6 +106,52 @@ impl ExprDataType for [f32; 4] {
   }
 }
 
+impl ExprDataType for [[f32; 2]; 2] {
+  fn repr() -> DataTypeRepr {
+    DataTypeRepr::new_mat2x2_f32()
+  }
+}
+impl ExprDataType for [[f32; 3]; 2] {
+  fn repr() -> DataTypeRepr {
+    DataTypeRepr::new_mat2x3_f32()
+  }
+}
+impl ExprDataType for [[f32; 4]; 2] {
+  fn repr() -> DataTypeRepr {
+    DataTypeRepr::new_mat2x4_f32()
+  }
+}
+impl ExprDataType for [[f32; 2]; 3] {
+  fn repr() -> DataTypeRepr {
+    DataTypeRepr::new_mat3x2_f32()
+  }
+}
+impl ExprDataType for [[f32; 3]; 3] {
+  fn repr() -> DataTypeRepr {
+    DataTypeRepr::new_mat3x3_f32()
+  }
+}
+impl ExprDataType for [[f32; 4]; 3] {
+  fn repr() -> DataTypeRepr {
+    DataTypeRepr::new_mat3x4_f32()
+  }
+}
+impl ExprDataType for [[f32; 2]; 4] {
+  fn repr() -> DataTypeRepr {
+    DataTypeRepr::new_mat4x2_f32()
+  }
+}
+impl ExprDataType for [[f32; 3]; 4] {
+  fn repr() -> DataTypeRepr {
+    DataTypeRepr::new_mat4x3_f32()
+  }
+}
+impl ExprDataType for [[f32; 4]; 4] {
+  fn repr() -> DataTypeRepr {
+    DataTypeRepr::new_mat4x4_f32()
+  }
+}
+
 impl<T> ExprDataType for Struct<T>
   where T: Copy + StructMappedDataType
 {
@@ -105,6 +180,166 @@ impl ExprNumericDataType for [f32; 2] {}
 impl ExprNumericDataType for [f32; 3] {}
 impl ExprNumericDataType for [f32; 4] {}
 
+/**
+ * Expr data types that are boolean scalars or vectors.
+ */
+pub trait ExprBoolDataType: ExprDataType {}
+
+impl ExprBoolDataType for bool {}
+impl ExprBoolDataType for [bool; 2] {}
+impl ExprBoolDataType for [bool; 3] {}
+impl ExprBoolDataType for [bool; 4] {}
+
+/**
+ * Expr data types that can be indexed at runtime, `e[index]`: vectors by
+ * component, matrices by column, and arrays by element.
+ */
+pub trait ExprIndexableDataType: ExprDataType {
+  type Element: ExprDataType;
+}
+
+/**
+ * Expr data types that are vectors of any scalar type.
+ */
+pub trait ExprVectorDataType: ExprDataType {
+  type Scalar: ExprDataType;
+  const DIMS: u32;
+}
+
+// Macro to implement ExprVectorDataType and ExprIndexableDataType for the
+// vectors of a scalar type.
+macro_rules! impl_vector {
+  ($scalar:ty) => {
+    impl ExprVectorDataType for [$scalar; 2] {
+      type Scalar = $scalar;
+      const DIMS: u32 = 2;
+    }
+    impl ExprVectorDataType for [$scalar; 3] {
+      type Scalar = $scalar;
+      const DIMS: u32 = 3;
+    }
+    impl ExprVectorDataType for [$scalar; 4] {
+      type Scalar = $scalar;
+      const DIMS: u32 = 4;
+    }
+    impl ExprIndexableDataType for [$scalar; 2] {
+      type Element = $scalar;
+    }
+    impl ExprIndexableDataType for [$scalar; 3] {
+      type Element = $scalar;
+    }
+    impl ExprIndexableDataType for [$scalar; 4] {
+      type Element = $scalar;
+    }
+  };
+}
+
+impl_vector!(bool);
+impl_vector!(i32);
+impl_vector!(u32);
+impl_vector!(f32);
+
+/**
+ * Expr data types that are matrices of `f32`, stored as arrays of columns:
+ * `[[f32; R]; C]` maps to `matCxR<f32>`.
+ */
+pub trait ExprMatrixDataType: ExprDataType {
+  type Column: ExprVectorDataType<Scalar = f32>;
+  type Transposed: ExprMatrixDataType;
+  const COLUMNS: u32;
+  const ROWS: u32;
+}
+
+// Macro to implement ExprMatrixDataType and ExprIndexableDataType for the
+// matrices with a given number of columns.
+macro_rules! impl_matrix {
+  ($columns:literal) => {
+    impl ExprMatrixDataType for [[f32; 2]; $columns] {
+      type Column = [f32; 2];
+      type Transposed = [[f32; $columns]; 2];
+      const COLUMNS: u32 = $columns;
+      const ROWS: u32 = 2;
+    }
+    impl ExprIndexableDataType for [[f32; 2]; $columns] {
+      type Element = [f32; 2];
+    }
+    impl ExprMatrixDataType for [[f32; 3]; $columns] {
+      type Column = [f32; 3];
+      type Transposed = [[f32; $columns]; 3];
+      const COLUMNS: u32 = $columns;
+      const ROWS: u32 = 3;
+    }
+    impl ExprIndexableDataType for [[f32; 3]; $columns] {
+      type Element = [f32; 3];
+    }
+    impl ExprMatrixDataType for [[f32; 4]; $columns] {
+      type Column = [f32; 4];
+      type Transposed = [[f32; $columns]; 4];
+      const COLUMNS: u32 = $columns;
+      const ROWS: u32 = 4;
+    }
+    impl ExprIndexableDataType for [[f32; 4]; $columns] {
+      type Element = [f32; 4];
+    }
+  };
+}
+
+impl_matrix!(2);
+impl_matrix!(3);
+impl_matrix!(4);
+
+/**
+ * Matrix expr data types with as many rows as columns, for builtins such
+ * as `determinant`.
+ */
+pub trait ExprSquareMatrixDataType: ExprMatrixDataType {}
+
+impl ExprSquareMatrixDataType for [[f32; 2]; 2] {}
+impl ExprSquareMatrixDataType for [[f32; 3]; 3] {}
+impl ExprSquareMatrixDataType for [[f32; 4]; 4] {}
+
+/**
+ * Expr data types that can be compared, with comparisons of vectors
+ * yielding a boolean vector of componentwise results.
+ */
+pub trait ExprComparableDataType: ExprDataType {
+  type CmpResult: ExprBoolDataType;
+}
+
+// Macro to implement ExprComparableDataType for scalar and vector types.
+macro_rules! impl_comparable {
+  ($scalar:ty) => {
+    impl ExprComparableDataType for $scalar {
+      type CmpResult = bool;
+    }
+    impl ExprComparableDataType for [$scalar; 2] {
+      type CmpResult = [bool; 2];
+    }
+    impl ExprComparableDataType for [$scalar; 3] {
+      type CmpResult = [bool; 3];
+    }
+    impl ExprComparableDataType for [$scalar; 4] {
+      type CmpResult = [bool; 4];
+    }
+  };
+}
+
+impl_comparable!(bool);
+impl_comparable!(i32);
+impl_comparable!(u32);
+impl_comparable!(f32);
+
+/**
+ * Expr data types with floating point components, for builtins such as
+ * `sqrt` that are only defined on floats.
+ */
+pub trait ExprFloatDataType: ExprNumericDataType {}
+
+impl ExprFloatDataType for f32 {}
+impl ExprFloatDataType for [f32; 2] {}
+impl ExprFloatDataType for [f32; 3] {}
+impl ExprFloatDataType for [f32; 4] {}
+
 /**
  * Expr data types that are scalar.
  */
@@ -165,7 +400,11 @@ impl ExprVectorNumericDataType for [f32; 4] {
 /**
  * Expr data types that are numeric in nature (i.e. are field data types).
  */
-pub trait ExprIntegralDataType: ExprNumericDataType {
+pub trait ExprIntegralDataType:
+  ExprNumericDataType
+  + ExprBitcastableTo<Self::AsUnsigned>
+  + ExprBitcastableTo<Self::AsSigned>
+{
   type AsUnsigned: ExprNumericDataType;
   type AsSigned: ExprNumericDataType;
 }
@@ -203,3 +442,47 @@ impl ExprIntegralDataType for [u32; 4] {
   type AsUnsigned = [u32; 4];
   type AsSigned = [i32; 4];
 }
+
+/**
+ * Scalar integral expr data types, usable e.g. as switch selectors.
+ */
+pub trait ExprScalarIntegralDataType:
+  ExprIntegralDataType + ExprScalarNumericDataType + LiteralDataType
+  + PartialEq + fmt::Display
+{}
+
+impl ExprScalarIntegralDataType for i32 {}
+impl ExprScalarIntegralDataType for u32 {}
+
+/**
+ * Expr data types that can be converted by value to `T`, e.g. `f32(x)`.
+ * Vectors convert componentwise to vectors of the same size.
+ */
+pub trait ExprConvertibleTo<T: ExprDataType>: ExprDataType {}
+
+/**
+ * Expr data types whose bits can be reinterpreted as `T`, e.g.
+ * `bitcast<u32>(x)`.
+ */
+pub trait ExprBitcastableTo<T: ExprDataType>: ExprDataType {}
+
+// Macro to implement a conversion trait from scalars and their vectors.
+macro_rules! impl_conversion_to {
+  ($trait:ident, $to:ty, $($from:ty),*) => {
+    $(
+      impl $trait<$to> for $from {}
+      impl $trait<[$to; 2]> for [$from; 2] {}
+      impl $trait<[$to; 3]> for [$from; 3] {}
+      impl $trait<[$to; 4]> for [$from; 4] {}
+    )*
+  };
+}
+
+impl_conversion_to!(ExprConvertibleTo, bool, bool, i32, u32, f32);
+impl_conversion_to!(ExprConvertibleTo, i32, bool, i32, u32, f32);
+impl_conversion_to!(ExprConvertibleTo, u32, bool, i32, u32, f32);
+impl_conversion_to!(ExprConvertibleTo, f32, bool, i32, u32, f32);
+
+impl_conversion_to!(ExprBitcastableTo, i32, i32, u32, f32);
+impl_conversion_to!(ExprBitcastableTo, u32, i32, u32, f32);
+impl_conversion_to!(ExprBitcastableTo, f32, i32, u32, f32);
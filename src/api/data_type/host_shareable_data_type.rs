@@ -1,7 +1,15 @@
-use crate::api::data_type::{
-  ExprDataType,
-  Struct,
-  StructMappedDataType,
+use std::slice;
+use crate::api::{
+  buffer_attributes::BufferMemorySpaceRepr,
+  data_type::{
+    round_up,
+    ExprDataType,
+    Struct,
+    StructFieldDataType,
+    StructFieldLayout,
+    StructFieldVisitor,
+    StructMappedDataType,
+  },
 };
 
 /**
@@ -10,30 +18,332 @@ use crate::api::data_type::{
  * | Host-shareable types are used to describe the contents of buffers which are
  * | shared between the host and the GPU, or copied between host and GPU without
  * | format translation. When used for this purpose, the type may additionally
- * | have layout attributes applied as described in § 13.4 Memory Layout. As
- * | described in § 7.3 var Declarations, the store type of uniform buffer and
+ * | have layout attributes applied as described in § 13.4 Memory Layout. As
+ * | described in § 7.3 var Declarations, the store type of uniform buffer and
  * | storage buffer variables must be host-shareable.
+ *
+ * Values are packed to and from bytes following the wgsl memory layout rules,
+ * so that host values can be copied directly into buffers.  Struct layouts
+ * depend on the memory space of the buffer, see `StructDataTypeRepr::layout`.
+ * The alignment and size given here are those of the storage space.
  */
-pub trait HostShareableDataType: ExprDataType {}
+pub trait HostShareableDataType: ExprDataType {
+  /** The alignment of the type in buffer memory, in bytes. */
+  fn alignment() -> usize;
 
-impl HostShareableDataType for i32 {}
-impl HostShareableDataType for [i32; 2] {}
-impl HostShareableDataType for [i32; 3] {}
-impl HostShareableDataType for [i32; 4] {}
+  /** The size of the type in buffer memory, in bytes. */
+  fn size() -> usize;
 
-impl HostShareableDataType for u32 {}
-impl HostShareableDataType for [u32; 2] {}
-impl HostShareableDataType for [u32; 3] {}
-impl HostShareableDataType for [u32; 4] {}
+  /** The distance between consecutive elements in an array of the type. */
+  fn array_stride() -> usize {
+    round_up(Self::alignment(), Self::size())
+  }
 
-impl HostShareableDataType for f32 {}
-impl HostShareableDataType for [f32; 2] {}
-impl HostShareableDataType for [f32; 3] {}
-impl HostShareableDataType for [f32; 4] {}
+  /** Pack the value into the start of the given bytes. */
+  fn write_bytes(&self, bytes: &mut [u8]) {
+    self.write_bytes_in(BufferMemorySpaceRepr::Storage, bytes);
+  }
 
+  /** Update the value from the start of the given bytes. */
+  fn read_bytes(&mut self, bytes: &[u8]) {
+    self.read_bytes_in(BufferMemorySpaceRepr::Storage, bytes);
+  }
+
+  /** Pack the value, laid out for the given memory space. */
+  fn write_bytes_in(&self,
+    memory_space: BufferMemorySpaceRepr,
+    bytes: &mut [u8],
+  );
+
+  /** Update the value from bytes laid out for the given memory space. */
+  fn read_bytes_in(&mut self,
+    memory_space: BufferMemorySpaceRepr,
+    bytes: &[u8],
+  );
+}
+
+/**
+ * Host-shareable data types whose values can be loaded and stored directly
+ * in shaders: all but atomics, and arrays of them, which are accessed only
+ * through the atomic builtins.
+ *
+ * Structs are loadable whatever their fields, so that the fields of a
+ * struct holding atomics can be referenced.  Assigning such a struct as a
+ * whole is reported as an error instead.
+ *
+ * Reading an element of a buffer of atomics is rejected when compiling:
+ *
+ * ```compile_fail
+ * # use blacklight::{
+ * #   buffer_attributes::BufferReadWrite,
+ * #   builder::literal,
+ * #   data_type::Atomic,
+ * #   handle::{ BufferBindingHandle, ExprHandle },
+ * # };
+ * fn load<'cb>(buffer: &BufferBindingHandle<'cb, Atomic<u32>, BufferReadWrite>)
+ *   -> ExprHandle<'cb, Atomic<u32>>
+ * {
+ *   buffer.read(literal(0))
+ * }
+ * ```
+ *
+ * As is assigning one:
+ *
+ * ```compile_fail
+ * # use blacklight::{
+ * #   buffer_attributes::BufferReadWrite,
+ * #   builder::{ literal, zero_value, CodeBlockBuilder },
+ * #   data_type::Atomic,
+ * #   handle::BufferBindingHandle,
+ * # };
+ * fn store<'cb>(
+ *   cbb: &mut CodeBlockBuilder<'cb, 'cb, ()>,
+ *   buffer: &BufferBindingHandle<'cb, Atomic<u32>, BufferReadWrite>,
+ * ) {
+ *   cbb.add_assignment_statement(&buffer.elem(literal(0)), zero_value());
+ * }
+ * ```
+ */
+pub trait ExprLoadableDataType: HostShareableDataType {}
+
+// Macro to implement HostShareableDataType for scalar types.
+macro_rules! impl_host_shareable_scalar {
+  ($($ty:ty),*) => {
+    $(
+      impl HostShareableDataType for $ty {
+        fn alignment() -> usize {
+          4
+        }
+        fn size() -> usize {
+          4
+        }
+        fn write_bytes_in(&self,
+          _memory_space: BufferMemorySpaceRepr,
+          bytes: &mut [u8],
+        ) {
+          bytes[..4].copy_from_slice(&self.to_le_bytes());
+        }
+        fn read_bytes_in(&mut self,
+          _memory_space: BufferMemorySpaceRepr,
+          bytes: &[u8],
+        ) {
+          *self = <$ty>::from_le_bytes(bytes[..4].try_into().unwrap());
+        }
+      }
+      impl ExprLoadableDataType for $ty {}
+      impl StructFieldDataType for $ty {
+        type DataType = $ty;
+        fn to_data_type(self) -> Self::DataType {
+          self
+        }
+        fn from_data_type(data_type: Self::DataType) -> Self {
+          data_type
+        }
+      }
+    )*
+  };
+}
+
+// Macro to implement HostShareableDataType for vector types.
+macro_rules! impl_host_shareable_vector {
+  ($(($scalar:ty, $dims:expr, $align:expr)),*) => {
+    $(
+      impl HostShareableDataType for [$scalar; $dims] {
+        fn alignment() -> usize {
+          $align
+        }
+        fn size() -> usize {
+          $dims * 4
+        }
+        fn write_bytes_in(&self,
+          _memory_space: BufferMemorySpaceRepr,
+          bytes: &mut [u8],
+        ) {
+          for (i, component) in self.iter().enumerate() {
+            component.write_bytes(&mut bytes[i * 4 ..]);
+          }
+        }
+        fn read_bytes_in(&mut self,
+          _memory_space: BufferMemorySpaceRepr,
+          bytes: &[u8],
+        ) {
+          for (i, component) in self.iter_mut().enumerate() {
+            component.read_bytes(&bytes[i * 4 ..]);
+          }
+        }
+      }
+      impl ExprLoadableDataType for [$scalar; $dims] {}
+      impl StructFieldDataType for [$scalar; $dims] {
+        type DataType = [$scalar; $dims];
+        fn to_data_type(self) -> Self::DataType {
+          self
+        }
+        fn from_data_type(data_type: Self::DataType) -> Self {
+          data_type
+        }
+      }
+    )*
+  };
+}
+
+// Macro to implement HostShareableDataType for matrix types.  Columns are
+// packed as vectors, each starting at a multiple of the column alignment.
+macro_rules! impl_host_shareable_matrix {
+  ($(($columns:expr, $rows:expr)),*) => {
+    $(
+      impl HostShareableDataType for [[f32; $rows]; $columns] {
+        fn alignment() -> usize {
+          <[f32; $rows]>::alignment()
+        }
+        fn size() -> usize {
+          $columns * <[f32; $rows]>::array_stride()
+        }
+        fn write_bytes_in(&self,
+          _memory_space: BufferMemorySpaceRepr,
+          bytes: &mut [u8],
+        ) {
+          let stride = <[f32; $rows]>::array_stride();
+          for (i, column) in self.iter().enumerate() {
+            column.write_bytes(&mut bytes[i * stride ..]);
+          }
+        }
+        fn read_bytes_in(&mut self,
+          _memory_space: BufferMemorySpaceRepr,
+          bytes: &[u8],
+        ) {
+          let stride = <[f32; $rows]>::array_stride();
+          for (i, column) in self.iter_mut().enumerate() {
+            column.read_bytes(&bytes[i * stride ..]);
+          }
+        }
+      }
+      impl ExprLoadableDataType for [[f32; $rows]; $columns] {}
+      impl StructFieldDataType for [[f32; $rows]; $columns] {
+        type DataType = [[f32; $rows]; $columns];
+        fn to_data_type(self) -> Self::DataType {
+          self
+        }
+        fn from_data_type(data_type: Self::DataType) -> Self {
+          data_type
+        }
+      }
+    )*
+  };
+}
+
+impl_host_shareable_scalar!(i32, u32, f32);
+
+impl_host_shareable_vector!(
+  (i32, 2, 8),
+  (i32, 3, 16),
+  (i32, 4, 16),
+
+  (u32, 2, 8),
+  (u32, 3, 16),
+  (u32, 4, 16),
+
+  (f32, 2, 8),
+  (f32, 3, 16),
+  (f32, 4, 16)
+);
+
+impl_host_shareable_matrix!(
+  (2, 2), (2, 3), (2, 4),
+  (3, 2), (3, 3), (3, 4),
+  (4, 2), (4, 3), (4, 4)
+);
+
+impl<T> ExprLoadableDataType for Struct<T>
+  where T: Copy + StructMappedDataType
+{
+}
 impl<T> HostShareableDataType for Struct<T>
   where T: Copy + StructMappedDataType
-{}
+{
+  fn alignment() -> usize {
+    Self::make_struct_repr().layout(BufferMemorySpaceRepr::Storage).alignment()
+  }
+
+  fn size() -> usize {
+    Self::make_struct_repr().layout(BufferMemorySpaceRepr::Storage).size()
+  }
+
+  fn write_bytes_in(&self,
+    memory_space: BufferMemorySpaceRepr,
+    bytes: &mut [u8],
+  ) {
+    let layout = Self::make_struct_repr().layout(memory_space);
+    let mut visitor = WriteBytesVisitor {
+      value: self.data(),
+      bytes,
+      memory_space,
+      fields: layout.fields().iter(),
+    };
+    T::visit_fields(&mut visitor);
+  }
+
+  fn read_bytes_in(&mut self,
+    memory_space: BufferMemorySpaceRepr,
+    bytes: &[u8],
+  ) {
+    let layout = Self::make_struct_repr().layout(memory_space);
+    let mut visitor = ReadBytesVisitor {
+      value: self.data_mut(),
+      bytes,
+      memory_space,
+      fields: layout.fields().iter(),
+    };
+    T::visit_fields(&mut visitor);
+  }
+}
+
+/**
+ * Visitor that packs struct fields at the offsets given by a struct layout.
+ * Fields are visited in declaration order, matching the layout's fields.
+ */
+struct WriteBytesVisitor<'a, T> {
+  value: &'a T,
+  bytes: &'a mut [u8],
+  memory_space: BufferMemorySpaceRepr,
+  fields: slice::Iter<'a, StructFieldLayout>,
+}
+impl<'a, T> StructFieldVisitor<T> for WriteBytesVisitor<'a, T>
+  where T: StructMappedDataType
+{
+  fn visit_field<DT, GET, SET>(&mut self, _name: &str, get: GET, _set: SET)
+    where DT: HostShareableDataType,
+          GET: Fn(&T) -> DT,
+          SET: Fn(&mut T, DT)
+  {
+    let offset = self.fields.next().expect("Field missing from layout").offset();
+    get(self.value).write_bytes_in(self.memory_space, &mut self.bytes[offset..]);
+  }
+}
+
+/**
+ * Visitor that unpacks struct fields from the offsets given by a struct
+ * layout.
+ */
+struct ReadBytesVisitor<'a, T> {
+  value: &'a mut T,
+  bytes: &'a [u8],
+  memory_space: BufferMemorySpaceRepr,
+  fields: slice::Iter<'a, StructFieldLayout>,
+}
+impl<'a, T> StructFieldVisitor<T> for ReadBytesVisitor<'a, T>
+  where T: StructMappedDataType
+{
+  fn visit_field<DT, GET, SET>(&mut self, _name: &str, get: GET, set: SET)
+    where DT: HostShareableDataType,
+          GET: Fn(&T) -> DT,
+          SET: Fn(&mut T, DT)
+  {
+    let offset = self.fields.next().expect("Field missing from layout").offset();
+    let mut field_value = get(self.value);
+    field_value.read_bytes_in(self.memory_space, &self.bytes[offset..]);
+    set(self.value, field_value);
+  }
+}
 
 /**
  * A type erasure from static and incorporation into runtime for a literal data type.
@@ -0,0 +1,94 @@
+use crate::model::IdentifierModel;
+
+/** Round `value` up to the next multiple of `align`. */
+pub(crate) fn round_up(align: usize, value: usize) -> usize {
+  value.div_ceil(align) * align
+}
+
+/**
+ * The memory layout of a struct in a particular buffer memory space.
+ * See https://www.w3.org/TR/WGSL/#memory-layouts.
+ *
+ * Produced by `StructDataTypeRepr::layout`.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructLayout {
+  alignment: usize,
+  size: usize,
+  fields: Vec<StructFieldLayout>,
+}
+impl StructLayout {
+  /** Create a new struct layout. */
+  pub(crate) fn new(
+    alignment: usize,
+    size: usize,
+    fields: Vec<StructFieldLayout>,
+  ) -> Self {
+    StructLayout { alignment, size, fields }
+  }
+
+  /** Get the alignment of the struct, in bytes. */
+  pub fn alignment(&self) -> usize {
+    self.alignment
+  }
+
+  /** Get the size of the struct, in bytes. */
+  pub fn size(&self) -> usize {
+    self.size
+  }
+
+  /** Get the layouts of the fields, in declaration order. */
+  pub fn fields(&self) -> &[StructFieldLayout] {
+    &self.fields
+  }
+
+  /** Look up the layout of a particular field. */
+  pub fn get_field(&self, name: &str) -> Option<&StructFieldLayout> {
+    self.fields.iter().find(|field| field.name() == name)
+  }
+}
+
+/**
+ * The memory layout of a struct field.
+ *
+ * The alignment and size are those required of the field by the memory
+ * space, which may exceed the alignment and size of the field's type.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructFieldLayout {
+  name: IdentifierModel,
+  offset: usize,
+  alignment: usize,
+  size: usize,
+}
+impl StructFieldLayout {
+  /** Create a new struct field layout. */
+  pub(crate) fn new(
+    name: IdentifierModel,
+    offset: usize,
+    alignment: usize,
+    size: usize,
+  ) -> Self {
+    StructFieldLayout { name, offset, alignment, size }
+  }
+
+  /** Get the name of the field. */
+  pub fn name(&self) -> &str {
+    self.name.as_str()
+  }
+
+  /** Get the offset of the field from the start of the struct, in bytes. */
+  pub fn offset(&self) -> usize {
+    self.offset
+  }
+
+  /** Get the alignment of the field, in bytes. */
+  pub fn alignment(&self) -> usize {
+    self.alignment
+  }
+
+  /** Get the size of the field, in bytes. */
+  pub fn size(&self) -> usize {
+    self.size
+  }
+}
@@ -1,4 +1,4 @@
-use crate::api::data_type::{ DataTypeRepr, ExprDataType };
+use crate::api::data_type::{ ArrayDataTypeRepr, DataTypeRepr, ExprDataType };
 
 /**
  * Data types that can have literal values in the shader source.
@@ -14,6 +14,22 @@ impl LiteralDataType for bool {
   }
 }
 
+impl LiteralDataType for [bool; 2] {
+  fn to_literal_data_value(&self) -> LiteralDataValue {
+    LiteralDataValue::Vec2Bool(*self)
+  }
+}
+impl LiteralDataType for [bool; 3] {
+  fn to_literal_data_value(&self) -> LiteralDataValue {
+    LiteralDataValue::Vec3Bool(*self)
+  }
+}
+impl LiteralDataType for [bool; 4] {
+  fn to_literal_data_value(&self) -> LiteralDataValue {
+    LiteralDataValue::Vec4Bool(*self)
+  }
+}
+
 impl LiteralDataType for i32 {
   fn to_literal_data_value(&self) -> LiteralDataValue {
     LiteralDataValue::I32(*self)
@@ -77,12 +93,61 @@ impl LiteralDataType for [f32; 4] {
   }
 }
 
+impl LiteralDataType for [[f32; 2]; 2] {
+  fn to_literal_data_value(&self) -> LiteralDataValue {
+    LiteralDataValue::Mat2x2F32(*self)
+  }
+}
+impl LiteralDataType for [[f32; 3]; 2] {
+  fn to_literal_data_value(&self) -> LiteralDataValue {
+    LiteralDataValue::Mat2x3F32(*self)
+  }
+}
+impl LiteralDataType for [[f32; 4]; 2] {
+  fn to_literal_data_value(&self) -> LiteralDataValue {
+    LiteralDataValue::Mat2x4F32(*self)
+  }
+}
+impl LiteralDataType for [[f32; 2]; 3] {
+  fn to_literal_data_value(&self) -> LiteralDataValue {
+    LiteralDataValue::Mat3x2F32(*self)
+  }
+}
+impl LiteralDataType for [[f32; 3]; 3] {
+  fn to_literal_data_value(&self) -> LiteralDataValue {
+    LiteralDataValue::Mat3x3F32(*self)
+  }
+}
+impl LiteralDataType for [[f32; 4]; 3] {
+  fn to_literal_data_value(&self) -> LiteralDataValue {
+    LiteralDataValue::Mat3x4F32(*self)
+  }
+}
+impl LiteralDataType for [[f32; 2]; 4] {
+  fn to_literal_data_value(&self) -> LiteralDataValue {
+    LiteralDataValue::Mat4x2F32(*self)
+  }
+}
+impl LiteralDataType for [[f32; 3]; 4] {
+  fn to_literal_data_value(&self) -> LiteralDataValue {
+    LiteralDataValue::Mat4x3F32(*self)
+  }
+}
+impl LiteralDataType for [[f32; 4]; 4] {
+  fn to_literal_data_value(&self) -> LiteralDataValue {
+    LiteralDataValue::Mat4x4F32(*self)
+  }
+}
+
 /**
  * A type erasure from static and incorporation into runtime for a literal data type.
  */
 #[derive(Clone, Debug)]
 pub enum LiteralDataValue {
   Bool(bool),
+  Vec2Bool([bool; 2]),
+  Vec3Bool([bool; 3]),
+  Vec4Bool([bool; 4]),
   I32(i32),
   Vec2I32([i32; 2]),
   Vec3I32([i32; 3]),
@@ -95,12 +160,25 @@ pub enum LiteralDataValue {
   Vec2F32([f32; 2]),
   Vec3F32([f32; 3]),
   Vec4F32([f32; 4]),
+  Mat2x2F32([[f32; 2]; 2]),
+  Mat2x3F32([[f32; 3]; 2]),
+  Mat2x4F32([[f32; 4]; 2]),
+  Mat3x2F32([[f32; 2]; 3]),
+  Mat3x3F32([[f32; 3]; 3]),
+  Mat3x4F32([[f32; 4]; 3]),
+  Mat4x2F32([[f32; 2]; 4]),
+  Mat4x3F32([[f32; 3]; 4]),
+  Mat4x4F32([[f32; 4]; 4]),
+  Array(ArrayDataTypeRepr, Vec<LiteralDataValue>),
 }
 impl LiteralDataValue {
   /** Get the datatype for this value. */
   pub fn data_type_repr(&self) -> DataTypeRepr {
     match self {
       LiteralDataValue::Bool(_) => DataTypeRepr::new_bool(),
+      LiteralDataValue::Vec2Bool(_) => DataTypeRepr::new_vec2_bool(),
+      LiteralDataValue::Vec3Bool(_) => DataTypeRepr::new_vec3_bool(),
+      LiteralDataValue::Vec4Bool(_) => DataTypeRepr::new_vec4_bool(),
       LiteralDataValue::I32(_) => DataTypeRepr::new_i32(),
       LiteralDataValue::Vec2I32(_) => DataTypeRepr::new_vec2_i32(),
       LiteralDataValue::Vec3I32(_) => DataTypeRepr::new_vec3_i32(),
@@ -113,6 +191,17 @@ impl LiteralDataValue {
       LiteralDataValue::Vec2F32(_) => DataTypeRepr::new_vec2_f32(),
       LiteralDataValue::Vec3F32(_) => DataTypeRepr::new_vec3_f32(),
       LiteralDataValue::Vec4F32(_) => DataTypeRepr::new_vec4_f32(),
+      LiteralDataValue::Mat2x2F32(_) => DataTypeRepr::new_mat2x2_f32(),
+      LiteralDataValue::Mat2x3F32(_) => DataTypeRepr::new_mat2x3_f32(),
+      LiteralDataValue::Mat2x4F32(_) => DataTypeRepr::new_mat2x4_f32(),
+      LiteralDataValue::Mat3x2F32(_) => DataTypeRepr::new_mat3x2_f32(),
+      LiteralDataValue::Mat3x3F32(_) => DataTypeRepr::new_mat3x3_f32(),
+      LiteralDataValue::Mat3x4F32(_) => DataTypeRepr::new_mat3x4_f32(),
+      LiteralDataValue::Mat4x2F32(_) => DataTypeRepr::new_mat4x2_f32(),
+      LiteralDataValue::Mat4x3F32(_) => DataTypeRepr::new_mat4x3_f32(),
+      LiteralDataValue::Mat4x4F32(_) => DataTypeRepr::new_mat4x4_f32(),
+      LiteralDataValue::Array(array_data_type, _) =>
+        DataTypeRepr::new_array(array_data_type.clone()),
     }
   }
 
@@ -122,6 +211,15 @@ impl LiteralDataValue {
       LiteralDataValue::Bool(b) => {
         (if *b { "true" } else { "false" }).to_string()
       },
+      LiteralDataValue::Vec2Bool([x, y]) => {
+        format!("vec2<bool>({}, {})", x, y)
+      },
+      LiteralDataValue::Vec3Bool([x, y, z]) => {
+        format!("vec3<bool>({}, {}, {})", x, y, z)
+      },
+      LiteralDataValue::Vec4Bool([x, y, z, w]) => {
+        format!("vec4<bool>({}, {}, {}, {})", x, y, z, w)
+      },
       LiteralDataValue::I32(int32) => {
         format!("{}i", int32)
       },
@@ -158,6 +256,55 @@ impl LiteralDataValue {
       LiteralDataValue::Vec4F32([x, y, z, w]) => {
         format!("vec4<f32>({}, {}, {}, {})", x, y, z, w)
       },
+      LiteralDataValue::Mat2x2F32(columns) => {
+        matrix_wgsl_source(columns)
+      },
+      LiteralDataValue::Mat2x3F32(columns) => {
+        matrix_wgsl_source(columns)
+      },
+      LiteralDataValue::Mat2x4F32(columns) => {
+        matrix_wgsl_source(columns)
+      },
+      LiteralDataValue::Mat3x2F32(columns) => {
+        matrix_wgsl_source(columns)
+      },
+      LiteralDataValue::Mat3x3F32(columns) => {
+        matrix_wgsl_source(columns)
+      },
+      LiteralDataValue::Mat3x4F32(columns) => {
+        matrix_wgsl_source(columns)
+      },
+      LiteralDataValue::Mat4x2F32(columns) => {
+        matrix_wgsl_source(columns)
+      },
+      LiteralDataValue::Mat4x3F32(columns) => {
+        matrix_wgsl_source(columns)
+      },
+      LiteralDataValue::Mat4x4F32(columns) => {
+        matrix_wgsl_source(columns)
+      },
+      LiteralDataValue::Array(array_data_type, elements) => {
+        let elements = elements.iter()
+          .map(|element| element.wgsl_source())
+          .collect::<Vec<_>>();
+        format!("{}({})",
+          DataTypeRepr::new_array(array_data_type.clone()).wgsl_source(),
+          elements.join(", "),
+        )
+      },
     }
   }
 }
+
+/**
+ * Generate a wgsl source string for a matrix literal, from its columns.
+ * Components are listed in column-major order, e.g.
+ * `mat2x2<f32>(1f, 0f, 0f, 1f)`.
+ */
+fn matrix_wgsl_source<const R: usize>(columns: &[[f32; R]]) -> String {
+  let components = columns.iter()
+    .flatten()
+    .map(|component| format!("{}f", component))
+    .collect::<Vec<_>>();
+  format!("mat{}x{}<f32>({})", columns.len(), R, components.join(", "))
+}
@@ -1,37 +1,71 @@
 
 mod arg_data_type;
+mod array_data_type;
+mod atomic_data_type;
 mod host_shareable_data_type;
+mod layout;
 mod ep_arg_data_type;
 mod expr_data_type;
 mod literal_data_type;
+mod override_data_type;
 mod proc_result_type;
 mod struct_data_type;
+mod vector_component;
 
 mod repr;
 
 pub use self::{
   arg_data_type::{ ArgTupleDataType, ArgTupleHandleMap, ArgTupleHandleVisitor },
+  array_data_type::{ Array, RuntimeArray },
+  atomic_data_type::{
+    Atomic,
+    AtomicCompareExchangeResult,
+    ExprAtomicScalarDataType,
+  },
   ep_arg_data_type::EntryPointArgDataType,
   expr_data_type::{
+    ExprBitcastableTo,
+    ExprBoolDataType,
+    ExprComparableDataType,
+    ExprConvertibleTo,
     ExprDataType,
+    ExprFloatDataType,
     ExprNumericDataType,
+    ExprIndexableDataType,
     ExprIntegralDataType,
+    ExprMatrixDataType,
+    ExprScalarIntegralDataType,
     ExprScalarNumericDataType,
+    ExprSquareMatrixDataType,
+    ExprVectorDataType,
     ExprVectorNumericDataType,
   },
-  host_shareable_data_type::{ HostShareableDataType, BufferDataValue },
+  host_shareable_data_type::{
+    BufferDataValue,
+    ExprLoadableDataType,
+    HostShareableDataType,
+  },
+  layout::{ StructLayout, StructFieldLayout },
   literal_data_type::{ LiteralDataType, LiteralDataValue },
+  override_data_type::OverrideDataType,
   proc_result_type::ProcResultType,
   struct_data_type::{
     Struct,
+    StructFieldDataType,
     StructFieldVisitor,
     StructMappedDataType,
   },
+  vector_component::{ ComponentOf, VectorComponent, X, Y, Z, W },
   repr::{
     DataTypeRepr,
+    ArrayDataTypeRepr,
     BuiltinDataTypeRepr,
     StructDataTypeRepr,
     StructFieldRepr,
   },
 };
 
+/** Derive macro for `StructMappedDataType`, see `blacklight_derive`. */
+pub use blacklight_derive::StructMappedDataType;
+
+pub(crate) use self::layout::round_up;
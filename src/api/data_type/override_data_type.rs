@@ -0,0 +1,34 @@
+use crate::api::data_type::LiteralDataType;
+
+/**
+ * Scalar data types of pipeline-overridable constants, i.e. `bool`, `i32`,
+ * `u32` and `f32`.
+ */
+pub trait OverrideDataType: LiteralDataType {
+  /**
+   * Convert to the value of a pipeline constant, as passed in
+   * `wgpu::PipelineCompilationOptions::constants`.
+   */
+  fn to_pipeline_constant(&self) -> f64;
+}
+
+impl OverrideDataType for bool {
+  fn to_pipeline_constant(&self) -> f64 {
+    if *self { 1.0 } else { 0.0 }
+  }
+}
+impl OverrideDataType for i32 {
+  fn to_pipeline_constant(&self) -> f64 {
+    *self as f64
+  }
+}
+impl OverrideDataType for u32 {
+  fn to_pipeline_constant(&self) -> f64 {
+    *self as f64
+  }
+}
+impl OverrideDataType for f32 {
+  fn to_pipeline_constant(&self) -> f64 {
+    *self as f64
+  }
+}
@@ -1,4 +1,16 @@
-use crate::model::IdentifierModel;
+use crate::{
+  api::{
+    buffer_attributes::BufferMemorySpaceRepr,
+    data_type::{ round_up, StructFieldLayout, StructLayout },
+  },
+  model::IdentifierModel,
+};
+
+/** The alignment required of struct-typed fields in the uniform space. */
+const UNIFORM_STRUCT_FIELD_ALIGN: usize = 16;
+
+/** The multiple required of array strides in the uniform space. */
+const UNIFORM_ARRAY_STRIDE_ALIGN: usize = 16;
 
 /**
  * Representation of a data type.
@@ -7,6 +19,7 @@ use crate::model::IdentifierModel;
 pub enum DataTypeRepr {
   Builtin(BuiltinDataTypeRepr),
   Struct(StructDataTypeRepr),
+  Array(ArrayDataTypeRepr),
 }
 impl DataTypeRepr {
   /** Create a new data type representation. */
@@ -21,6 +34,15 @@ impl DataTypeRepr {
   pub fn new_bool() -> Self {
     DataTypeRepr::new_builtin(BuiltinDataTypeRepr::Bool)
   }
+  pub fn new_vec2_bool() -> Self {
+    DataTypeRepr::new_builtin(BuiltinDataTypeRepr::Vec2Bool)
+  }
+  pub fn new_vec3_bool() -> Self {
+    DataTypeRepr::new_builtin(BuiltinDataTypeRepr::Vec3Bool)
+  }
+  pub fn new_vec4_bool() -> Self {
+    DataTypeRepr::new_builtin(BuiltinDataTypeRepr::Vec4Bool)
+  }
   pub fn new_i32() -> Self {
     DataTypeRepr::new_builtin(BuiltinDataTypeRepr::I32)
   }
@@ -57,18 +79,65 @@ impl DataTypeRepr {
   pub fn new_vec4_f32() -> Self {
     DataTypeRepr::new_builtin(BuiltinDataTypeRepr::Vec4F32)
   }
+  pub fn new_mat2x2_f32() -> Self {
+    DataTypeRepr::new_builtin(BuiltinDataTypeRepr::Mat2x2F32)
+  }
+  pub fn new_mat2x3_f32() -> Self {
+    DataTypeRepr::new_builtin(BuiltinDataTypeRepr::Mat2x3F32)
+  }
+  pub fn new_mat2x4_f32() -> Self {
+    DataTypeRepr::new_builtin(BuiltinDataTypeRepr::Mat2x4F32)
+  }
+  pub fn new_mat3x2_f32() -> Self {
+    DataTypeRepr::new_builtin(BuiltinDataTypeRepr::Mat3x2F32)
+  }
+  pub fn new_mat3x3_f32() -> Self {
+    DataTypeRepr::new_builtin(BuiltinDataTypeRepr::Mat3x3F32)
+  }
+  pub fn new_mat3x4_f32() -> Self {
+    DataTypeRepr::new_builtin(BuiltinDataTypeRepr::Mat3x4F32)
+  }
+  pub fn new_mat4x2_f32() -> Self {
+    DataTypeRepr::new_builtin(BuiltinDataTypeRepr::Mat4x2F32)
+  }
+  pub fn new_mat4x3_f32() -> Self {
+    DataTypeRepr::new_builtin(BuiltinDataTypeRepr::Mat4x3F32)
+  }
+  pub fn new_mat4x4_f32() -> Self {
+    DataTypeRepr::new_builtin(BuiltinDataTypeRepr::Mat4x4F32)
+  }
+  pub fn new_atomic_i32() -> Self {
+    DataTypeRepr::new_builtin(BuiltinDataTypeRepr::AtomicI32)
+  }
+  pub fn new_atomic_u32() -> Self {
+    DataTypeRepr::new_builtin(BuiltinDataTypeRepr::AtomicU32)
+  }
+  pub fn new_atomic_compare_exchange_result_i32() -> Self {
+    DataTypeRepr::new_builtin(BuiltinDataTypeRepr::AtomicCompareExchangeResultI32)
+  }
+  pub fn new_atomic_compare_exchange_result_u32() -> Self {
+    DataTypeRepr::new_builtin(BuiltinDataTypeRepr::AtomicCompareExchangeResultU32)
+  }
 
   /** Create a new data type representation. */
   pub(crate) fn new_struct(struct_data_type_repr: StructDataTypeRepr) -> Self {
     DataTypeRepr::Struct(struct_data_type_repr)
   }
 
+  /** Create a new array data type representation. */
+  pub(crate) fn new_array(array_data_type_repr: ArrayDataTypeRepr) -> Self {
+    DataTypeRepr::Array(array_data_type_repr)
+  }
+
   /** Get a wgsl source string representation of this type. */
-  pub(crate) fn wgsl_source<'a>(&'a self) -> &'a str {
-    match self {
+  pub(crate) fn wgsl_source(&self) -> String {
+    let source = match self {
       DataTypeRepr::Builtin(builtin) => match builtin {
         BuiltinDataTypeRepr::Void => "void",
         BuiltinDataTypeRepr::Bool => "bool",
+        BuiltinDataTypeRepr::Vec2Bool => "vec2<bool>",
+        BuiltinDataTypeRepr::Vec3Bool => "vec3<bool>",
+        BuiltinDataTypeRepr::Vec4Bool => "vec4<bool>",
         BuiltinDataTypeRepr::I32 => "i32",
         BuiltinDataTypeRepr::Vec2I32 => "vec2<i32>",
         BuiltinDataTypeRepr::Vec3I32 => "vec3<i32>",
@@ -81,16 +150,123 @@ impl DataTypeRepr {
         BuiltinDataTypeRepr::Vec2F32 => "vec2<f32>",
         BuiltinDataTypeRepr::Vec3F32 => "vec3<f32>",
         BuiltinDataTypeRepr::Vec4F32 => "vec4<f32>",
+        BuiltinDataTypeRepr::Mat2x2F32 => "mat2x2<f32>",
+        BuiltinDataTypeRepr::Mat2x3F32 => "mat2x3<f32>",
+        BuiltinDataTypeRepr::Mat2x4F32 => "mat2x4<f32>",
+        BuiltinDataTypeRepr::Mat3x2F32 => "mat3x2<f32>",
+        BuiltinDataTypeRepr::Mat3x3F32 => "mat3x3<f32>",
+        BuiltinDataTypeRepr::Mat3x4F32 => "mat3x4<f32>",
+        BuiltinDataTypeRepr::Mat4x2F32 => "mat4x2<f32>",
+        BuiltinDataTypeRepr::Mat4x3F32 => "mat4x3<f32>",
+        BuiltinDataTypeRepr::Mat4x4F32 => "mat4x4<f32>",
+        BuiltinDataTypeRepr::AtomicI32 => "atomic<i32>",
+        BuiltinDataTypeRepr::AtomicU32 => "atomic<u32>",
+        BuiltinDataTypeRepr::AtomicCompareExchangeResultI32 =>
+          "__atomic_compare_exchange_result<i32>",
+        BuiltinDataTypeRepr::AtomicCompareExchangeResultU32 =>
+          "__atomic_compare_exchange_result<u32>",
       },
       DataTypeRepr::Struct(struct_data_type) =>
         struct_data_type.name().as_str(),
+      DataTypeRepr::Array(array_data_type) =>
+        return array_data_type.wgsl_source(),
+    };
+    source.to_string()
+  }
+
+  /**
+   * Check whether this type is predeclared by wgsl, and cannot be spelled
+   * out in source.  Declarations of such types must leave them inferred.
+   */
+  pub(crate) fn is_predeclared(&self) -> bool {
+    matches!(self,
+      DataTypeRepr::Builtin(BuiltinDataTypeRepr::AtomicCompareExchangeResultI32) |
+      DataTypeRepr::Builtin(BuiltinDataTypeRepr::AtomicCompareExchangeResultU32)
+    )
+  }
+
+  /**
+   * Get the alignment of this type in the given memory space.
+   *
+   * Panics if the type is not host-shareable.
+   */
+  pub fn alignment(&self, memory_space: BufferMemorySpaceRepr) -> usize {
+    match self {
+      DataTypeRepr::Builtin(builtin) => builtin.alignment(),
+      DataTypeRepr::Struct(struct_data_type) =>
+        struct_data_type.layout(memory_space).alignment(),
+      DataTypeRepr::Array(array_data_type) =>
+        array_data_type.element().alignment(memory_space),
     }
   }
 
-  pub(crate) fn take_struct(self) -> Option<StructDataTypeRepr> {
+  /**
+   * Get the size of this type in the given memory space.
+   * Runtime-sized arrays have no fixed size, and are given a size of 0.
+   *
+   * Panics if the type is not host-shareable.
+   */
+  pub fn size(&self, memory_space: BufferMemorySpaceRepr) -> usize {
     match self {
-      DataTypeRepr::Struct(struct_data_type) => Some(struct_data_type),
-      _ => None,
+      DataTypeRepr::Builtin(builtin) => builtin.size(),
+      DataTypeRepr::Struct(struct_data_type) =>
+        struct_data_type.layout(memory_space).size(),
+      DataTypeRepr::Array(array_data_type) =>
+        array_data_type.length().unwrap_or(0)
+          * array_data_type.stride(memory_space),
+    }
+  }
+
+  /**
+   * Check whether this type is runtime-sized: a runtime-sized array, or a
+   * struct ending in one.
+   */
+  pub fn is_runtime_sized(&self) -> bool {
+    match self {
+      DataTypeRepr::Builtin(_) => false,
+      DataTypeRepr::Struct(struct_data_type) => struct_data_type.fields()
+        .last()
+        .is_some_and(|field| field.data_type().is_runtime_sized()),
+      DataTypeRepr::Array(array_data_type) => array_data_type.length().is_none(),
+    }
+  }
+
+  /** Check whether this type is or holds an atomic, at any depth. */
+  pub fn contains_atomic(&self) -> bool {
+    match self {
+      DataTypeRepr::Builtin(builtin) => matches!(builtin,
+        BuiltinDataTypeRepr::AtomicI32 | BuiltinDataTypeRepr::AtomicU32
+      ),
+      DataTypeRepr::Struct(struct_data_type) => struct_data_type.fields()
+        .iter()
+        .any(|field| field.data_type().contains_atomic()),
+      DataTypeRepr::Array(array_data_type) =>
+        array_data_type.element().contains_atomic(),
+    }
+  }
+
+  /**
+   * Get the runtime-sized array ending this type, along with its offset in
+   * the given memory space, if the type is runtime-sized.
+   */
+  pub(crate) fn runtime_array(&self, memory_space: BufferMemorySpaceRepr)
+    -> Option<(usize, &ArrayDataTypeRepr)>
+  {
+    match self {
+      DataTypeRepr::Builtin(_) => None,
+      DataTypeRepr::Struct(struct_data_type) => {
+        let last_field = struct_data_type.fields().last()?;
+        let (offset, array_data_type) =
+          last_field.data_type().runtime_array(memory_space)?;
+        let field_offset = struct_data_type.layout(memory_space)
+          .fields().last()?
+          .offset();
+        Some((field_offset + offset, array_data_type))
+      },
+      DataTypeRepr::Array(array_data_type) => match array_data_type.length() {
+        Some(_) => None,
+        None => Some((0, array_data_type)),
+      },
     }
   }
 }
@@ -102,10 +278,83 @@ impl DataTypeRepr {
  */
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum BuiltinDataTypeRepr {
-  Void, Bool,
+  Void,
+  Bool, Vec2Bool, Vec3Bool, Vec4Bool,
   I32, Vec2I32, Vec3I32, Vec4I32,
   U32, Vec2U32, Vec3U32, Vec4U32,
   F32, Vec2F32, Vec3F32, Vec4F32,
+  Mat2x2F32, Mat2x3F32, Mat2x4F32,
+  Mat3x2F32, Mat3x3F32, Mat3x4F32,
+  Mat4x2F32, Mat4x3F32, Mat4x4F32,
+  AtomicI32, AtomicU32,
+  AtomicCompareExchangeResultI32, AtomicCompareExchangeResultU32,
+}
+impl BuiltinDataTypeRepr {
+  /**
+   * Get the number of columns and rows of scalar components, if
+   * host-shareable.  Scalars and vectors have a single column.
+   */
+  fn host_shareable_shape(&self) -> Option<(usize, usize)> {
+    match self {
+      BuiltinDataTypeRepr::Void |
+      BuiltinDataTypeRepr::Bool |
+      BuiltinDataTypeRepr::Vec2Bool |
+      BuiltinDataTypeRepr::Vec3Bool |
+      BuiltinDataTypeRepr::Vec4Bool |
+      BuiltinDataTypeRepr::AtomicCompareExchangeResultI32 |
+      BuiltinDataTypeRepr::AtomicCompareExchangeResultU32 => None,
+      BuiltinDataTypeRepr::I32 |
+      BuiltinDataTypeRepr::U32 |
+      BuiltinDataTypeRepr::F32 |
+      BuiltinDataTypeRepr::AtomicI32 |
+      BuiltinDataTypeRepr::AtomicU32 => Some((1, 1)),
+      BuiltinDataTypeRepr::Vec2I32 |
+      BuiltinDataTypeRepr::Vec2U32 |
+      BuiltinDataTypeRepr::Vec2F32 => Some((1, 2)),
+      BuiltinDataTypeRepr::Vec3I32 |
+      BuiltinDataTypeRepr::Vec3U32 |
+      BuiltinDataTypeRepr::Vec3F32 => Some((1, 3)),
+      BuiltinDataTypeRepr::Vec4I32 |
+      BuiltinDataTypeRepr::Vec4U32 |
+      BuiltinDataTypeRepr::Vec4F32 => Some((1, 4)),
+      BuiltinDataTypeRepr::Mat2x2F32 => Some((2, 2)),
+      BuiltinDataTypeRepr::Mat2x3F32 => Some((2, 3)),
+      BuiltinDataTypeRepr::Mat2x4F32 => Some((2, 4)),
+      BuiltinDataTypeRepr::Mat3x2F32 => Some((3, 2)),
+      BuiltinDataTypeRepr::Mat3x3F32 => Some((3, 3)),
+      BuiltinDataTypeRepr::Mat3x4F32 => Some((3, 4)),
+      BuiltinDataTypeRepr::Mat4x2F32 => Some((4, 2)),
+      BuiltinDataTypeRepr::Mat4x3F32 => Some((4, 3)),
+      BuiltinDataTypeRepr::Mat4x4F32 => Some((4, 4)),
+    }
+  }
+
+  fn expect_host_shareable_shape(&self) -> (usize, usize) {
+    match self.host_shareable_shape() {
+      Some(shape) => shape,
+      None => panic!("Data type {:?} is not host-shareable", self),
+    }
+  }
+
+  /**
+   * Get the alignment of this type.  Vec3 types align as vec4, and
+   * matrices align as their column vectors.
+   */
+  fn alignment(&self) -> usize {
+    match self.expect_host_shareable_shape() {
+      (_, 1) => 4,
+      (_, 2) => 8,
+      _ => 16,
+    }
+  }
+
+  /** Get the size of this type.  Matrix columns are padded to their alignment. */
+  fn size(&self) -> usize {
+    match self.expect_host_shareable_shape() {
+      (1, rows) => rows * 4,
+      (columns, rows) => columns * round_up(self.alignment(), rows * 4),
+    }
+  }
 }
 
 /**
@@ -136,6 +385,109 @@ impl StructDataTypeRepr {
   pub fn get_field(&self, name: &str) -> Option<&StructFieldRepr> {
     self.fields.iter().find(|field| field.name() == name)
   }
+
+  /**
+   * Compute the memory layout of the struct in the given memory space.
+   *
+   * Fields are placed in declaration order at the next offset satisfying
+   * their alignment, and the struct size is rounded up to its alignment.
+   * In the uniform space, struct-typed fields are additionally aligned and
+   * sized to multiples of 16 bytes, and array-typed fields aligned to 16.
+   *
+   * Panics if any field is not host-shareable.
+   */
+  pub fn layout(&self, memory_space: BufferMemorySpaceRepr) -> StructLayout {
+    let mut offset = 0;
+    let mut struct_alignment = 1;
+    let mut fields = Vec::with_capacity(self.fields.len());
+    for field in &self.fields {
+      let data_type = field.data_type();
+      let mut alignment = data_type.alignment(memory_space);
+      let mut size = data_type.size(memory_space);
+      if memory_space == BufferMemorySpaceRepr::Uniform {
+        match data_type {
+          DataTypeRepr::Struct(_) => {
+            alignment = round_up(UNIFORM_STRUCT_FIELD_ALIGN, alignment);
+            size = round_up(UNIFORM_STRUCT_FIELD_ALIGN, size);
+          },
+          DataTypeRepr::Array(_) => {
+            alignment = round_up(UNIFORM_STRUCT_FIELD_ALIGN, alignment);
+          },
+          DataTypeRepr::Builtin(_) => {},
+        }
+      }
+      let field_offset = round_up(alignment, offset);
+      fields.push(StructFieldLayout::new(
+        field.name.clone(), field_offset, alignment, size
+      ));
+      offset = field_offset + size;
+      struct_alignment = struct_alignment.max(alignment);
+    }
+    StructLayout::new(
+      struct_alignment,
+      round_up(struct_alignment, offset),
+      fields,
+    )
+  }
+}
+
+/**
+ * Representation of an array type, with a fixed length or runtime-sized.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArrayDataTypeRepr {
+  element: Box<DataTypeRepr>,
+  length: Option<usize>,
+}
+impl ArrayDataTypeRepr {
+  /**
+   * Create a new array data type representation.
+   * Arrays without a length are runtime-sized.
+   */
+  pub fn new(element: DataTypeRepr, length: Option<usize>) -> Self {
+    ArrayDataTypeRepr { element: Box::new(element), length }
+  }
+
+  /** Get the type of the elements. */
+  pub fn element(&self) -> &DataTypeRepr {
+    &self.element
+  }
+
+  /** Get the number of elements, if not runtime-sized. */
+  pub fn length(&self) -> Option<usize> {
+    self.length
+  }
+
+  /** Get the distance between consecutive elements in the given memory space. */
+  pub fn stride(&self, memory_space: BufferMemorySpaceRepr) -> usize {
+    round_up(
+      self.element.alignment(memory_space),
+      self.element.size(memory_space),
+    )
+  }
+
+  /**
+   * Get the stride of this array, or of an array nested in it, which wgsl
+   * does not allow in the uniform space as it is not a multiple of 16.
+   */
+  pub(crate) fn invalid_uniform_stride(&self) -> Option<usize> {
+    let stride = self.stride(BufferMemorySpaceRepr::Uniform);
+    if !stride.is_multiple_of(UNIFORM_ARRAY_STRIDE_ALIGN) {
+      return Some(stride);
+    }
+    match self.element.as_ref() {
+      DataTypeRepr::Array(element) => element.invalid_uniform_stride(),
+      _ => None,
+    }
+  }
+
+  /** Get a wgsl source string representation of this type. */
+  fn wgsl_source(&self) -> String {
+    match self.length {
+      Some(length) => format!("array<{}, {}>", self.element.wgsl_source(), length),
+      None => format!("array<{}>", self.element.wgsl_source()),
+    }
+  }
 }
 
 /**
@@ -154,7 +506,7 @@ impl StructFieldRepr {
 
   /** Get the name of the field. */
   pub fn name(&self) -> &str {
-    &self.name.as_str()
+    self.name.as_str()
   }
 
   /** Get the type of the field. */
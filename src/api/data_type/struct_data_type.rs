@@ -13,6 +13,23 @@ pub trait StructMappedDataType: 'static + Copy + Sized {
     where FV: StructFieldVisitor<Self>;
 }
 
+/**
+ * Rust types that can be used as fields of a struct deriving
+ * `StructMappedDataType`, along with the host-shareable data type each
+ * maps to.  Host-shareable types map to themselves, and derived structs
+ * map to `Struct<T>`.
+ */
+#[diagnostic::on_unimplemented(
+  message = "`{Self}` cannot be used as a field of a StructMappedDataType",
+  label = "not a host-shareable data type",
+  note = "fields must be host-shareable data types, or structs deriving StructMappedDataType"
+)]
+pub trait StructFieldDataType: Copy {
+  type DataType: HostShareableDataType;
+  fn to_data_type(self) -> Self::DataType;
+  fn from_data_type(data_type: Self::DataType) -> Self;
+}
+
 /**
  * A visitor interface for struct fields.
  */
@@ -30,6 +47,7 @@ pub trait StructFieldVisitor<T: StructMappedDataType> {
  * Instead, it is `Struct<T>`.  For this type to be instantiable, T must be
  * Copy and implement StructMappedDataType.
  */
+#[derive(Clone)]
 pub struct Struct<T: StructMappedDataType> {
   // The struct data.
   data: T,
@@ -55,17 +73,31 @@ impl<T: StructMappedDataType> Struct<T> {
   pub fn data(&self) -> &T {
     &self.data
   }
-}
-impl<T: StructMappedDataType> Clone for Struct<T> {
-  fn clone(&self) -> Self {
-    Struct { data: self.data }
+
+  /** Get the underlying data mutably. */
+  pub fn data_mut(&mut self) -> &mut T {
+    &mut self.data
   }
 }
 impl<T: StructMappedDataType> Copy for Struct<T> {
 }
+impl<T: StructMappedDataType + Default> Default for Struct<T> {
+  fn default() -> Self {
+    Struct { data: T::default() }
+  }
+}
+impl<T: StructMappedDataType> StructFieldDataType for Struct<T> {
+  type DataType = Struct<T>;
+  fn to_data_type(self) -> Self::DataType {
+    self
+  }
+  fn from_data_type(data_type: Self::DataType) -> Self {
+    data_type
+  }
+}
 impl<T: StructMappedDataType> From<T> for Struct<T> {
   fn from(data: T) -> Self {
-    Struct { data: data }
+    Struct { data }
   }
 }
 
@@ -0,0 +1,64 @@
+use crate::api::data_type::ExprVectorDataType;
+
+/**
+ * Type-level names of vector components, used to select components and
+ * swizzles of vector expressions, e.g. `v.swizzle2::<Y, X>()`.
+ */
+pub trait VectorComponent {
+  /** The index of the component within a vector. */
+  const INDEX: u32;
+}
+
+/** The first vector component. */
+pub struct X;
+
+/** The second vector component. */
+pub struct Y;
+
+/** The third vector component. */
+pub struct Z;
+
+/** The fourth vector component. */
+pub struct W;
+
+impl VectorComponent for X {
+  const INDEX: u32 = 0;
+}
+impl VectorComponent for Y {
+  const INDEX: u32 = 1;
+}
+impl VectorComponent for Z {
+  const INDEX: u32 = 2;
+}
+impl VectorComponent for W {
+  const INDEX: u32 = 3;
+}
+
+/**
+ * Vector components present in the vector type `V`.
+ */
+#[diagnostic::on_unimplemented(
+  message = "`{Self}` is not a component of `{V}`",
+)]
+pub trait ComponentOf<V: ExprVectorDataType>: VectorComponent {}
+
+// Macro to implement ComponentOf for the vectors of a scalar type.
+macro_rules! impl_component_of {
+  ($($scalar:ty),*) => {
+    $(
+      impl ComponentOf<[$scalar; 2]> for X {}
+      impl ComponentOf<[$scalar; 2]> for Y {}
+
+      impl ComponentOf<[$scalar; 3]> for X {}
+      impl ComponentOf<[$scalar; 3]> for Y {}
+      impl ComponentOf<[$scalar; 3]> for Z {}
+
+      impl ComponentOf<[$scalar; 4]> for X {}
+      impl ComponentOf<[$scalar; 4]> for Y {}
+      impl ComponentOf<[$scalar; 4]> for Z {}
+      impl ComponentOf<[$scalar; 4]> for W {}
+    )*
+  };
+}
+
+impl_component_of!(bool, i32, u32, f32);
@@ -8,12 +8,22 @@ use crate::{
  * Represents a typed entry point within a shader.
  */
 pub struct EntryPoint<T: EntryPointArgDataType> {
-  _model: EntryPointModel,
+  model: EntryPointModel,
   _phantom: PhantomData<T>,
 }
 impl<T: EntryPointArgDataType> EntryPoint<T> {
   /** Create a new entry point. */
   pub(crate) fn new(model: EntryPointModel) -> Self {
-    EntryPoint { _model: model, _phantom: PhantomData }
+    EntryPoint { model, _phantom: PhantomData }
+  }
+
+  /** Get the name of the entry point. */
+  pub fn name(&self) -> &str {
+    self.model.name()
+  }
+}
+impl<T: EntryPointArgDataType> Clone for EntryPoint<T> {
+  fn clone(&self) -> Self {
+    EntryPoint { model: self.model.clone(), _phantom: PhantomData }
   }
 }
@@ -0,0 +1,340 @@
+use std::{
+  error,
+  fmt,
+};
+
+/**
+ * Errors reported while defining a shader, compiling it, binding buffers
+ * to it, or reading buffers back.
+ *
+ * Each error carries the names of the bindings, structs and fields
+ * involved, so that it can be traced back to its source.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlacklightError {
+  /** A buffer binding was defined at the group and index reserved for uniforms. */
+  ReservedBufferBinding {
+    binding: String,
+    group: u32,
+    index: u32,
+  },
+
+  /** A buffer binding was defined at a group and index already in use. */
+  DuplicateBufferBinding {
+    binding: String,
+    existing_binding: String,
+    group: u32,
+    index: u32,
+  },
+
+  /** A buffer binding was defined with a name already in use. */
+  DuplicateBufferBindingName {
+    binding: String,
+  },
+
+  /**
+   * A constant or private variable was initialized with an expression
+   * which is not a wgsl const-expression, e.g. one reading a buffer or
+   * variable.
+   */
+  NonConstExpression {
+    name: String,
+    reason: String,
+  },
+
+  /** An override was defined with a pipeline constant id already in use. */
+  DuplicateOverrideId {
+    name: String,
+    existing_name: String,
+    id: u16,
+  },
+
+  /** A struct field was accessed which the struct does not define. */
+  UnknownStructField {
+    struct_name: String,
+    field: String,
+  },
+
+  /** A struct field was accessed as a different type than it was defined. */
+  StructFieldTypeMismatch {
+    struct_name: String,
+    field: String,
+    field_type: String,
+    accessed_type: String,
+  },
+
+  /** A struct has a runtime-sized array field which is not its last field. */
+  MisplacedRuntimeArray {
+    struct_name: String,
+    field: String,
+  },
+
+  /**
+   * An array field of a struct used in the uniforms has a stride which
+   * is not a multiple of 16, as wgsl requires in the uniform space.
+   */
+  InvalidUniformArrayStride {
+    struct_name: String,
+    field: String,
+    stride: usize,
+  },
+
+  /**
+   * A buffer binding, the uniforms, or a private or function-scope
+   * variable holds atomics, which wgsl allows only in read-write storage
+   * buffers and workgroup variables.
+   */
+  MisplacedAtomic {
+    name: String,
+    memory_space: String,
+  },
+
+  /** A struct used in uniform and storage buffers has different layouts in each. */
+  StructLayoutMismatch {
+    struct_name: String,
+  },
+
+  /** A statement was added where wgsl does not allow it. */
+  MisplacedStatement {
+    statement: String,
+    reason: String,
+  },
+
+  /** A switch statement has several clauses selected by the same case. */
+  DuplicateSwitchCase {
+    case: String,
+  },
+
+  /** A switch statement has no default clause. */
+  MissingSwitchDefault,
+
+  /**
+   * A barrier, or a call to a function containing one, is reached in
+   * non-uniform control flow, e.g. within an if-statement on the global id.
+   */
+  NonUniformBarrier {
+    item: ShaderItem,
+    barrier: String,
+  },
+
+  /**
+   * The generated wgsl failed to parse or validate.
+   *
+   * `item` is the function or entry point the diagnostic was traced to,
+   * and `line` the 1-based line of the generated wgsl it refers to.
+   */
+  InvalidWgsl {
+    item: Option<ShaderItem>,
+    message: String,
+    line: Option<u32>,
+  },
+
+  /** A buffer was bound to a buffer binding the shader does not define. */
+  UnknownBufferBinding {
+    binding: String,
+  },
+
+  /** A buffer was bound to a buffer binding which was already bound. */
+  BufferBindingAlreadyBound {
+    binding: String,
+  },
+
+  /** A buffer binding of the shader was left without a buffer. */
+  UnboundBufferBinding {
+    binding: String,
+  },
+
+  /**
+   * A buffer was bound which holds more elements than its length in the
+   * shader's uniforms can count.
+   */
+  BufferBindingTooLong {
+    binding: String,
+    len: usize,
+  },
+
+  /** A buffer could not be mapped to read it back, e.g. on device loss. */
+  BufferReadFailed {
+    reason: String,
+  },
+
+  /** A value was set for an override the shader does not define. */
+  UnknownOverride {
+    name: String,
+  },
+
+  /** An override was set to a value of a different data type. */
+  OverrideTypeMismatch {
+    name: String,
+    override_type: String,
+    value_type: String,
+  },
+
+  /** An override was set more than once. */
+  OverrideAlreadySet {
+    name: String,
+  },
+
+  /** An override without a default value was left unset. */
+  UnsetOverride {
+    name: String,
+  },
+
+  /**
+   * An override used as a workgroup size has a value which is not a
+   * non-zero `u32`.
+   */
+  InvalidWorkgroupSize {
+    name: String,
+    value: String,
+  },
+
+  /** Several errors were reported while defining a shader. */
+  Multiple(Vec<BlacklightError>),
+}
+impl BlacklightError {
+  /**
+   * Combine a list of errors into a single error.
+   * Returns `None` if the list is empty.
+   */
+  pub(crate) fn from_errors(mut errors: Vec<BlacklightError>)
+    -> Option<BlacklightError>
+  {
+    match errors.len() {
+      0 => None,
+      1 => errors.pop(),
+      _ => Some(BlacklightError::Multiple(errors)),
+    }
+  }
+
+  /** Get the individual errors, flattening any `Multiple` errors. */
+  pub fn errors(&self) -> Vec<&BlacklightError> {
+    match self {
+      BlacklightError::Multiple(errors) =>
+        errors.iter().flat_map(|error| error.errors()).collect(),
+      _ => vec![self],
+    }
+  }
+}
+impl fmt::Display for BlacklightError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      BlacklightError::ReservedBufferBinding { binding, group, index } =>
+        write!(f, "Buffer binding '{}' uses group {} and index {}, \
+                   which are reserved for uniforms",
+               binding, group, index),
+      BlacklightError::DuplicateBufferBinding {
+        binding, existing_binding, group, index
+      } =>
+        write!(f, "Buffer binding '{}' uses group {} and index {}, \
+                   which are already used by buffer binding '{}'",
+               binding, group, index, existing_binding),
+      BlacklightError::DuplicateBufferBindingName { binding } =>
+        write!(f, "Buffer binding '{}' already defined", binding),
+      BlacklightError::NonConstExpression { name, reason } =>
+        write!(f, "Initializer of '{}' is not a const-expression: {}",
+               name, reason),
+      BlacklightError::DuplicateOverrideId { name, existing_name, id } =>
+        write!(f, "Override '{}' uses id {}, which is already used by \
+                   override '{}'",
+               name, id, existing_name),
+      BlacklightError::UnknownStructField { struct_name, field } =>
+        write!(f, "Field '{}' not found in struct {}", field, struct_name),
+      BlacklightError::StructFieldTypeMismatch {
+        struct_name, field, field_type, accessed_type
+      } =>
+        write!(f, "Field '{}' of struct {} has type {}, but was accessed as {}",
+               field, struct_name, field_type, accessed_type),
+      BlacklightError::MisplacedRuntimeArray { struct_name, field } =>
+        write!(f, "Runtime-sized array field '{}' of struct {} \
+                   must be its last field",
+               field, struct_name),
+      BlacklightError::InvalidUniformArrayStride {
+        struct_name, field, stride
+      } =>
+        write!(f, "Array field '{}' of struct {} has stride {}, but arrays \
+                   in uniforms need a stride that is a multiple of 16",
+               field, struct_name, stride),
+      BlacklightError::MisplacedAtomic { name, memory_space } =>
+        write!(f, "'{}' holds atomics in the {} memory space, but atomics \
+                   are only allowed in read-write storage buffers and \
+                   workgroup variables",
+               name, memory_space),
+      BlacklightError::StructLayoutMismatch { struct_name } =>
+        write!(f, "Struct {} is used in uniform and storage buffers, \
+                   but has a different layout in each",
+               struct_name),
+      BlacklightError::MisplacedStatement { statement, reason } =>
+        write!(f, "Statement '{}' is not allowed {}", statement, reason),
+      BlacklightError::DuplicateSwitchCase { case } =>
+        write!(f, "Switch case '{}' selects more than one clause", case),
+      BlacklightError::MissingSwitchDefault =>
+        write!(f, "Switch statement has no default clause"),
+      BlacklightError::NonUniformBarrier { item, barrier } =>
+        write!(f, "Barrier '{}' in {} is reached in non-uniform control flow",
+               barrier, item),
+      BlacklightError::InvalidWgsl { item, message, line } => {
+        write!(f, "Invalid wgsl")?;
+        if let Some(item) = item {
+          write!(f, " in {}", item)?;
+        }
+        if let Some(line) = line {
+          write!(f, " at line {}", line)?;
+        }
+        write!(f, ": {}", message)
+      },
+      BlacklightError::UnknownBufferBinding { binding } =>
+        write!(f, "Buffer binding '{}' not found in shader", binding),
+      BlacklightError::BufferBindingAlreadyBound { binding } =>
+        write!(f, "Buffer binding '{}' already bound", binding),
+      BlacklightError::UnboundBufferBinding { binding } =>
+        write!(f, "Buffer binding '{}' was not bound", binding),
+      BlacklightError::BufferBindingTooLong { binding, len } =>
+        write!(f, "Buffer bound to '{}' holds {} elements, more than a u32 \
+                   can count",
+               binding, len),
+      BlacklightError::BufferReadFailed { reason } =>
+        write!(f, "Failed to read buffer: {}", reason),
+      BlacklightError::UnknownOverride { name } =>
+        write!(f, "Override '{}' not found in shader", name),
+      BlacklightError::OverrideTypeMismatch {
+        name, override_type, value_type
+      } =>
+        write!(f, "Override '{}' has type {}, but was set to a value of {}",
+               name, override_type, value_type),
+      BlacklightError::OverrideAlreadySet { name } =>
+        write!(f, "Override '{}' already set", name),
+      BlacklightError::UnsetOverride { name } =>
+        write!(f, "Override '{}' has no default value and was not set", name),
+      BlacklightError::InvalidWorkgroupSize { name, value } =>
+        write!(f, "Override '{}' is used as a workgroup size, but has value \
+                   {}, which is not a non-zero u32",
+               name, value),
+      BlacklightError::Multiple(errors) => {
+        write!(f, "{} errors defining shader", errors.len())?;
+        for error in errors {
+          write!(f, "\n  {}", error)?;
+        }
+        Ok(())
+      },
+    }
+  }
+}
+impl error::Error for BlacklightError {}
+
+/**
+ * A function or entry point of a shader, identified by name.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShaderItem {
+  Function(String),
+  EntryPoint(String),
+}
+impl fmt::Display for ShaderItem {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ShaderItem::Function(name) => write!(f, "function '{}'", name),
+      ShaderItem::EntryPoint(name) => write!(f, "entry point '{}'", name),
+    }
+  }
+}
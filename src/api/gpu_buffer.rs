@@ -0,0 +1,304 @@
+use std::{
+  fmt,
+  future,
+  marker::PhantomData,
+};
+use futures::{ channel::oneshot, FutureExt };
+use crate::api::{
+  buffer_attributes::BufferMemorySpaceRepr,
+  data_type::{ ArrayDataTypeRepr, HostShareableDataType },
+  BlacklightError,
+  Project,
+};
+
+/**
+ * A typed buffer on a project's device, holding an array of elements
+ * that can be bound to a shader's buffer binding of the same data type.
+ *
+ * Buffers of runtime-sized structs instead hold a single struct, whose
+ * runtime-sized array holds the buffer's elements.  Such buffers are
+ * accessed with `write_singleton` and `write_runtime_array`, and their
+ * `read_*` counterparts.
+ */
+pub struct GpuBuffer<'pr, DT>
+  where DT: HostShareableDataType
+{
+  project: &'pr Project,
+  buffer: wgpu::Buffer,
+  len: usize,
+  _phantom: PhantomData<DT>,
+}
+impl<'pr, DT> GpuBuffer<'pr, DT>
+  where DT: HostShareableDataType
+{
+  /** Create a new buffer holding `len` elements. */
+  pub(crate) fn new(project: &'pr Project, len: usize) -> Self {
+    // Bindings cannot be empty, so always allocate room for an element.
+    let size = Self::byte_size_for(len.max(1)) as u64;
+    let buffer = project.device().create_buffer(&wgpu::BufferDescriptor {
+      label: None,
+      size,
+      usage: wgpu::BufferUsages::STORAGE
+        | wgpu::BufferUsages::COPY_DST
+        | wgpu::BufferUsages::COPY_SRC,
+      mapped_at_creation: false,
+    });
+    GpuBuffer { project, buffer, len, _phantom: PhantomData }
+  }
+
+  /** Get the number of elements in the buffer. */
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /** Check whether the buffer holds no elements. */
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /** Get the size of the buffer contents in bytes. */
+  pub fn byte_size(&self) -> usize {
+    Self::byte_size_for(self.len)
+  }
+
+  /** Get the size in bytes of a buffer holding `len` elements. */
+  fn byte_size_for(len: usize) -> usize {
+    match Self::runtime_array() {
+      Some((offset, array_data_type)) => {
+        let stride = array_data_type.stride(BufferMemorySpaceRepr::Storage);
+        DT::size().max(offset + len * stride)
+      },
+      None => len * DT::array_stride(),
+    }
+  }
+
+  /**
+   * Get the runtime-sized array ending the data type and its offset, if
+   * the data type is runtime-sized.
+   */
+  fn runtime_array() -> Option<(usize, ArrayDataTypeRepr)> {
+    DT::repr().runtime_array(BufferMemorySpaceRepr::Storage)
+      .map(|(offset, array_data_type)| (offset, array_data_type.clone()))
+  }
+
+  /** Get the runtime-sized array, panicking if the data type has none. */
+  fn expect_runtime_array(&self) -> (usize, ArrayDataTypeRepr) {
+    match Self::runtime_array() {
+      Some(runtime_array) => runtime_array,
+      None => panic!("Buffer of {} is not runtime-sized",
+                     DT::repr().wgsl_source()),
+    }
+  }
+
+  /** Panic if the data type is runtime-sized. */
+  fn expect_not_runtime_sized(&self) {
+    if DT::repr().is_runtime_sized() {
+      panic!("Buffer of runtime-sized {} holds a singleton, \
+              see write_singleton and write_runtime_array",
+             DT::repr().wgsl_source());
+    }
+  }
+
+  /** Get the underlying wgpu buffer. */
+  pub fn buffer(&self) -> &wgpu::Buffer {
+    &self.buffer
+  }
+
+  /**
+   * Write elements into the start of the buffer.
+   *
+   * Panics if there are more elements than the buffer holds.
+   */
+  pub fn write_from_slice(&self, data: &[DT]) {
+    self.expect_not_runtime_sized();
+    if data.len() > self.len {
+      panic!("Cannot write {} elements into a buffer of {} elements",
+             data.len(), self.len);
+    }
+    if data.is_empty() {
+      return;
+    }
+    let stride = DT::array_stride();
+    let mut bytes = vec![0_u8; data.len() * stride];
+    for (elem, elem_bytes) in data.iter().zip(bytes.chunks_exact_mut(stride)) {
+      elem.write_bytes(elem_bytes);
+    }
+    self.project.queue().write_buffer(&self.buffer, 0, &bytes);
+  }
+
+  /**
+   * Read the elements of the buffer back to the host, once the device has
+   * finished writing them.
+   *
+   * Fails if the buffer could not be mapped for reading, e.g. because the
+   * device was lost.
+   */
+  pub async fn read_to_vec(&self) -> Result<Vec<DT>, BlacklightError>
+    where DT: Default
+  {
+    self.expect_not_runtime_sized();
+    if self.is_empty() {
+      return Ok(Vec::new());
+    }
+    let bytes = self.read_bytes().await?;
+    let elems = bytes.chunks_exact(DT::array_stride())
+      .map(|elem_bytes| {
+        let mut elem = DT::default();
+        elem.read_bytes(elem_bytes);
+        elem
+      })
+      .collect();
+    Ok(elems)
+  }
+
+  /**
+   * Write the fields of the struct held by a buffer of a runtime-sized
+   * struct, other than its runtime-sized array.
+   *
+   * Panics if the data type is not runtime-sized.
+   */
+  pub fn write_singleton(&self, value: &DT) {
+    let (offset, _) = self.expect_runtime_array();
+    let mut bytes = vec![0_u8; offset];
+    value.write_bytes(&mut bytes);
+    if !bytes.is_empty() {
+      self.project.queue().write_buffer(&self.buffer, 0, &bytes);
+    }
+  }
+
+  /**
+   * Read back the fields of the struct held by a buffer of a runtime-sized
+   * struct, other than its runtime-sized array.  Fails like `read_to_vec`.
+   *
+   * Panics if the data type is not runtime-sized.
+   */
+  pub async fn read_singleton(&self) -> Result<DT, BlacklightError>
+    where DT: Default
+  {
+    self.expect_runtime_array();
+    let bytes = self.read_bytes().await?;
+    let mut value = DT::default();
+    value.read_bytes(&bytes);
+    Ok(value)
+  }
+
+  /**
+   * Write elements into the start of the runtime-sized array of a buffer
+   * of a runtime-sized struct.
+   *
+   * Panics if the data type is not runtime-sized, if the elements are of
+   * a different type than the array's, or if there are more elements than
+   * the buffer holds.
+   */
+  pub fn write_runtime_array<T>(&self, data: &[T])
+    where T: HostShareableDataType
+  {
+    let (offset, array_data_type) = self.expect_runtime_array();
+    Self::check_runtime_array_element::<T>(&array_data_type);
+    if data.len() > self.len {
+      panic!("Cannot write {} elements into a buffer of {} elements",
+             data.len(), self.len);
+    }
+    if data.is_empty() {
+      return;
+    }
+    let stride = array_data_type.stride(BufferMemorySpaceRepr::Storage);
+    let mut bytes = vec![0_u8; data.len() * stride];
+    for (elem, elem_bytes) in data.iter().zip(bytes.chunks_exact_mut(stride)) {
+      elem.write_bytes(elem_bytes);
+    }
+    self.project.queue().write_buffer(&self.buffer, offset as u64, &bytes);
+  }
+
+  /**
+   * Read back the elements of the runtime-sized array of a buffer of a
+   * runtime-sized struct.  Fails like `read_to_vec`.
+   *
+   * Panics if the data type is not runtime-sized, or if the elements are
+   * of a different type than the array's.
+   */
+  pub async fn read_runtime_array<T>(&self) -> Result<Vec<T>, BlacklightError>
+    where T: HostShareableDataType + Default
+  {
+    let (offset, array_data_type) = self.expect_runtime_array();
+    Self::check_runtime_array_element::<T>(&array_data_type);
+    let stride = array_data_type.stride(BufferMemorySpaceRepr::Storage);
+    let bytes = self.read_bytes().await?;
+    let elems = bytes[offset ..].chunks_exact(stride)
+      .take(self.len)
+      .map(|elem_bytes| {
+        let mut elem = T::default();
+        elem.read_bytes(elem_bytes);
+        elem
+      })
+      .collect();
+    Ok(elems)
+  }
+
+  /** Panic if `T` is not the element type of the runtime-sized array. */
+  fn check_runtime_array_element<T>(array_data_type: &ArrayDataTypeRepr)
+    where T: HostShareableDataType
+  {
+    if array_data_type.element() != &T::repr() {
+      panic!("Runtime-sized array holds {}, but was accessed as {}",
+             array_data_type.element().wgsl_source(), T::repr().wgsl_source());
+    }
+  }
+
+  /**
+   * Copy the buffer contents back to the host.
+   *
+   * The device is polled without blocking each time the returned future
+   * is polled, until the staging buffer is mapped.
+   */
+  async fn read_bytes(&self) -> Result<Vec<u8>, BlacklightError> {
+    let device = self.project.device();
+    let size = self.buffer.size();
+    let staging = device.create_buffer(&wgpu::BufferDescriptor {
+      label: None,
+      size,
+      usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+      mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(
+      &wgpu::CommandEncoderDescriptor::default()
+    );
+    encoder.copy_buffer_to_buffer(&self.buffer, 0, &staging, 0, size);
+    self.project.queue().submit(Some(encoder.finish()));
+
+    let (sender, mut receiver) = oneshot::channel();
+    staging.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+      let _ = sender.send(result);
+    });
+    let mapped = future::poll_fn(|cx| {
+      device.poll(wgpu::Maintain::Poll);
+      let poll = receiver.poll_unpin(cx);
+      if poll.is_pending() {
+        cx.waker().wake_by_ref();
+      }
+      poll
+    }).await;
+    let reason = match mapped {
+      Ok(Ok(())) => None,
+      Ok(Err(error)) => Some(error.to_string()),
+      Err(_) => Some("mapping was cancelled".to_string()),
+    };
+    if let Some(reason) = reason {
+      return Err(BlacklightError::BufferReadFailed { reason });
+    }
+
+    let bytes = staging.slice(..).get_mapped_range().to_vec();
+    staging.unmap();
+    Ok(bytes)
+  }
+}
+impl<'pr, DT> fmt::Debug for GpuBuffer<'pr, DT>
+  where DT: HostShareableDataType
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("GpuBuffer")
+      .field("data_type", &DT::repr())
+      .field("len", &self.len)
+      .finish()
+  }
+}
@@ -0,0 +1,195 @@
+use crate::{
+  api::{
+    buffer_attributes::BufferReadWrite,
+    data_type::{
+      Atomic,
+      AtomicCompareExchangeResult,
+      ExprAtomicScalarDataType,
+      ExprDataType,
+    },
+    handle::{ BufferBindingHandle, ExprHandle, LvalueHandle },
+  },
+  model::{
+    BuiltinCallExprModel,
+    BuiltinFn,
+    ExpressionModel,
+    IdentifierModel,
+    ReferenceExprModel,
+    StructFieldReadModel,
+    UnaryOp,
+    UnaryOpExprModel,
+  },
+};
+
+/*
+ * Atomic builtins over atomic lvalues: elements of read-write buffers of
+ * atomics, and atomic fields of structs held by such buffers.
+ *
+ * Every builtin returns an expression.  Its side effect happens where the
+ * expression is evaluated, so an expression should be used exactly once,
+ * e.g. with `add_expr_statement` when its value is not needed.
+ */
+
+// Build a call to an atomic builtin, passing `&target` first.
+#[allow(clippy::vec_box)]
+fn make_atomic_call<'cb, T, RES>(
+  function: BuiltinFn,
+  target: &LvalueHandle<'cb, Atomic<T>>,
+  mut arguments: Vec<Box<ExpressionModel>>,
+) -> ExprHandle<'cb, RES>
+  where T: ExprAtomicScalarDataType, RES: ExprDataType
+{
+  let reference_model = ReferenceExprModel::new(target.model().clone());
+  let address_model = UnaryOpExprModel::new(
+    Box::new(ExpressionModel::Reference(reference_model)),
+    UnaryOp::AddressOf,
+  );
+  arguments.insert(0, Box::new(ExpressionModel::UnaryOp(address_model)));
+  let builtin_call_model =
+    BuiltinCallExprModel::new(function, arguments, RES::repr());
+  ExprHandle::new(Box::new(ExpressionModel::BuiltinCall(builtin_call_model)))
+}
+
+// Read-modify-write builtins, taking a value and returning the original.
+macro_rules! impl_atomic_rmw_lvalue {
+  ($($name:ident => $function:ident, $doc:literal;)*) => {
+    $(
+      #[doc = $doc]
+      pub fn $name(&self, value: ExprHandle<'cb, T>) -> ExprHandle<'cb, T> {
+        make_atomic_call(BuiltinFn::$function, self, vec![value.model])
+      }
+    )*
+  };
+}
+macro_rules! impl_atomic_rmw_buffer {
+  ($($name:ident, $doc:literal;)*) => {
+    $(
+      #[doc = $doc]
+      pub fn $name<'cb>(&self,
+        index: ExprHandle<'cb, u32>,
+        value: ExprHandle<'cb, T>,
+      ) -> ExprHandle<'cb, T>
+        where 'sh: 'cb
+      {
+        self.elem(index).$name(value)
+      }
+    )*
+  };
+}
+
+impl<'cb, T: ExprAtomicScalarDataType> LvalueHandle<'cb, Atomic<T>> {
+  /** Build an `atomicLoad(&self)` expression. */
+  pub fn atomic_load(&self) -> ExprHandle<'cb, T> {
+    make_atomic_call(BuiltinFn::AtomicLoad, self, vec![])
+  }
+
+  /**
+   * Build an `atomicStore(&self, value)` expression.  It has no value, and
+   * is added to a block with `add_expr_statement`.
+   */
+  pub fn atomic_store(&self, value: ExprHandle<'cb, T>) -> ExprHandle<'cb, ()> {
+    make_atomic_call(BuiltinFn::AtomicStore, self, vec![value.model])
+  }
+
+  impl_atomic_rmw_lvalue! {
+    atomic_add => AtomicAdd, "Build an `atomicAdd(&self, value)` expression.";
+    atomic_sub => AtomicSub, "Build an `atomicSub(&self, value)` expression.";
+    atomic_max => AtomicMax, "Build an `atomicMax(&self, value)` expression.";
+    atomic_min => AtomicMin, "Build an `atomicMin(&self, value)` expression.";
+    atomic_and => AtomicAnd, "Build an `atomicAnd(&self, value)` expression.";
+    atomic_or => AtomicOr, "Build an `atomicOr(&self, value)` expression.";
+    atomic_xor => AtomicXor, "Build an `atomicXor(&self, value)` expression.";
+    atomic_exchange => AtomicExchange,
+      "Build an `atomicExchange(&self, value)` expression.";
+  }
+
+  /**
+   * Build an `atomicCompareExchangeWeak(&self, compare, value)` expression.
+   *
+   * The result should be bound with `add_let_decl_statement` when both of
+   * its fields are needed, as each evaluation attempts the exchange.
+   */
+  pub fn atomic_compare_exchange_weak(&self,
+    compare: ExprHandle<'cb, T>,
+    value: ExprHandle<'cb, T>,
+  ) -> ExprHandle<'cb, AtomicCompareExchangeResult<T>> {
+    make_atomic_call(
+      BuiltinFn::AtomicCompareExchangeWeak,
+      self,
+      vec![compare.model, value.model],
+    )
+  }
+}
+
+impl<'sh, T> BufferBindingHandle<'sh, Atomic<T>, BufferReadWrite>
+  where T: ExprAtomicScalarDataType
+{
+  /** Build an `atomicLoad(&buffer[index])` expression. */
+  pub fn atomic_load<'cb>(&self, index: ExprHandle<'cb, u32>)
+    -> ExprHandle<'cb, T>
+  where 'sh: 'cb,
+  {
+    self.elem(index).atomic_load()
+  }
+
+  /**
+   * Build an `atomicStore(&buffer[index], value)` expression.  It has no
+   * value, and is added to a block with `add_expr_statement`.
+   */
+  pub fn atomic_store<'cb>(&self,
+    index: ExprHandle<'cb, u32>,
+    value: ExprHandle<'cb, T>,
+  ) -> ExprHandle<'cb, ()>
+    where 'sh: 'cb
+  {
+    self.elem(index).atomic_store(value)
+  }
+
+  impl_atomic_rmw_buffer! {
+    atomic_add, "Build an `atomicAdd(&buffer[index], value)` expression.";
+    atomic_sub, "Build an `atomicSub(&buffer[index], value)` expression.";
+    atomic_max, "Build an `atomicMax(&buffer[index], value)` expression.";
+    atomic_min, "Build an `atomicMin(&buffer[index], value)` expression.";
+    atomic_and, "Build an `atomicAnd(&buffer[index], value)` expression.";
+    atomic_or, "Build an `atomicOr(&buffer[index], value)` expression.";
+    atomic_xor, "Build an `atomicXor(&buffer[index], value)` expression.";
+    atomic_exchange,
+      "Build an `atomicExchange(&buffer[index], value)` expression.";
+  }
+
+  /**
+   * Build an `atomicCompareExchangeWeak(&buffer[index], compare, value)`
+   * expression, see `LvalueHandle::atomic_compare_exchange_weak`.
+   */
+  pub fn atomic_compare_exchange_weak<'cb>(&self,
+    index: ExprHandle<'cb, u32>,
+    compare: ExprHandle<'cb, T>,
+    value: ExprHandle<'cb, T>,
+  ) -> ExprHandle<'cb, AtomicCompareExchangeResult<T>>
+    where 'sh: 'cb
+  {
+    self.elem(index).atomic_compare_exchange_weak(compare, value)
+  }
+}
+
+impl<'cb, T: ExprAtomicScalarDataType> ExprHandle<'cb, AtomicCompareExchangeResult<T>> {
+  /** Read the value held before the exchange was attempted. */
+  pub fn old_value(&self) -> ExprHandle<'cb, T> {
+    self.result_field("old_value")
+  }
+
+  /** Read whether the exchange took place. */
+  pub fn exchanged(&self) -> ExprHandle<'cb, bool> {
+    self.result_field("exchanged")
+  }
+
+  // Read a field of the result struct.
+  fn result_field<FT: ExprDataType>(&self, name: &str) -> ExprHandle<'cb, FT> {
+    let field_read_model = StructFieldReadModel::new(
+      self.model.clone(),
+      IdentifierModel::new(name),
+      FT::repr(),
+    );
+    ExprHandle::new(Box::new(ExpressionModel::StructFieldRead(field_read_model)))
+  }
+}
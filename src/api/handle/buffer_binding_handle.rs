@@ -2,10 +2,18 @@ use std::marker::PhantomData;
 use crate::{
   api::{
     buffer_attributes::{ BufferDisposition, BufferReadWrite },
-    data_type::HostShareableDataType,
+    data_type::{ ExprLoadableDataType, HostShareableDataType },
+    BufferBinding,
     handle::{ ExprHandle, LvalueHandle },
   },
-  model::{ BufferReadExprModel, ExpressionModel, IdentifierModel, LvalueModel },
+  model::{
+    buffer_length_expr,
+    BufferReadExprModel,
+    ExpressionModel,
+    IdentifierExprModel,
+    IdentifierModel,
+    LvalueModel,
+  },
 };
 
 /**
@@ -26,6 +34,14 @@ impl<'sh, DT, DISP> BufferBindingHandle<'sh, DT, DISP>
     BufferBindingHandle { name, _phantom: PhantomData }
   }
 
+  /**
+   * Get the typed buffer binding, for binding a buffer to the compiled
+   * shader.  Unlike the handle, it may outlive the shader's definition.
+   */
+  pub fn binding(&self) -> BufferBinding<DT> {
+    BufferBinding::new(self.name.clone())
+  }
+
   /**
    * Create an lvalue ExprHandle for writing the buffer element at an index.
    *
@@ -34,7 +50,7 @@ impl<'sh, DT, DISP> BufferBindingHandle<'sh, DT, DISP>
    */
   pub fn read<'cb>(&self, index: ExprHandle<'cb, u32>)
     -> ExprHandle<'cb, DT>
-  where DT: HostShareableDataType,
+  where DT: ExprLoadableDataType,
         'sh: 'cb,
   {
     let buffer_read_expr_model =
@@ -42,6 +58,38 @@ impl<'sh, DT, DISP> BufferBindingHandle<'sh, DT, DISP>
     let expression_model = ExpressionModel::BufferRead(buffer_read_expr_model);
     ExprHandle::new(Box::new(expression_model))
   }
+
+  /**
+   * Create an ExprHandle for the whole contents of a buffer holding a
+   * single runtime-sized struct, rather than an array of elements.
+   * Its fields are accessed by reference, e.g. `singleton().get(..)`.
+   *
+   * Panics if the buffer's data type is not runtime-sized.
+   */
+  pub fn singleton<'cb>(&self) -> ExprHandle<'cb, DT>
+  where 'sh: 'cb,
+  {
+    if !DT::repr().is_runtime_sized() {
+      panic!("Buffer binding '{}' holds an array of {}, not a singleton",
+             self.name.as_str(), DT::repr().wgsl_source());
+    }
+    let ident_expr_model = IdentifierExprModel::new(self.name.clone(), DT::repr());
+    ExprHandle::new(Box::new(ExpressionModel::Identifier(ident_expr_model)))
+  }
+
+  /**
+   * Create an ExprHandle for the number of elements in the buffer.
+   *
+   * The length is filled in from the bound buffer when the shader is
+   * dispatched.  For singleton buffers, this is the length of the struct's
+   * runtime-sized array.
+   */
+  pub fn length<'cb>(&self) -> ExprHandle<'cb, u32>
+  where 'sh: 'cb,
+  {
+    let model = buffer_length_expr(self.name.clone());
+    ExprHandle::new(Box::new(model))
+  }
 }
 
 impl<'sh, DT> BufferBindingHandle<'sh, DT, BufferReadWrite>
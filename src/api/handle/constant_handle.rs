@@ -0,0 +1,55 @@
+use std::marker::PhantomData;
+use crate::{
+  api::{
+    data_type::ExprDataType,
+    handle::ExprHandle,
+  },
+  model::{ ExpressionModel, IdentifierExprModel, IdentifierModel },
+};
+
+/**
+ * A handle to a module-scope constant or pipeline-overridable constant
+ * declared within a shader module.
+ *
+ * Unlike other variable bindings, the constants of `u32` may serve as
+ * workgroup sizes.
+ */
+#[derive(Debug)]
+pub struct ConstantHandle<'sh, DT>
+  where DT: ExprDataType
+{
+  name: IdentifierModel,
+  _phantom: PhantomData<&'sh DT>,
+}
+impl<'sh, DT> ConstantHandle<'sh, DT>
+  where DT: ExprDataType
+{
+  /** Create a new constant handle. */
+  pub(crate) fn new(name: IdentifierModel) -> Self {
+    ConstantHandle { name, _phantom: PhantomData }
+  }
+
+  /** Get the name of the constant. */
+  pub(crate) fn name(&self) -> &IdentifierModel {
+    &self.name
+  }
+
+  /**
+   * Create an ExprHandle reading the constant.
+   */
+  pub fn read<'cb>(&self) -> ExprHandle<'cb, DT>
+  where 'sh: 'cb,
+  {
+    let ident_expr_model =
+      IdentifierExprModel::new(self.name.clone(), DT::repr());
+    let expr_model = ExpressionModel::Identifier(ident_expr_model);
+    ExprHandle::new(Box::new(expr_model))
+  }
+}
+impl<'sh, DT> Clone for ConstantHandle<'sh, DT>
+  where DT: ExprDataType
+{
+  fn clone(&self) -> Self {
+    ConstantHandle { name: self.name.clone(), _phantom: PhantomData }
+  }
+}
@@ -0,0 +1,292 @@
+use crate::{
+  api::{
+    data_type::{
+      ExprDataType,
+      ExprFloatDataType,
+      ExprIntegralDataType,
+      ExprMatrixDataType,
+      ExprNumericDataType,
+      ExprSquareMatrixDataType,
+      ExprVectorNumericDataType,
+    },
+    handle::ExprHandle,
+  },
+  model::{ BuiltinCallExprModel, BuiltinFn, ExpressionModel },
+};
+
+/*
+ * Typed wrappers for wgsl builtin functions.
+ *
+ * Each builtin is available on the expression handles of the data types
+ * wgsl defines it for, so that e.g. `sqrt` cannot be called on `u32`.
+ */
+
+// Generic builtin call helper.
+#[allow(clippy::vec_box)]
+fn make_builtin_call<'cb, RES>(
+  function: BuiltinFn,
+  arguments: Vec<Box<ExpressionModel>>,
+) -> ExprHandle<'cb, RES>
+  where RES: ExprDataType
+{
+  let builtin_call_model =
+    BuiltinCallExprModel::new(function, arguments, RES::repr());
+  let model = ExpressionModel::BuiltinCall(builtin_call_model);
+  ExprHandle::new(Box::new(model))
+}
+
+impl<'cb, DT: ExprNumericDataType> ExprHandle<'cb, DT> {
+  /** Build an `abs(self)` expression. */
+  pub fn abs(&self) -> Self {
+    make_builtin_call(BuiltinFn::Abs, vec![self.model.clone()])
+  }
+
+  /** Build a componentwise `min(self, other)` expression. */
+  pub fn min(&self, other: &Self) -> Self {
+    make_builtin_call(BuiltinFn::Min, vec![
+      self.model.clone(),
+      other.model.clone(),
+    ])
+  }
+
+  /** Build a componentwise `max(self, other)` expression. */
+  pub fn max(&self, other: &Self) -> Self {
+    make_builtin_call(BuiltinFn::Max, vec![
+      self.model.clone(),
+      other.model.clone(),
+    ])
+  }
+
+  /** Build a componentwise `clamp(self, low, high)` expression. */
+  pub fn clamp(&self, low: &Self, high: &Self) -> Self {
+    make_builtin_call(BuiltinFn::Clamp, vec![
+      self.model.clone(),
+      low.model.clone(),
+      high.model.clone(),
+    ])
+  }
+}
+
+impl<'cb, DT: ExprFloatDataType> ExprHandle<'cb, DT> {
+  /** Build a `floor(self)` expression. */
+  pub fn floor(&self) -> Self {
+    make_builtin_call(BuiltinFn::Floor, vec![self.model.clone()])
+  }
+
+  /** Build a `fract(self)` expression. */
+  pub fn fract(&self) -> Self {
+    make_builtin_call(BuiltinFn::Fract, vec![self.model.clone()])
+  }
+
+  /**
+   * Build a `mix(self, other, t)` expression, linearly interpolating
+   * from `self` at `t = 0` to `other` at `t = 1`.
+   */
+  pub fn mix(&self, other: &Self, t: &Self) -> Self {
+    make_builtin_call(BuiltinFn::Mix, vec![
+      self.model.clone(),
+      other.model.clone(),
+      t.model.clone(),
+    ])
+  }
+
+  /**
+   * Build a `smoothstep(low, high, self)` expression, smoothly
+   * interpolating from 0 at `low` to 1 at `high`.
+   */
+  pub fn smoothstep(&self, low: &Self, high: &Self) -> Self {
+    make_builtin_call(BuiltinFn::Smoothstep, vec![
+      low.model.clone(),
+      high.model.clone(),
+      self.model.clone(),
+    ])
+  }
+
+  /** Build a `sqrt(self)` expression. */
+  pub fn sqrt(&self) -> Self {
+    make_builtin_call(BuiltinFn::Sqrt, vec![self.model.clone()])
+  }
+
+  /** Build a `pow(self, exponent)` expression. */
+  pub fn pow(&self, exponent: &Self) -> Self {
+    make_builtin_call(BuiltinFn::Pow, vec![
+      self.model.clone(),
+      exponent.model.clone(),
+    ])
+  }
+
+  /** Build an `exp(self)` expression. */
+  pub fn exp(&self) -> Self {
+    make_builtin_call(BuiltinFn::Exp, vec![self.model.clone()])
+  }
+
+  /** Build a natural logarithm `log(self)` expression. */
+  pub fn log(&self) -> Self {
+    make_builtin_call(BuiltinFn::Log, vec![self.model.clone()])
+  }
+
+  /** Build a `sin(self)` expression. */
+  pub fn sin(&self) -> Self {
+    make_builtin_call(BuiltinFn::Sin, vec![self.model.clone()])
+  }
+
+  /** Build a `cos(self)` expression. */
+  pub fn cos(&self) -> Self {
+    make_builtin_call(BuiltinFn::Cos, vec![self.model.clone()])
+  }
+
+  /** Build an `atan2(self, x)` expression, with `self` as the y value. */
+  pub fn atan2(&self, x: &Self) -> Self {
+    make_builtin_call(BuiltinFn::Atan2, vec![
+      self.model.clone(),
+      x.model.clone(),
+    ])
+  }
+
+  /** Build a `length(self)` expression. */
+  pub fn length(&self) -> ExprHandle<'cb, f32> {
+    make_builtin_call(BuiltinFn::Length, vec![self.model.clone()])
+  }
+
+  /** Build a `distance(self, other)` expression. */
+  pub fn distance(&self, other: &Self) -> ExprHandle<'cb, f32> {
+    make_builtin_call(BuiltinFn::Distance, vec![
+      self.model.clone(),
+      other.model.clone(),
+    ])
+  }
+}
+
+impl<'cb, DT: ExprVectorNumericDataType> ExprHandle<'cb, DT> {
+  /** Build a `dot(self, other)` expression. */
+  pub fn dot(&self, other: &Self) -> ExprHandle<'cb, DT::Scalar> {
+    make_builtin_call(BuiltinFn::Dot, vec![
+      self.model.clone(),
+      other.model.clone(),
+    ])
+  }
+
+  /** Build a `normalize(self)` expression. */
+  pub fn normalize(&self) -> Self
+    where DT: ExprFloatDataType
+  {
+    make_builtin_call(BuiltinFn::Normalize, vec![self.model.clone()])
+  }
+}
+
+impl<'cb> ExprHandle<'cb, [f32; 3]> {
+  /** Build a `cross(self, other)` expression. */
+  pub fn cross(&self, other: &Self) -> Self {
+    make_builtin_call(BuiltinFn::Cross, vec![
+      self.model.clone(),
+      other.model.clone(),
+    ])
+  }
+}
+
+impl<'cb, DT: ExprMatrixDataType> ExprHandle<'cb, DT> {
+  /** Build a `transpose(self)` expression, swapping rows and columns. */
+  pub fn transpose(&self) -> ExprHandle<'cb, DT::Transposed> {
+    make_builtin_call(BuiltinFn::Transpose, vec![self.model.clone()])
+  }
+
+  /** Build a `determinant(self)` expression of a square matrix. */
+  pub fn determinant(&self) -> ExprHandle<'cb, f32>
+    where DT: ExprSquareMatrixDataType
+  {
+    make_builtin_call(BuiltinFn::Determinant, vec![self.model.clone()])
+  }
+}
+
+impl<'cb, DT: ExprIntegralDataType> ExprHandle<'cb, DT> {
+  /** Build a componentwise `countOneBits(self)` expression. */
+  pub fn count_one_bits(&self) -> Self {
+    make_builtin_call(BuiltinFn::CountOneBits, vec![self.model.clone()])
+  }
+
+  /** Build a componentwise `countLeadingZeros(self)` expression. */
+  pub fn count_leading_zeros(&self) -> Self {
+    make_builtin_call(BuiltinFn::CountLeadingZeros, vec![self.model.clone()])
+  }
+
+  /** Build a componentwise `countTrailingZeros(self)` expression. */
+  pub fn count_trailing_zeros(&self) -> Self {
+    make_builtin_call(BuiltinFn::CountTrailingZeros, vec![self.model.clone()])
+  }
+
+  /**
+   * Build a componentwise `firstLeadingBit(self)` expression.
+   * For signed types this finds the first bit differing from the sign bit.
+   * Yields all ones (`-1` for signed types) if there is no such bit.
+   */
+  pub fn first_leading_bit(&self) -> Self {
+    make_builtin_call(BuiltinFn::FirstLeadingBit, vec![self.model.clone()])
+  }
+
+  /**
+   * Build a componentwise `firstTrailingBit(self)` expression.
+   * Yields all ones (`-1` for signed types) if no bit is set.
+   */
+  pub fn first_trailing_bit(&self) -> Self {
+    make_builtin_call(BuiltinFn::FirstTrailingBit, vec![self.model.clone()])
+  }
+
+  /** Build a componentwise `reverseBits(self)` expression. */
+  pub fn reverse_bits(&self) -> Self {
+    make_builtin_call(BuiltinFn::ReverseBits, vec![self.model.clone()])
+  }
+
+  /**
+   * Build an `extractBits(self, offset, count)` expression, reading `count`
+   * bits starting at bit `offset`.  Signed types are sign-extended.
+   */
+  pub fn extract_bits(&self,
+    offset: &ExprHandle<'cb, u32>,
+    count: &ExprHandle<'cb, u32>,
+  ) -> Self {
+    make_builtin_call(BuiltinFn::ExtractBits, vec![
+      self.model.clone(),
+      offset.model.clone(),
+      count.model.clone(),
+    ])
+  }
+
+  /**
+   * Build an `insertBits(self, bits, offset, count)` expression, replacing
+   * `count` bits of `self` starting at bit `offset` with the low bits of
+   * `bits`.
+   */
+  pub fn insert_bits(&self,
+    bits: &Self,
+    offset: &ExprHandle<'cb, u32>,
+    count: &ExprHandle<'cb, u32>,
+  ) -> Self {
+    make_builtin_call(BuiltinFn::InsertBits, vec![
+      self.model.clone(),
+      bits.model.clone(),
+      offset.model.clone(),
+      count.model.clone(),
+    ])
+  }
+}
+
+// Builtins reducing boolean vectors.
+macro_rules! impl_bool_vector_builtins {
+  ($($type:ty),*) => {
+    $(
+      impl<'cb> ExprHandle<'cb, $type> {
+        /** Build an `any(self)` expression, true if any component is. */
+        pub fn any(&self) -> ExprHandle<'cb, bool> {
+          make_builtin_call(BuiltinFn::Any, vec![self.model.clone()])
+        }
+
+        /** Build an `all(self)` expression, true if all components are. */
+        pub fn all(&self) -> ExprHandle<'cb, bool> {
+          make_builtin_call(BuiltinFn::All, vec![self.model.clone()])
+        }
+      }
+    )*
+  };
+}
+
+impl_bool_vector_builtins!([bool; 2], [bool; 3], [bool; 4]);
@@ -5,7 +5,12 @@ use std::{
 use crate::{
   api::{
     data_type::{
+      DataTypeRepr,
+      ExprBitcastableTo,
+      ExprComparableDataType,
+      ExprConvertibleTo,
       ExprDataType,
+      ExprIntegralDataType,
       ExprNumericDataType,
       ExprScalarNumericDataType,
       ExprVectorNumericDataType,
@@ -13,17 +18,22 @@ use crate::{
       StructMappedDataType,
     },
     handle::LvalueHandle,
+    BlacklightError,
   },
-  data_type::HostShareableDataType,
+  data_type::{ ExprLoadableDataType, HostShareableDataType },
   model::{
     BinOp,
     BinOpExprModel,
+    CastExprModel,
+    CastKind,
     CmpOp,
     CmpOpExprModel,
     ExpressionModel,
     IdentifierModel,
     LvalueModel,
     StructFieldReadModel,
+    UnaryOp,
+    UnaryOpExprModel,
     VecConstructorExprModel,
   }
 };
@@ -41,58 +51,95 @@ impl<'cb, DT: ExprDataType> ExprHandle<'cb, DT> {
     ExprHandle { model, _phantom: PhantomData }
   }
 
-  /** Build a comparison expression. */
-  pub fn cmp(&self, other: &Self, cmp_op: CmpOp) -> ExprHandle<'cb, bool>
-    where DT: ExprNumericDataType
+  /**
+   * Build a comparison expression.
+   * Vectors are compared componentwise, yielding a boolean vector.
+   */
+  pub fn cmp(&self, other: &Self, cmp_op: CmpOp)
+    -> ExprHandle<'cb, DT::CmpResult>
+    where DT: ExprNumericDataType + ExprComparableDataType
   {
-    let cmp_op_expr_model =
-      CmpOpExprModel::new(self.model.clone(), other.model.clone(), cmp_op);
-    let model = ExpressionModel::CmpOp(cmp_op_expr_model);
-    ExprHandle::new(Box::new(model))
+    make_cmp(self, other, cmp_op)
   }
 
   /** Build an equality `==` expression. */
-  pub fn eq(&self, other: &Self) -> ExprHandle<'cb, bool>
-    where DT: ExprNumericDataType
+  pub fn eq(&self, other: &Self) -> ExprHandle<'cb, DT::CmpResult>
+    where DT: ExprComparableDataType
   {
-    self.cmp(other, CmpOp::Eq)
+    make_cmp(self, other, CmpOp::Eq)
   }
 
   /** Build an non-equality `!=` expression. */
-  pub fn ne(&self, other: &Self) -> ExprHandle<'cb, bool>
-    where DT: ExprNumericDataType
+  pub fn ne(&self, other: &Self) -> ExprHandle<'cb, DT::CmpResult>
+    where DT: ExprComparableDataType
   {
-    self.cmp(other, CmpOp::Ne)
+    make_cmp(self, other, CmpOp::Ne)
   }
 
   /** Build a less-than `<` expression. */
-  pub fn lt(&self, other: &Self) -> ExprHandle<'cb, bool>
-    where DT: ExprNumericDataType
+  pub fn lt(&self, other: &Self) -> ExprHandle<'cb, DT::CmpResult>
+    where DT: ExprNumericDataType + ExprComparableDataType
   {
     self.cmp(other, CmpOp::Lt)
   }
 
   /** Build a less-than-or-equal-to `<=` expression. */
-  pub fn le(&self, other: &Self) -> ExprHandle<'cb, bool>
-    where DT: ExprNumericDataType
+  pub fn le(&self, other: &Self) -> ExprHandle<'cb, DT::CmpResult>
+    where DT: ExprNumericDataType + ExprComparableDataType
   {
     self.cmp(other, CmpOp::Le)
   }
 
   /** Build a greater-than `>` expression. */
-  pub fn gt(&self, other: &Self) -> ExprHandle<'cb, bool>
-    where DT: ExprNumericDataType
+  pub fn gt(&self, other: &Self) -> ExprHandle<'cb, DT::CmpResult>
+    where DT: ExprNumericDataType + ExprComparableDataType
   {
     self.cmp(other, CmpOp::Gt)
   }
 
   /** Build a greater-than-or-equal-to `>=` expression. */
-  pub fn ge(&self, other: &Self) -> ExprHandle<'cb, bool>
-    where DT: ExprNumericDataType
+  pub fn ge(&self, other: &Self) -> ExprHandle<'cb, DT::CmpResult>
+    where DT: ExprNumericDataType + ExprComparableDataType
   {
     self.cmp(other, CmpOp::Ge)
   }
 
+  /**
+   * Convert the value to another data type, e.g. `f32(x)`.
+   * Vectors are converted componentwise.
+   */
+  pub fn cast<T>(&self) -> ExprHandle<'cb, T>
+    where DT: ExprConvertibleTo<T>,
+          T: ExprDataType,
+  {
+    make_cast(self, CastKind::Value)
+  }
+
+  /**
+   * Reinterpret the bits of the value as another data type of the same
+   * size, e.g. `bitcast<u32>(x)`.
+   */
+  pub fn bitcast<T>(&self) -> ExprHandle<'cb, T>
+    where DT: ExprBitcastableTo<T>,
+          T: ExprDataType,
+  {
+    make_cast(self, CastKind::Bitcast)
+  }
+
+  /** Reinterpret the bits of an integral value as unsigned. */
+  pub fn as_unsigned(&self) -> ExprHandle<'cb, DT::AsUnsigned>
+    where DT: ExprIntegralDataType
+  {
+    self.bitcast()
+  }
+
+  /** Reinterpret the bits of an integral value as signed. */
+  pub fn as_signed(&self) -> ExprHandle<'cb, DT::AsSigned>
+    where DT: ExprIntegralDataType
+  {
+    self.bitcast()
+  }
+
   /** Cast scalar to vector. */
   pub fn to_vec<VDT>(&self) -> ExprHandle<'cb, VDT>
     where DT: ExprScalarNumericDataType,
@@ -113,15 +160,65 @@ impl<'cb, DT: ExprDataType> Clone for ExprHandle<'cb, DT> {
   }
 }
 
+// Methods available on boolean expressions.
+impl<'cb> ExprHandle<'cb, bool> {
+  /** Build a short-circuiting logical and `&&` expression. */
+  pub fn and(&self, other: &Self) -> Self {
+    make_logical_binop(self, other, BinOp::LogicalAnd)
+  }
+
+  /** Build a short-circuiting logical or `||` expression. */
+  pub fn or(&self, other: &Self) -> Self {
+    make_logical_binop(self, other, BinOp::LogicalOr)
+  }
+}
+
+// Generic conversion helper.
+fn make_cast<'cb, DT, T>(operand: &ExprHandle<'cb, DT>, kind: CastKind)
+  -> ExprHandle<'cb, T>
+  where DT: ExprDataType,
+        T: ExprDataType,
+{
+  let cast_expr_model = CastExprModel::new(operand.model.clone(), T::repr(), kind);
+  let model = ExpressionModel::Cast(cast_expr_model);
+  ExprHandle::new(Box::new(model))
+}
+
+// Generic comparison helper.
+fn make_cmp<'cb, DT>(
+  lhs: &ExprHandle<'cb, DT>,
+  rhs: &ExprHandle<'cb, DT>,
+  cmp_op: CmpOp,
+) -> ExprHandle<'cb, DT::CmpResult>
+  where DT: ExprComparableDataType
+{
+  let cmp_op_expr_model =
+    CmpOpExprModel::new(lhs.model.clone(), rhs.model.clone(), cmp_op);
+  let model = ExpressionModel::CmpOp(cmp_op_expr_model);
+  ExprHandle::new(Box::new(model))
+}
+
+// Logical binop helper.
+fn make_logical_binop<'cb>(
+  lhs: &ExprHandle<'cb, bool>,
+  rhs: &ExprHandle<'cb, bool>,
+  binop: BinOp,
+) -> ExprHandle<'cb, bool> {
+  let binop_expr_model =
+    BinOpExprModel::new(lhs.model.clone(), rhs.model.clone(), binop);
+  let expr_model = ExpressionModel::BinOp(binop_expr_model);
+  ExprHandle::new(Box::new(expr_model))
+}
+
 // Generic binop helper.
 fn make_binop<'cb, LHS, RHS, RES>(
   lhs: ExprHandle<'cb, LHS>,
   rhs: ExprHandle<'cb, RHS>,
   binop: BinOp
 ) -> ExprHandle<'cb, RES>
-  where LHS: ExprNumericDataType,
-        RHS: ExprNumericDataType,
-        RES: ExprNumericDataType
+  where LHS: ExprDataType,
+        RHS: ExprDataType,
+        RES: ExprDataType
 {
   let binop_expr_model = BinOpExprModel::new(lhs.model, rhs.model, binop);
   let expr_model = ExpressionModel::BinOp(binop_expr_model);
@@ -284,25 +381,130 @@ impl_binop_vec_scalar_shift_bulk!(
   (i32, [u32; 4])
 );
 
+// Bulk-implementation of matrix operators for `matCxR<f32>` matrices, i.e.
+// `[[f32; R]; C]`.  Multiplying by a vector or matrix follows the usual
+// linear algebra rules: `matCxR * vecC` is a `vecR`, `vecR * matCxR` is a
+// `vecC` and `matCxR * matKxC` is a `matKxR`.
+macro_rules! impl_binop_matrix_bulk {
+  ($(($columns:literal, $rows:literal)),*) => {
+    $(
+      impl_binop!(Add,
+        [[f32; $rows]; $columns], [[f32; $rows]; $columns],
+        [[f32; $rows]; $columns], add, BinOp::Add);
+      impl_binop!(Sub,
+        [[f32; $rows]; $columns], [[f32; $rows]; $columns],
+        [[f32; $rows]; $columns], sub, BinOp::Sub);
+      impl_binop!(Mul,
+        [[f32; $rows]; $columns], f32,
+        [[f32; $rows]; $columns], mul, BinOp::Mul);
+      impl_binop!(Mul,
+        f32, [[f32; $rows]; $columns],
+        [[f32; $rows]; $columns], mul, BinOp::Mul);
+      impl_binop!(Mul,
+        [[f32; $rows]; $columns], [f32; $columns],
+        [f32; $rows], mul, BinOp::Mul);
+      impl_binop!(Mul,
+        [f32; $rows], [[f32; $rows]; $columns],
+        [f32; $columns], mul, BinOp::Mul);
+      impl_binop!(Mul,
+        [[f32; $rows]; $columns], [[f32; $columns]; 2],
+        [[f32; $rows]; 2], mul, BinOp::Mul);
+      impl_binop!(Mul,
+        [[f32; $rows]; $columns], [[f32; $columns]; 3],
+        [[f32; $rows]; 3], mul, BinOp::Mul);
+      impl_binop!(Mul,
+        [[f32; $rows]; $columns], [[f32; $columns]; 4],
+        [[f32; $rows]; 4], mul, BinOp::Mul);
+    )*
+  };
+}
+
+impl_binop_matrix_bulk!(
+  (2, 2), (2, 3), (2, 4),
+  (3, 2), (3, 3), (3, 4),
+  (4, 2), (4, 3), (4, 4)
+);
+
+// Generic unary op helper.
+fn make_unop<'cb, DT>(operand: ExprHandle<'cb, DT>, unop: UnaryOp)
+  -> ExprHandle<'cb, DT>
+  where DT: ExprDataType
+{
+  let unop_expr_model = UnaryOpExprModel::new(operand.model, unop);
+  let expr_model = ExpressionModel::UnaryOp(unop_expr_model);
+  ExprHandle::new(Box::new(expr_model))
+}
+
+// Macro to generate unary operator implementations.
+macro_rules! impl_unop {
+  ($class:ident, $op:ident, $unop:expr, $($type:ty),*) => {
+    $(
+      impl<'cb> ops::$class for ExprHandle<'cb, $type> {
+        type Output = ExprHandle<'cb, $type>;
+        fn $op(self) -> Self::Output {
+          make_unop(self, $unop)
+        }
+      }
+    )*
+  };
+}
+
+impl_unop!(Neg, neg, UnaryOp::Neg, i32, [i32; 2], [i32; 3], [i32; 4]);
+impl_unop!(Neg, neg, UnaryOp::Neg, f32, [f32; 2], [f32; 3], [f32; 4]);
+
+impl_unop!(Not, not, UnaryOp::Not, bool, [bool; 2], [bool; 3], [bool; 4]);
+impl_unop!(Not, not, UnaryOp::Complement, u32, [u32; 2], [u32; 3], [u32; 4]);
+impl_unop!(Not, not, UnaryOp::Complement, i32, [i32; 2], [i32; 3], [i32; 4]);
+
 // Methods available on struct-type expressions.
 impl<'cb, DT> ExprHandle<'cb, Struct<DT>>
   where DT: StructMappedDataType
 {
-  /** Read a field from the struct. */
-  pub fn read<FT>(&self, name: &str) -> ExprHandle<'cb, FT>
+  /**
+   * Check that the struct has a field with the given name and type,
+   * returning the field's type.
+   */
+  fn check_field<FT>(name: &str) -> Result<DataTypeRepr, BlacklightError>
     where FT: ExprDataType
   {
     let struct_repr = Struct::<DT>::make_struct_repr();
-    let maybe_field = struct_repr.get_field(name);
-    let field = match maybe_field {
+    let field = match struct_repr.get_field(name) {
       Some(field) => field,
-      None => panic!("Field '{}' not found in struct", name),
+      None => return Err(BlacklightError::UnknownStructField {
+        struct_name: DT::NAME.to_string(),
+        field: name.to_string(),
+      }),
     };
     let read_repr = FT::repr();
     if field.data_type() != &read_repr {
-      panic!("Field '{}' has type {:?}, expected {:?}", name, field.data_type(), FT::repr());
+      return Err(BlacklightError::StructFieldTypeMismatch {
+        struct_name: DT::NAME.to_string(),
+        field: name.to_string(),
+        field_type: field.data_type().wgsl_source().to_string(),
+        accessed_type: read_repr.wgsl_source().to_string(),
+      });
     }
+    Ok(read_repr)
+  }
+
+  /**
+   * Read a field from the struct.
+   *
+   * Panics if the struct has no such field of the given type.
+   * See `try_read` for a fallible variant.
+   */
+  pub fn read<FT>(&self, name: &str) -> ExprHandle<'cb, FT>
+    where FT: ExprLoadableDataType
+  {
+    self.try_read(name).unwrap_or_else(|err| panic!("{}", err))
+  }
 
+  /** Read a field from the struct, if it has such a field of the given type. */
+  pub fn try_read<FT>(&self, name: &str)
+    -> Result<ExprHandle<'cb, FT>, BlacklightError>
+    where FT: ExprLoadableDataType
+  {
+    let read_repr = Self::check_field::<FT>(name)?;
     let ident = IdentifierModel::new(name);
     let field_read_model = StructFieldReadModel::new(
       self.model.clone(),
@@ -310,52 +512,53 @@ impl<'cb, DT> ExprHandle<'cb, Struct<DT>>
       read_repr,
     );
     let expr_model = ExpressionModel::StructFieldRead(field_read_model);
-    ExprHandle::new(Box::new(expr_model))
+    Ok(ExprHandle::new(Box::new(expr_model)))
   }
 
-  /** Reference a field from the struct. */
+  /**
+   * Reference a field from the struct.
+   *
+   * Panics if the struct has no such field of the given type.
+   * See `try_get` for a fallible variant.
+   */
   pub fn get<FT>(&self, name: &str) -> ExprHandle<'cb, FT>
-    where FT: HostShareableDataType
+    where FT: ExprLoadableDataType
   {
-    let struct_repr = Struct::<DT>::make_struct_repr();
-    let maybe_field = struct_repr.get_field(name);
-    let field = match maybe_field {
-      Some(field) => field,
-      None => panic!("Field '{}' not found in struct", name),
-    };
-    let read_repr = FT::repr();
-    if field.data_type() != &read_repr {
-      panic!("Field '{}' has type {:?}, expected {:?}", name, field.data_type(), FT::repr());
-    }
+    self.try_get(name).unwrap_or_else(|err| panic!("{}", err))
+  }
 
-    let ident = IdentifierModel::new(name);
-    let field_read_model = StructFieldReadModel::new(
-      self.model.clone(),
-      ident,
-      read_repr,
-    );
-    let expr_model = ExpressionModel::StructFieldRead(field_read_model);
-    ExprHandle::new(Box::new(expr_model))
+  /** Reference a field from the struct, if it has such a field of the given type. */
+  pub fn try_get<FT>(&self, name: &str)
+    -> Result<ExprHandle<'cb, FT>, BlacklightError>
+    where FT: ExprLoadableDataType
+  {
+    self.try_read(name)
   }
 
-  /** Reference a field from the struct. */
+  /**
+   * Reference a field from the struct as an lvalue.
+   *
+   * Panics if the struct has no such field of the given type.
+   * See `try_field` for a fallible variant.
+   */
   pub fn field<FT>(&self, name: &str) -> LvalueHandle<'cb, FT>
     where FT: HostShareableDataType
   {
-    let struct_repr = Struct::<DT>::make_struct_repr();
-    let maybe_field = struct_repr.get_field(name);
-    let field = match maybe_field {
-      Some(field) => field,
-      None => panic!("Field '{}' not found in struct", name),
-    };
-    let read_repr = FT::repr();
-    if field.data_type() != &read_repr {
-      panic!("Field '{}' has type {:?}, expected {:?}", name, field.data_type(), FT::repr());
-    }
+    self.try_field(name).unwrap_or_else(|err| panic!("{}", err))
+  }
 
+  /**
+   * Reference a field from the struct as an lvalue, if it has such a field
+   * of the given type.
+   */
+  pub fn try_field<FT>(&self, name: &str)
+    -> Result<LvalueHandle<'cb, FT>, BlacklightError>
+    where FT: HostShareableDataType
+  {
+    let read_repr = Self::check_field::<FT>(name)?;
     let ident = IdentifierModel::new(name);
-    LvalueHandle::new(
+    Ok(LvalueHandle::new(
       LvalueModel::new_struct_field(self.model.clone(), ident, read_repr)
-    )
+    ))
   }
 }
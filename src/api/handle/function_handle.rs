@@ -5,6 +5,7 @@ use crate::{
       ArgTupleDataType,
       ArgTupleHandleMap,
       ArgTupleHandleVisitor,
+      DataTypeRepr,
       ExprDataType,
       ProcResultType,
     },
@@ -33,21 +34,31 @@ impl<'sh, ARG, RET> FunctionHandle<'sh, ARG, RET>
   }
 
   /** Create a call expression from this function and argument expressions.
-   * A void call is a statement and added from the codeblock builder api.
+   * A void call is a statement and added from the codeblock builder api,
+   * see `CodeBlockBuilder::add_call_statement`.
    */
   pub fn call<'cb>(&self, args: ARG::HandleTuple) -> ExprHandle<'cb, RET>
     where 'sh: 'cb,
           RET: ExprDataType,
   {
-    let mut visitor = MakeArgumentExprVectorVisitor::new();
-    ARG::visit_argument_handles(args, &mut visitor);
-    let function_call_model =
-      FunctionCallExprModel::new(self.name.clone(), visitor.args, RET::repr());
+    let function_call_model = self.call_model(args);
     let expression_model = ExpressionModel::FunctionCall(function_call_model);
     ExprHandle::new(Box::new(expression_model))
   }
+
+  /** Create the model of a call to this function. */
+  pub(crate) fn call_model(&self, args: ARG::HandleTuple)
+    -> FunctionCallExprModel
+  {
+    let mut visitor = MakeArgumentExprVectorVisitor::new();
+    ARG::visit_argument_handles(args, &mut visitor);
+    let return_data_type =
+      RET::proc_result_repr().unwrap_or_else(DataTypeRepr::new_void);
+    FunctionCallExprModel::new(self.name.clone(), visitor.args, return_data_type)
+  }
 }
 
+#[allow(clippy::vec_box)]
 struct MakeArgumentExprVectorVisitor {
   args: Vec<Box<ExpressionModel>>,
 }
@@ -0,0 +1,68 @@
+use crate::{
+  api::{
+    data_type::{
+      ExprDataType,
+      ExprIndexableDataType,
+      HostShareableDataType,
+      RuntimeArray,
+    },
+    handle::{ ExprHandle, LvalueHandle },
+  },
+  model::{
+    BuiltinCallExprModel,
+    BuiltinFn,
+    ExpressionModel,
+    IndexExprModel,
+    LvalueModel,
+    UnaryOp,
+    UnaryOpExprModel,
+  },
+};
+
+/*
+ * Runtime indexing of vector, matrix and array expressions and lvalues, and
+ * the length of runtime-sized arrays.
+ */
+
+impl<'cb, DT: ExprIndexableDataType> ExprHandle<'cb, DT> {
+  /**
+   * Read the element at a runtime index, `e[index]`: the component of a
+   * vector, the column of a matrix or the element of an array.
+   */
+  pub fn index(&self, index: &ExprHandle<'cb, u32>)
+    -> ExprHandle<'cb, DT::Element>
+  {
+    let index_model = IndexExprModel::new(
+      self.model.clone(),
+      index.model.clone(),
+      DT::Element::repr(),
+    );
+    ExprHandle::new(Box::new(ExpressionModel::Index(index_model)))
+  }
+}
+
+impl<'cb, T: HostShareableDataType> ExprHandle<'cb, RuntimeArray<T>> {
+  /** Build an `arrayLength(&self)` expression, the number of elements. */
+  pub fn length(&self) -> ExprHandle<'cb, u32> {
+    let address_model = UnaryOpExprModel::new(self.model.clone(), UnaryOp::AddressOf);
+    let builtin_call_model = BuiltinCallExprModel::new(
+      BuiltinFn::ArrayLength,
+      vec![Box::new(ExpressionModel::UnaryOp(address_model))],
+      u32::repr(),
+    );
+    ExprHandle::new(Box::new(ExpressionModel::BuiltinCall(builtin_call_model)))
+  }
+}
+
+impl<'cb, DT: ExprIndexableDataType> LvalueHandle<'cb, DT> {
+  /** Reference the element at a runtime index as an lvalue. */
+  pub fn index(&self, index: &ExprHandle<'cb, u32>)
+    -> LvalueHandle<'cb, DT::Element>
+  {
+    LvalueHandle::new(LvalueModel::new_index(
+      self.model().clone(),
+      index.model.clone(),
+      DT::Element::repr(),
+    ))
+  }
+}
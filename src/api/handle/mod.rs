@@ -1,11 +1,17 @@
+mod atomic_access;
 mod buffer_binding_handle;
+mod constant_handle;
+mod expr_builtins;
 mod expr_handle;
 mod lvalue_handle;
 mod function_handle;
+mod index_access;
 mod variable_binding_handle;
+mod vector_access;
 
 pub use self::{
   buffer_binding_handle::BufferBindingHandle,
+  constant_handle::ConstantHandle,
   expr_handle::ExprHandle,
   lvalue_handle::LvalueHandle,
   function_handle::FunctionHandle,
@@ -0,0 +1,170 @@
+use crate::{
+  api::{
+    data_type::{
+      ComponentOf,
+      ExprDataType,
+      ExprMatrixDataType,
+      ExprVectorDataType,
+      W,
+      X,
+      Y,
+      Z,
+    },
+    handle::{ ExprHandle, LvalueHandle },
+  },
+  model::{ ExpressionModel, IndexExprModel, LvalueModel, SwizzleExprModel },
+};
+
+/*
+ * Component access, swizzles and indexing of vector expressions and
+ * lvalues.  Components are selected by the marker types `X`..`W`, which
+ * are only accepted for vectors that have them.  Matrices are indexed by
+ * column.
+ */
+
+impl<'cb, DT: ExprVectorDataType> ExprHandle<'cb, DT> {
+  // Build a swizzle expression from component indices.
+  fn make_swizzle<RES>(&self, components: Vec<u32>) -> ExprHandle<'cb, RES>
+    where RES: ExprDataType
+  {
+    let swizzle_model = SwizzleExprModel::new(self.model.clone(), components);
+    ExprHandle::new(Box::new(ExpressionModel::Swizzle(swizzle_model)))
+  }
+
+  /** Read the component `C` of the vector. */
+  pub fn component<C>(&self) -> ExprHandle<'cb, DT::Scalar>
+    where C: ComponentOf<DT>
+  {
+    self.make_swizzle(vec![C::INDEX])
+  }
+
+  /** Read the `x` component of the vector. */
+  pub fn x(&self) -> ExprHandle<'cb, DT::Scalar>
+    where X: ComponentOf<DT>
+  {
+    self.component::<X>()
+  }
+
+  /** Read the `y` component of the vector. */
+  pub fn y(&self) -> ExprHandle<'cb, DT::Scalar>
+    where Y: ComponentOf<DT>
+  {
+    self.component::<Y>()
+  }
+
+  /** Read the `z` component of the vector. */
+  pub fn z(&self) -> ExprHandle<'cb, DT::Scalar>
+    where Z: ComponentOf<DT>
+  {
+    self.component::<Z>()
+  }
+
+  /** Read the `w` component of the vector. */
+  pub fn w(&self) -> ExprHandle<'cb, DT::Scalar>
+    where W: ComponentOf<DT>
+  {
+    self.component::<W>()
+  }
+
+  /** Read two components of the vector, e.g. `v.swizzle2::<Y, X>()`. */
+  pub fn swizzle2<C0, C1>(&self) -> ExprHandle<'cb, [DT::Scalar; 2]>
+    where C0: ComponentOf<DT>,
+          C1: ComponentOf<DT>,
+          [DT::Scalar; 2]: ExprDataType,
+  {
+    self.make_swizzle(vec![C0::INDEX, C1::INDEX])
+  }
+
+  /** Read three components of the vector, e.g. `v.swizzle3::<Z, Y, X>()`. */
+  pub fn swizzle3<C0, C1, C2>(&self) -> ExprHandle<'cb, [DT::Scalar; 3]>
+    where C0: ComponentOf<DT>,
+          C1: ComponentOf<DT>,
+          C2: ComponentOf<DT>,
+          [DT::Scalar; 3]: ExprDataType,
+  {
+    self.make_swizzle(vec![C0::INDEX, C1::INDEX, C2::INDEX])
+  }
+
+  /** Read four components of the vector, e.g. `v.swizzle4::<X, X, Y, Y>()`. */
+  pub fn swizzle4<C0, C1, C2, C3>(&self) -> ExprHandle<'cb, [DT::Scalar; 4]>
+    where C0: ComponentOf<DT>,
+          C1: ComponentOf<DT>,
+          C2: ComponentOf<DT>,
+          C3: ComponentOf<DT>,
+          [DT::Scalar; 4]: ExprDataType,
+  {
+    self.make_swizzle(vec![C0::INDEX, C1::INDEX, C2::INDEX, C3::INDEX])
+  }
+}
+
+impl<'cb, DT: ExprVectorDataType> LvalueHandle<'cb, DT> {
+  /** Reference the component `C` of the vector as an lvalue. */
+  pub fn component<C>(&self) -> LvalueHandle<'cb, DT::Scalar>
+    where C: ComponentOf<DT>
+  {
+    LvalueHandle::new(LvalueModel::new_component(
+      self.model().clone(),
+      C::INDEX,
+      DT::Scalar::repr(),
+    ))
+  }
+
+  /** Reference the `x` component of the vector as an lvalue. */
+  pub fn x(&self) -> LvalueHandle<'cb, DT::Scalar>
+    where X: ComponentOf<DT>
+  {
+    self.component::<X>()
+  }
+
+  /** Reference the `y` component of the vector as an lvalue. */
+  pub fn y(&self) -> LvalueHandle<'cb, DT::Scalar>
+    where Y: ComponentOf<DT>
+  {
+    self.component::<Y>()
+  }
+
+  /** Reference the `z` component of the vector as an lvalue. */
+  pub fn z(&self) -> LvalueHandle<'cb, DT::Scalar>
+    where Z: ComponentOf<DT>
+  {
+    self.component::<Z>()
+  }
+
+  /** Reference the `w` component of the vector as an lvalue. */
+  pub fn w(&self) -> LvalueHandle<'cb, DT::Scalar>
+    where W: ComponentOf<DT>
+  {
+    self.component::<W>()
+  }
+}
+
+impl<'cb, DT: ExprMatrixDataType> ExprHandle<'cb, DT> {
+  /**
+   * Read the column vector at a runtime index, `m[index]`.
+   * Note that naga only accepts non-constant indices into matrices held in
+   * variables or buffers, not into `let` values.
+   */
+  pub fn column(&self, index: &ExprHandle<'cb, u32>)
+    -> ExprHandle<'cb, DT::Column>
+  {
+    let index_model = IndexExprModel::new(
+      self.model.clone(),
+      index.model.clone(),
+      DT::Column::repr(),
+    );
+    ExprHandle::new(Box::new(ExpressionModel::Index(index_model)))
+  }
+}
+
+impl<'cb, DT: ExprMatrixDataType> LvalueHandle<'cb, DT> {
+  /** Reference the column vector at a runtime index as an lvalue. */
+  pub fn column(&self, index: &ExprHandle<'cb, u32>)
+    -> LvalueHandle<'cb, DT::Column>
+  {
+    LvalueHandle::new(LvalueModel::new_index(
+      self.model().clone(),
+      index.model.clone(),
+      DT::Column::repr(),
+    ))
+  }
+}
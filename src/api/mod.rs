@@ -1,6 +1,11 @@
+mod buffer_binding;
+mod compiled_shader;
 mod entry_point;
+mod error;
+mod gpu_buffer;
 mod project;
 mod shader;
+mod shader_bindings;
 
 pub mod data_type;
 pub mod builder;
@@ -9,7 +14,12 @@ pub mod buffer_attributes;
 pub mod variable_attributes;
 pub mod block_dims;
 pub use self::{
+  buffer_binding::BufferBinding,
+  compiled_shader::CompiledShader,
   entry_point::EntryPoint,
+  error::{ BlacklightError, ShaderItem },
+  gpu_buffer::GpuBuffer,
   project::Project,
   shader::Shader,
+  shader_bindings::ShaderBindings,
 };
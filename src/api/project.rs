@@ -1,8 +1,9 @@
-use wgpu;
 use crate::api::{
+  BlacklightError,
+  GpuBuffer,
   Shader,
   builder::ShaderBuilder,
-  data_type::StructMappedDataType,
+  data_type::{ HostShareableDataType, StructMappedDataType },
 };
 
 /**
@@ -10,18 +11,33 @@ use crate::api::{
  * and functions that can be used to specify and execute shader modules.
  */
 pub struct Project {
-  _device: wgpu::Device,
-  _queue: wgpu::Queue,
+  device: wgpu::Device,
+  queue: wgpu::Queue,
 }
 impl Project {
   /** Create a new project with the given device and queue. */
   pub fn new(device: wgpu::Device, queue: wgpu::Queue) -> Self {
-    Project { _device: device, _queue: queue }
+    Project { device, queue }
   }
 
-  /** Define a new shader module within this project. */
+  /** Get the device the project's shaders are compiled for. */
+  pub fn device(&self) -> &wgpu::Device {
+    &self.device
+  }
+
+  /** Get the queue the project's shaders are dispatched on. */
+  pub fn queue(&self) -> &wgpu::Queue {
+    &self.queue
+  }
+
+  /**
+   * Define a new shader module within this project.
+   *
+   * Errors reported while running the definer are collected, and returned
+   * together once it completes.
+   */
   pub fn define_shader<'pr, UDT, DFN>(&'pr self, definer_fn: DFN)
-    -> Shader<UDT>
+    -> Result<Shader<UDT>, BlacklightError>
   where DFN: for <'sh> FnOnce(&mut ShaderBuilder<'sh, 'pr, UDT>),
         UDT: StructMappedDataType
   {
@@ -29,4 +45,15 @@ impl Project {
     definer_fn(&mut shader_builder);
     shader_builder.build()
   }
+
+  /**
+   * Create a new buffer holding `len` elements of the given data type.
+   * Buffers of runtime-sized structs hold a single struct, with `len`
+   * elements in its runtime-sized array.
+   */
+  pub fn create_buffer<DT>(&self, len: usize) -> GpuBuffer<'_, DT>
+    where DT: HostShareableDataType
+  {
+    GpuBuffer::new(self, len)
+  }
 }
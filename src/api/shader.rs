@@ -1,11 +1,20 @@
 use std::{
+  collections::HashMap,
   fmt,
   marker::PhantomData,
 };
 use crate::{
-  api::data_type::StructMappedDataType,
-  model::ShaderModel,
-  printer::generate_wgsl,
+  api::{
+    builder::OverrideValuesBuilder,
+    data_type::StructMappedDataType,
+    BlacklightError,
+    CompiledShader,
+    Project,
+  },
+  model::{ resolve_workgroup_sizes, ShaderModel },
+  pipeline::bind_group_layout_entries,
+  printer::{ generate_pipeline_wgsl, generate_wgsl },
+  validator::validate_wgsl,
 };
 
 /**
@@ -28,10 +37,73 @@ impl<UDT> Shader<UDT>
     Shader { model, _phantom: PhantomData }
   }
 
+  /** Get the shader model. */
+  pub(crate) fn model(&self) -> &ShaderModel {
+    &self.model
+  }
+
   /** Generate the wgsl for this shader. */
   pub fn generate_wgsl(&self) -> String {
     generate_wgsl(&self.model)
   }
+
+  /**
+   * Check that the generated wgsl parses and validates.
+   *
+   * Errors are traced back to the function or entry point whose generated
+   * code caused them, so they can be reported before compiling the shader.
+   *
+   * Workgroup sizes given by overrides are validated with their default
+   * values, as they would be compiled.
+   */
+  pub fn validate(&self) -> Result<(), BlacklightError> {
+    let workgroup_sizes =
+      resolve_workgroup_sizes(&self.model, &HashMap::new())?;
+    let source = generate_pipeline_wgsl(&self.model, &workgroup_sizes);
+    validate_wgsl(&self.model, &source)
+  }
+
+  /**
+   * Generate the bind group layout entries matching the shader's buffer
+   * bindings, indexed by group.
+   */
+  pub fn bind_group_layout_entries(&self)
+    -> Vec<Vec<wgpu::BindGroupLayoutEntry>>
+  {
+    bind_group_layout_entries(&self.model)
+  }
+
+  /**
+   * Compile this shader into compute pipelines for the project's device.
+   *
+   * Overrides take their default values, see `compile_with_overrides`.
+   */
+  pub fn compile<'pr>(&self, project: &'pr Project)
+    -> Result<CompiledShader<'pr, UDT>, BlacklightError>
+  {
+    self.compile_with_overrides(project, |_| {})
+  }
+
+  /**
+   * Compile this shader into compute pipelines for the project's device,
+   * setting the values of its overrides.
+   *
+   * Fails with the errors reported while setting values, see
+   * `OverrideValuesBuilder`, if an override without a default value was
+   * left unset, or if an override used as a workgroup size is not a
+   * non-zero `u32`.
+   */
+  pub fn compile_with_overrides<'pr, DFN>(&self,
+    project: &'pr Project,
+    definer_fn: DFN,
+  ) -> Result<CompiledShader<'pr, UDT>, BlacklightError>
+    where DFN: FnOnce(&mut OverrideValuesBuilder)
+  {
+    let mut values_builder = OverrideValuesBuilder::new(&self.model);
+    definer_fn(&mut values_builder);
+    let override_values = values_builder.build()?;
+    CompiledShader::new(project, self.clone(), &override_values)
+  }
 }
 impl<UDT> fmt::Debug for Shader<UDT>
   where UDT: StructMappedDataType
@@ -0,0 +1,54 @@
+use std::sync::Mutex;
+
+/**
+ * The bind groups binding a set of buffers to a compiled shader.
+ *
+ * Also owns the buffer backing the shader's uniforms for these bindings,
+ * which holds the lengths of the bound buffers, along with the encoding of
+ * the user uniforms last copied into it.
+ */
+#[derive(Debug)]
+pub struct ShaderBindings {
+  bind_groups: Vec<wgpu::BindGroup>,
+  uniforms_buffer: wgpu::Buffer,
+  uploaded_uniforms: Mutex<Option<Vec<u8>>>,
+  lengths: Vec<u32>,
+}
+impl ShaderBindings {
+  /** Create a new set of bindings. */
+  pub(crate) fn new(
+    bind_groups: Vec<wgpu::BindGroup>,
+    uniforms_buffer: wgpu::Buffer,
+    lengths: Vec<u32>,
+  ) -> Self {
+    ShaderBindings {
+      bind_groups,
+      uniforms_buffer,
+      uploaded_uniforms: Mutex::new(None),
+      lengths,
+    }
+  }
+
+  /** Get the bind groups, indexed by group. */
+  pub fn bind_groups(&self) -> &[wgpu::BindGroup] {
+    &self.bind_groups
+  }
+
+  /** Get the buffer backing the shader's uniforms for these bindings. */
+  pub fn uniforms_buffer(&self) -> &wgpu::Buffer {
+    &self.uniforms_buffer
+  }
+
+  /**
+   * Get the encoding of the user uniforms last copied into the uniforms
+   * buffer, if any.
+   */
+  pub(crate) fn uploaded_uniforms(&self) -> &Mutex<Option<Vec<u8>>> {
+    &self.uploaded_uniforms
+  }
+
+  /** Get the lengths of the bound buffers, in buffer binding order. */
+  pub fn buffer_lengths(&self) -> &[u32] {
+    &self.lengths
+  }
+}
@@ -1,5 +1,10 @@
+// Allow the derive macros to refer to `::blacklight` from within this crate.
+extern crate self as blacklight;
+
 mod model;
 mod printer;
+mod pipeline;
+mod validator;
 mod util;
 mod api;
 
@@ -1,4 +1,4 @@
-use crate::model::{ DataTypeCollector, StatementModel };
+use crate::model::{ DataTypeCollector, ExpressionModel, StatementModel };
 
 /**
  * Models a code block within a function or entry point.
@@ -20,6 +20,16 @@ impl CodeBlockModel {
     &self.statements
   }
 
+  /**
+   * Check whether any expression within the code block, including those
+   * nested in its statements, satisfies a predicate.
+   */
+  pub(crate) fn any_expression(&self,
+    pred: &mut impl FnMut(&ExpressionModel) -> bool,
+  ) -> bool {
+    self.statements.iter().any(|statement| statement.any_expression(pred))
+  }
+
   /** Collect struct data types reference by this buffer into a vector. */
   pub(crate) fn collect_struct_data_types_into(&self,
     collector: &mut DataTypeCollector,
@@ -20,13 +20,28 @@ impl DataTypeCollector {
     }
   }
 
-  /** Add a data type to the collector. */
+  /**
+   * Add a data type to the collector.  Struct element types of arrays are
+   * collected too.
+   */
   pub(crate) fn add_data_type(&mut self, data_type: DataTypeRepr) {
-    if let Some(struct_data_type) = data_type.take_struct() {
-      self.add_struct_data_type(struct_data_type);
+    match data_type {
+      DataTypeRepr::Struct(struct_data_type) =>
+        self.add_struct_data_type(struct_data_type),
+      DataTypeRepr::Array(array_data_type) =>
+        self.add_data_type(array_data_type.element().clone()),
+      DataTypeRepr::Builtin(_) => {},
     }
   }
 
+  /**
+   * Exclude struct data types with the given name from collection.
+   * Used for generated structs that are emitted separately.
+   */
+  pub(crate) fn exclude_name(&mut self, name: &str) {
+    self.seen_names.insert(IdentifierModel::new(name));
+  }
+
   /** Get the collected data types. */
   pub(crate) fn take_data_types(self) -> Vec<StructDataTypeRepr> {
     self.data_types
@@ -1,8 +1,72 @@
 use crate::{
-  api::block_dims::BlockDims,
-  model::CodeBlockModel,
+  api::block_dims::WorkgroupDim,
+  model::{ CodeBlockModel, ExpressionModel },
 };
 
+/**
+ * The builtin values available to compute entry points.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ComputeBuiltin {
+  GlobalInvocationId,
+  LocalInvocationId,
+  LocalInvocationIndex,
+  WorkgroupId,
+  NumWorkgroups,
+}
+impl ComputeBuiltin {
+  /** All compute builtins, in the order their parameters are declared. */
+  pub(crate) const ALL: [ComputeBuiltin; 5] = [
+    ComputeBuiltin::GlobalInvocationId,
+    ComputeBuiltin::LocalInvocationId,
+    ComputeBuiltin::LocalInvocationIndex,
+    ComputeBuiltin::WorkgroupId,
+    ComputeBuiltin::NumWorkgroups,
+  ];
+
+  /** Get the name binding the builtin within entry points. */
+  pub(crate) fn name(self) -> &'static str {
+    match self {
+      ComputeBuiltin::GlobalInvocationId => "global_id",
+      ComputeBuiltin::LocalInvocationId => "local_id",
+      ComputeBuiltin::LocalInvocationIndex => "local_index",
+      ComputeBuiltin::WorkgroupId => "workgroup_id",
+      ComputeBuiltin::NumWorkgroups => "num_workgroups",
+    }
+  }
+
+  /** Get the wgsl name of the builtin. */
+  pub(crate) fn wgsl_source(self) -> &'static str {
+    match self {
+      ComputeBuiltin::GlobalInvocationId => "global_invocation_id",
+      ComputeBuiltin::LocalInvocationId => "local_invocation_id",
+      ComputeBuiltin::LocalInvocationIndex => "local_invocation_index",
+      ComputeBuiltin::WorkgroupId => "workgroup_id",
+      ComputeBuiltin::NumWorkgroups => "num_workgroups",
+    }
+  }
+
+  /**
+   * Check whether the builtin is a `vec3<u32>`, bound truncated to the
+   * dimensions of the entry point.  Otherwise it is a `u32`.
+   */
+  pub(crate) fn is_vector(self) -> bool {
+    !matches!(self, ComputeBuiltin::LocalInvocationIndex)
+  }
+
+  /**
+   * Check whether the builtin may differ between the invocations of a
+   * workgroup.
+   */
+  pub(crate) fn is_non_uniform(self) -> bool {
+    matches!(self,
+      ComputeBuiltin::GlobalInvocationId |
+      ComputeBuiltin::LocalInvocationId |
+      ComputeBuiltin::LocalInvocationIndex
+    )
+  }
+}
+
 /**
  * Models an entry point in a shader module.
  */
@@ -11,8 +75,8 @@ pub(crate) struct EntryPointModel {
   // The name of the entry point.
   name: String,
 
-  // The block dims of the entry point.
-  block_dims: BlockDims,
+  // The workgroup size of the entry point, one entry per dimension.
+  workgroup_size: Vec<WorkgroupDim>,
 
   // The code block for the entry point.
   code_block: CodeBlockModel,
@@ -21,10 +85,10 @@ impl EntryPointModel {
   /** Create a new entry point. */
   pub(crate) fn new(
     name: String,
-    block_dims: BlockDims,
+    workgroup_size: Vec<WorkgroupDim>,
     code_block: CodeBlockModel,
   ) -> EntryPointModel {
-    EntryPointModel { name, block_dims, code_block }
+    EntryPointModel { name, workgroup_size, code_block }
   }
 
   /** Get the name of the entry point. */
@@ -32,13 +96,26 @@ impl EntryPointModel {
     &self.name
   }
 
-  /** Get the block dims of the entry point. */
-  pub(crate) fn block_dims(&self) -> BlockDims {
-    self.block_dims
+  /** Get the workgroup size of the entry point. */
+  pub(crate) fn workgroup_size(&self) -> &[WorkgroupDim] {
+    &self.workgroup_size
   }
 
   /** Get the code block for the entry point. */
   pub(crate) fn code_block(&self) -> &CodeBlockModel {
     &self.code_block
   }
+
+  /** Get the builtins read by the entry point's code. */
+  pub(crate) fn used_builtins(&self) -> Vec<ComputeBuiltin> {
+    ComputeBuiltin::ALL.into_iter()
+      .filter(|builtin| {
+        self.code_block.any_expression(&mut |expr| match expr {
+          ExpressionModel::Identifier(ident_expr) =>
+            ident_expr.identifier().as_str() == builtin.name(),
+          _ => false,
+        })
+      })
+      .collect()
+  }
 }
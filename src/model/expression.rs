@@ -1,6 +1,6 @@
 use crate::{
   api::data_type::{ DataTypeRepr, LiteralDataValue },
-  model::{ DataTypeCollector, IdentifierModel },
+  model::{ DataTypeCollector, IdentifierModel, LvalueModel },
 };
 
 /**
@@ -9,15 +9,61 @@ use crate::{
 #[derive(Clone, Debug)]
 pub(crate) enum ExpressionModel {
   Literal(LiteralExprModel),
+  ZeroValue(ZeroValueExprModel),
   Identifier(IdentifierExprModel),
   CmpOp(CmpOpExprModel),
   BinOp(BinOpExprModel),
+  UnaryOp(UnaryOpExprModel),
   BufferRead(BufferReadExprModel),
   StructFieldRead(StructFieldReadModel),
   FunctionCall(FunctionCallExprModel),
+  BuiltinCall(BuiltinCallExprModel),
+  Cast(CastExprModel),
+  Swizzle(SwizzleExprModel),
+  Index(IndexExprModel),
   VecConstructor(VecConstructorExprModel),
+  Reference(ReferenceExprModel),
 }
 impl ExpressionModel {
+  /**
+   * Check whether this expression, or any expression nested within it,
+   * satisfies a predicate.  This includes the expressions within lvalues
+   * referenced by the expression.
+   */
+  pub(crate) fn any(&self, pred: &mut impl FnMut(&ExpressionModel) -> bool) -> bool {
+    pred(self) || self.operands().into_iter().any(|operand| operand.any(pred))
+  }
+
+  /** Get the expressions directly nested within this expression. */
+  fn operands(&self) -> Vec<&ExpressionModel> {
+    match self {
+      ExpressionModel::Literal(_) |
+      ExpressionModel::ZeroValue(_) |
+      ExpressionModel::Identifier(_) => vec![],
+      ExpressionModel::CmpOp(cmp_op_expr) =>
+        vec![cmp_op_expr.lhs(), cmp_op_expr.rhs()],
+      ExpressionModel::BinOp(bin_op_expr) =>
+        vec![bin_op_expr.lhs(), bin_op_expr.rhs()],
+      ExpressionModel::UnaryOp(unary_op_expr) => vec![unary_op_expr.operand()],
+      ExpressionModel::BufferRead(buffer_read_expr) =>
+        vec![buffer_read_expr.index()],
+      ExpressionModel::StructFieldRead(struct_field_read) =>
+        vec![struct_field_read.struct_expr()],
+      ExpressionModel::FunctionCall(function_call) =>
+        function_call.arguments().iter().map(|arg| arg.as_ref()).collect(),
+      ExpressionModel::BuiltinCall(builtin_call) =>
+        builtin_call.arguments().iter().map(|arg| arg.as_ref()).collect(),
+      ExpressionModel::Cast(cast_expr) => vec![cast_expr.operand()],
+      ExpressionModel::Swizzle(swizzle_expr) => vec![swizzle_expr.base()],
+      ExpressionModel::Index(index_expr) =>
+        vec![index_expr.base(), index_expr.index()],
+      ExpressionModel::VecConstructor(vec_constructor) =>
+        vec_constructor.components().iter().map(|c| c.as_ref()).collect(),
+      ExpressionModel::Reference(reference_expr) =>
+        reference_expr.lvalue().operands(),
+    }
+  }
+
   /** Collect struct data types reference by this buffer into a vector. */
   pub(crate) fn collect_struct_data_types_into(&self,
     collector: &mut DataTypeCollector,
@@ -26,6 +72,9 @@ impl ExpressionModel {
       ExpressionModel::Literal(literal_expr) => {
         collector.add_data_type(literal_expr.value().data_type_repr());
       },
+      ExpressionModel::ZeroValue(zero_value_expr) => {
+        collector.add_data_type(zero_value_expr.data_type().clone());
+      },
       ExpressionModel::Identifier(ident_expr) => {
         collector.add_data_type(ident_expr.data_type().clone());
       },
@@ -37,6 +86,9 @@ impl ExpressionModel {
         bin_op_expr.lhs().collect_struct_data_types_into(collector);
         bin_op_expr.rhs().collect_struct_data_types_into(collector);
       },
+      ExpressionModel::UnaryOp(unary_op_expr) => {
+        unary_op_expr.operand().collect_struct_data_types_into(collector);
+      },
       ExpressionModel::BufferRead(buffer_read_expr) => {
         collector.add_data_type(buffer_read_expr.data_type().clone());
       },
@@ -49,12 +101,32 @@ impl ExpressionModel {
           arg.as_ref().collect_struct_data_types_into(collector);
         }
       },
+      ExpressionModel::BuiltinCall(builtin_call) => {
+        collector.add_data_type(builtin_call.data_type().clone());
+        for arg in builtin_call.arguments() {
+          arg.collect_struct_data_types_into(collector);
+        }
+      },
+      ExpressionModel::Cast(cast_expr) => {
+        cast_expr.operand().collect_struct_data_types_into(collector);
+      },
+      ExpressionModel::Swizzle(swizzle_expr) => {
+        swizzle_expr.base().collect_struct_data_types_into(collector);
+      },
+      ExpressionModel::Index(index_expr) => {
+        collector.add_data_type(index_expr.data_type().clone());
+        index_expr.base().collect_struct_data_types_into(collector);
+        index_expr.index().collect_struct_data_types_into(collector);
+      },
       ExpressionModel::VecConstructor(vec_constructor) => {
         collector.add_data_type(vec_constructor.data_type().clone());
         for component in vec_constructor.components() {
           component.collect_struct_data_types_into(collector);
         }
       },
+      ExpressionModel::Reference(reference_expr) => {
+        collector.add_data_type(reference_expr.lvalue().data_type().clone());
+      },
     }
   }
 }
@@ -78,6 +150,25 @@ impl LiteralExprModel {
   }
 }
 
+/**
+ * Represents the zero value of a type, with all of its components zero.
+ */
+#[derive(Clone, Debug)]
+pub(crate) struct ZeroValueExprModel {
+  data_type: DataTypeRepr,
+}
+impl ZeroValueExprModel {
+  /** Create a new zero value expression. */
+  pub(crate) fn new(data_type: DataTypeRepr) -> Self {
+    ZeroValueExprModel { data_type }
+  }
+
+  /** Get the type of the zero value. */
+  pub(crate) fn data_type(&self) -> &DataTypeRepr {
+    &self.data_type
+  }
+}
+
 /**
  * Represents an identifier expression.
  */
@@ -251,6 +342,7 @@ impl BinOpExprModel {
 pub enum BinOp {
   Add, Sub, Mul, Div, Rem,
   BitOr, BitAnd, BitXor, Shl, Shr,
+  LogicalAnd, LogicalOr,
 }
 impl BinOp {
   /** Get the string representation of the binary operator */
@@ -266,6 +358,53 @@ impl BinOp {
       BinOp::BitXor => "^",
       BinOp::Shl => "<<",
       BinOp::Shr => ">>",
+      BinOp::LogicalAnd => "&&",
+      BinOp::LogicalOr => "||",
+    }
+  }
+}
+
+/**
+ * Represents a unary operation expression.
+ */
+#[derive(Clone, Debug)]
+pub(crate) struct UnaryOpExprModel {
+  // The operand of the unary operation.
+  operand: Box<ExpressionModel>,
+
+  // The unary operator.
+  op: UnaryOp,
+}
+
+impl UnaryOpExprModel {
+  /** Create a new unary operation expression. */
+  pub(crate) fn new(operand: Box<ExpressionModel>, op: UnaryOp) -> Self {
+    UnaryOpExprModel { operand, op }
+  }
+
+  /** Get the operand of the unary operation. */
+  pub(crate) fn operand(&self) -> &ExpressionModel {
+    &self.operand
+  }
+
+  /** Get the unary operator. */
+  pub(crate) fn op(&self) -> UnaryOp {
+    self.op
+  }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum UnaryOp {
+  Neg, Not, Complement, AddressOf,
+}
+impl UnaryOp {
+  /** Get the string representation of the unary operator */
+  pub(crate) fn operator_str(self) -> &'static str {
+    match self {
+      UnaryOp::Neg => "-",
+      UnaryOp::Not => "!",
+      UnaryOp::Complement => "~",
+      UnaryOp::AddressOf => "&",
     }
   }
 }
@@ -313,6 +452,7 @@ impl StructFieldReadModel {
 /**
  * Represents a function call expression.
  */
+#[allow(clippy::vec_box)]
 #[derive(Clone, Debug)]
 pub(crate) struct FunctionCallExprModel {
   // The name of the function being called.
@@ -326,6 +466,7 @@ pub(crate) struct FunctionCallExprModel {
 }
 impl FunctionCallExprModel {
   /** Create a new function call expression. */
+  #[allow(clippy::vec_box)]
   pub(crate) fn new(
     function_name: IdentifierModel,
     arguments: Vec<Box<ExpressionModel>>,
@@ -350,9 +491,248 @@ impl FunctionCallExprModel {
   }
 }
 
+/**
+ * Represents a call to a wgsl builtin function.
+ */
+#[allow(clippy::vec_box)]
+#[derive(Clone, Debug)]
+pub(crate) struct BuiltinCallExprModel {
+  // The builtin function being called.
+  function: BuiltinFn,
+
+  // The arguments to the function.
+  arguments: Vec<Box<ExpressionModel>>,
+
+  // The result data type of the call.
+  data_type: DataTypeRepr,
+}
+impl BuiltinCallExprModel {
+  /** Create a new builtin call expression. */
+  #[allow(clippy::vec_box)]
+  pub(crate) fn new(
+    function: BuiltinFn,
+    arguments: Vec<Box<ExpressionModel>>,
+    data_type: DataTypeRepr,
+  ) -> Self {
+    BuiltinCallExprModel { function, arguments, data_type }
+  }
+
+  /** Get the builtin function being called. */
+  pub(crate) fn function(&self) -> BuiltinFn {
+    self.function
+  }
+
+  /** Get the arguments to the function. */
+  pub(crate) fn arguments(&self) -> &[Box<ExpressionModel>] {
+    &self.arguments
+  }
+
+  /** Get the result data type of the call. */
+  pub(crate) fn data_type(&self) -> &DataTypeRepr {
+    &self.data_type
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum BuiltinFn {
+  Abs, Min, Max, Clamp,
+  Floor, Fract, Mix, Smoothstep,
+  Sqrt, Pow, Exp, Log,
+  Sin, Cos, Atan2,
+  Dot, Cross, Length, Normalize, Distance,
+  CountOneBits, CountLeadingZeros, CountTrailingZeros,
+  FirstLeadingBit, FirstTrailingBit, ReverseBits,
+  ExtractBits, InsertBits,
+  Any, All,
+  Transpose, Determinant,
+  ArrayLength,
+  AtomicLoad, AtomicStore, AtomicAdd, AtomicSub, AtomicMax, AtomicMin,
+  AtomicAnd, AtomicOr, AtomicXor, AtomicExchange, AtomicCompareExchangeWeak,
+}
+impl BuiltinFn {
+  /** Get the wgsl name of the builtin function. */
+  pub(crate) fn name(self) -> &'static str {
+    match self {
+      BuiltinFn::Abs => "abs",
+      BuiltinFn::Min => "min",
+      BuiltinFn::Max => "max",
+      BuiltinFn::Clamp => "clamp",
+      BuiltinFn::Floor => "floor",
+      BuiltinFn::Fract => "fract",
+      BuiltinFn::Mix => "mix",
+      BuiltinFn::Smoothstep => "smoothstep",
+      BuiltinFn::Sqrt => "sqrt",
+      BuiltinFn::Pow => "pow",
+      BuiltinFn::Exp => "exp",
+      BuiltinFn::Log => "log",
+      BuiltinFn::Sin => "sin",
+      BuiltinFn::Cos => "cos",
+      BuiltinFn::Atan2 => "atan2",
+      BuiltinFn::Dot => "dot",
+      BuiltinFn::Cross => "cross",
+      BuiltinFn::Length => "length",
+      BuiltinFn::Normalize => "normalize",
+      BuiltinFn::Distance => "distance",
+      BuiltinFn::CountOneBits => "countOneBits",
+      BuiltinFn::CountLeadingZeros => "countLeadingZeros",
+      BuiltinFn::CountTrailingZeros => "countTrailingZeros",
+      BuiltinFn::FirstLeadingBit => "firstLeadingBit",
+      BuiltinFn::FirstTrailingBit => "firstTrailingBit",
+      BuiltinFn::ReverseBits => "reverseBits",
+      BuiltinFn::ExtractBits => "extractBits",
+      BuiltinFn::InsertBits => "insertBits",
+      BuiltinFn::Any => "any",
+      BuiltinFn::All => "all",
+      BuiltinFn::Transpose => "transpose",
+      BuiltinFn::Determinant => "determinant",
+      BuiltinFn::ArrayLength => "arrayLength",
+      BuiltinFn::AtomicLoad => "atomicLoad",
+      BuiltinFn::AtomicStore => "atomicStore",
+      BuiltinFn::AtomicAdd => "atomicAdd",
+      BuiltinFn::AtomicSub => "atomicSub",
+      BuiltinFn::AtomicMax => "atomicMax",
+      BuiltinFn::AtomicMin => "atomicMin",
+      BuiltinFn::AtomicAnd => "atomicAnd",
+      BuiltinFn::AtomicOr => "atomicOr",
+      BuiltinFn::AtomicXor => "atomicXor",
+      BuiltinFn::AtomicExchange => "atomicExchange",
+      BuiltinFn::AtomicCompareExchangeWeak => "atomicCompareExchangeWeak",
+    }
+  }
+}
+
+/**
+ * Represents a conversion of an expression to another data type.
+ */
+#[derive(Clone, Debug)]
+pub(crate) struct CastExprModel {
+  // The expression being converted.
+  operand: Box<ExpressionModel>,
+
+  // The data type being converted to.
+  data_type: DataTypeRepr,
+
+  // How the expression is converted.
+  kind: CastKind,
+}
+impl CastExprModel {
+  /** Create a new conversion expression. */
+  pub(crate) fn new(
+    operand: Box<ExpressionModel>,
+    data_type: DataTypeRepr,
+    kind: CastKind,
+  ) -> Self {
+    CastExprModel { operand, data_type, kind }
+  }
+
+  /** Get the expression being converted. */
+  pub(crate) fn operand(&self) -> &ExpressionModel {
+    &self.operand
+  }
+
+  /** Get the data type being converted to. */
+  pub(crate) fn data_type(&self) -> &DataTypeRepr {
+    &self.data_type
+  }
+
+  /** Get how the expression is converted. */
+  pub(crate) fn kind(&self) -> CastKind {
+    self.kind
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CastKind {
+  // A value conversion, e.g. `f32(x)`.
+  Value,
+
+  // A reinterpretation of bits, e.g. `bitcast<u32>(x)`.
+  Bitcast,
+}
+
+/** The names of vector components, in order. */
+const VECTOR_COMPONENT_NAMES: [char; 4] = ['x', 'y', 'z', 'w'];
+
+/** Get the wgsl name of a sequence of vector components, e.g. `xy`. */
+pub(crate) fn vector_components_str(components: &[u32]) -> String {
+  components.iter()
+    .map(|component| VECTOR_COMPONENT_NAMES[*component as usize])
+    .collect()
+}
+
+/**
+ * Represents a read of one or more components of a vector, e.g. `v.x`
+ * or `v.zyx`.
+ */
+#[derive(Clone, Debug)]
+pub(crate) struct SwizzleExprModel {
+  // The vector expression being read from.
+  base: Box<ExpressionModel>,
+
+  // The indices of the components read.
+  components: Vec<u32>,
+}
+impl SwizzleExprModel {
+  /** Create a new swizzle expression. */
+  pub(crate) fn new(base: Box<ExpressionModel>, components: Vec<u32>) -> Self {
+    SwizzleExprModel { base, components }
+  }
+
+  /** Get the vector expression being read from. */
+  pub(crate) fn base(&self) -> &ExpressionModel {
+    &self.base
+  }
+
+  /** Get the indices of the components read. */
+  pub(crate) fn components(&self) -> &[u32] {
+    &self.components
+  }
+}
+
+/**
+ * Represents a read of an element at a runtime index, e.g. `v[i]`.
+ */
+#[derive(Clone, Debug)]
+pub(crate) struct IndexExprModel {
+  // The expression being indexed.
+  base: Box<ExpressionModel>,
+
+  // The index expression.
+  index: Box<ExpressionModel>,
+
+  // The data type of the element.
+  data_type: DataTypeRepr,
+}
+impl IndexExprModel {
+  /** Create a new index expression. */
+  pub(crate) fn new(
+    base: Box<ExpressionModel>,
+    index: Box<ExpressionModel>,
+    data_type: DataTypeRepr,
+  ) -> Self {
+    IndexExprModel { base, index, data_type }
+  }
+
+  /** Get the expression being indexed. */
+  pub(crate) fn base(&self) -> &ExpressionModel {
+    &self.base
+  }
+
+  /** Get the index expression. */
+  pub(crate) fn index(&self) -> &ExpressionModel {
+    &self.index
+  }
+
+  /** Get the data type of the element. */
+  pub(crate) fn data_type(&self) -> &DataTypeRepr {
+    &self.data_type
+  }
+}
+
 /**
  * Represents a vector-constructor expression.
  */
+#[allow(clippy::vec_box)]
 #[derive(Clone, Debug)]
 pub(crate) struct VecConstructorExprModel {
   // The dimensions of the vector.
@@ -366,6 +746,7 @@ pub(crate) struct VecConstructorExprModel {
 }
 impl VecConstructorExprModel {
   /** Create a new vector-constructor expression. */
+  #[allow(clippy::vec_box)]
   pub(crate) fn new(
     dimensions: u32,
     data_type: DataTypeRepr,
@@ -389,3 +770,24 @@ impl VecConstructorExprModel {
     &self.components
   }
 }
+
+/**
+ * Represents a reference to an lvalue, read as an expression.  This is
+ * mostly useful as the operand of `&`, e.g. for atomic builtins.
+ */
+#[derive(Clone, Debug)]
+pub(crate) struct ReferenceExprModel {
+  // The lvalue being referenced.
+  lvalue: LvalueModel,
+}
+impl ReferenceExprModel {
+  /** Create a new reference expression. */
+  pub(crate) fn new(lvalue: LvalueModel) -> Self {
+    ReferenceExprModel { lvalue }
+  }
+
+  /** Get the lvalue being referenced. */
+  pub(crate) fn lvalue(&self) -> &LvalueModel {
+    &self.lvalue
+  }
+}
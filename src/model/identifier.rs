@@ -1,9 +1,10 @@
+use std::hash::{ Hash, Hasher };
 use crate::util::Shared;
 
 /**
  * Represents an identifier used in syntax.
  */
-#[derive(Clone, Debug, Hash)]
+#[derive(Clone, Debug)]
 pub struct IdentifierModel(Shared<String>);
 impl IdentifierModel {
   /** Create a new identifier model. */
@@ -23,3 +24,8 @@ impl PartialEq for IdentifierModel {
   }
 }
 impl Eq for IdentifierModel {}
+impl Hash for IdentifierModel {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.as_str().hash(state)
+  }
+}
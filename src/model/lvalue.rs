@@ -16,6 +16,12 @@ pub(crate) enum LvalueModel {
 
   // A struct field reference.
   StructField(Box<ExpressionModel>, IdentifierModel, DataTypeRepr),
+
+  // A vector component reference.
+  Component(Box<LvalueModel>, u32, DataTypeRepr),
+
+  // A reference to an element at a runtime index.
+  Index(Box<LvalueModel>, Box<ExpressionModel>, DataTypeRepr),
 }
 impl LvalueModel {
   /** Create a new lvalue variable model. */
@@ -43,4 +49,75 @@ impl LvalueModel {
   ) -> Self {
     LvalueModel::StructField(base, field_name, data_type)
   }
+
+  /** Create a new lvalue model for a vector component. */
+  pub(crate) fn new_component(
+    base: LvalueModel,
+    component: u32,
+    data_type: DataTypeRepr,
+  ) -> Self {
+    LvalueModel::Component(Box::new(base), component, data_type)
+  }
+
+  /** Create a new lvalue model for an element at a runtime index. */
+  pub(crate) fn new_index(
+    base: LvalueModel,
+    index: Box<ExpressionModel>,
+    data_type: DataTypeRepr,
+  ) -> Self {
+    LvalueModel::Index(Box::new(base), index, data_type)
+  }
+
+  /** Get the expressions nested within the lvalue, e.g. its indices. */
+  pub(crate) fn operands(&self) -> Vec<&ExpressionModel> {
+    match self {
+      LvalueModel::Variable(_, _) => vec![],
+      LvalueModel::BufferElement(_, index, _) => vec![index],
+      LvalueModel::StructField(base, _, _) => vec![base],
+      LvalueModel::Component(base, _, _) => base.operands(),
+      LvalueModel::Index(base, index, _) => {
+        let mut operands = base.operands();
+        operands.push(index);
+        operands
+      },
+    }
+  }
+
+  /**
+   * Get the name of the variable or buffer binding the lvalue is part of,
+   * if it is named directly rather than through an expression.
+   */
+  pub(crate) fn root_name(&self) -> Option<&IdentifierModel> {
+    match self {
+      LvalueModel::Variable(name, _) => Some(name),
+      LvalueModel::BufferElement(name, _, _) => Some(name),
+      LvalueModel::StructField(base, _, _) => expr_root_name(base),
+      LvalueModel::Component(base, _, _) => base.root_name(),
+      LvalueModel::Index(base, _, _) => base.root_name(),
+    }
+  }
+
+  /** Get the data type of the lvalue. */
+  pub(crate) fn data_type(&self) -> &DataTypeRepr {
+    match self {
+      LvalueModel::Variable(_, data_type) => data_type,
+      LvalueModel::BufferElement(_, _, data_type) => data_type,
+      LvalueModel::StructField(_, _, data_type) => data_type,
+      LvalueModel::Component(_, _, data_type) => data_type,
+      LvalueModel::Index(_, _, data_type) => data_type,
+    }
+  }
+}
+
+/** Get the name of the variable or buffer binding an access path starts at. */
+fn expr_root_name(expr: &ExpressionModel) -> Option<&IdentifierModel> {
+  match expr {
+    ExpressionModel::Identifier(ident_expr) => Some(ident_expr.identifier()),
+    ExpressionModel::BufferRead(buffer_read) => Some(buffer_read.buffer_name()),
+    ExpressionModel::StructFieldRead(struct_field_read) =>
+      expr_root_name(struct_field_read.struct_expr()),
+    ExpressionModel::Index(index_expr) => expr_root_name(index_expr.base()),
+    ExpressionModel::Swizzle(swizzle_expr) => expr_root_name(swizzle_expr.base()),
+    _ => None,
+  }
 }
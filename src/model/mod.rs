@@ -6,6 +6,7 @@ mod expression;
 mod function;
 mod identifier;
 mod lvalue;
+mod pipeline_override;
 mod shader;
 mod statement;
 mod variable_binding;
@@ -14,31 +15,60 @@ pub(crate) use self::{
   buffer_binding::BufferBindingModel,
   code_block::CodeBlockModel,
   data_type_collector::DataTypeCollector,
-  entry_point::EntryPointModel,
+  entry_point::{ ComputeBuiltin, EntryPointModel },
   expression::{
     BufferReadExprModel,
     BinOp,
     BinOpExprModel,
+    BuiltinCallExprModel,
+    BuiltinFn,
+    CastExprModel,
+    CastKind,
     CmpOp,
     CmpOpExprModel,
     ExpressionModel,
     FunctionCallExprModel,
     IdentifierExprModel,
+    IndexExprModel,
     LiteralExprModel,
+    ReferenceExprModel,
     StructFieldReadModel,
+    SwizzleExprModel,
+    UnaryOp,
+    UnaryOpExprModel,
     VecConstructorExprModel,
+    ZeroValueExprModel,
+    vector_components_str,
   },
   function::FunctionModel,
   identifier::IdentifierModel,
   lvalue::LvalueModel,
-  shader::ShaderModel,
+  pipeline_override::{ resolve_workgroup_sizes, OverrideModel },
+  shader::{
+    buffer_length_expr,
+    user_uniforms_expr,
+    ShaderModel,
+    LENGTHS_FIELD_NAME,
+    LENGTHS_STRUCT_NAME,
+    UNIFORMS_GROUP,
+    UNIFORMS_INDEX,
+    UNIFORMS_STRUCT_NAME,
+  },
   statement::{
     AssignStmtModel,
+    CallStmtModel,
+    ContinuingModel,
     ExprStmtModel,
+    ForStmtModel,
     IfElseStmtModel,
+    LoopStmtModel,
+    PhonyAssignStmtModel,
     ReturnStmtModel,
     StatementModel,
+    SwitchClauseModel,
+    SwitchStmtModel,
     VarDeclStmtModel,
+    WhileStmtModel,
   },
   variable_binding::{ VariableBindingModel, VariableBindingDisposition },
 };
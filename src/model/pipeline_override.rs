@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use crate::{
+  api::{
+    block_dims::WorkgroupDim,
+    data_type::{ DataTypeRepr, LiteralDataValue },
+    BlacklightError,
+  },
+  model::{ ExpressionModel, IdentifierModel, ShaderModel, VariableBindingModel },
+};
+
+/**
+ * Models a pipeline-overridable constant, declared with `override`.
+ */
+#[derive(Clone, Debug)]
+pub(crate) struct OverrideModel {
+  // The declaration, holding the default value if any.
+  binding: VariableBindingModel,
+
+  // The pipeline constant id, given with an `@id` attribute.
+  id: Option<u16>,
+}
+impl OverrideModel {
+  /** Create a new override model. */
+  pub(crate) fn new(binding: VariableBindingModel, id: Option<u16>) -> Self {
+    OverrideModel { binding, id }
+  }
+
+  /** Get the name of the override. */
+  pub(crate) fn name(&self) -> &IdentifierModel {
+    self.binding.name()
+  }
+
+  /** Get the declaration of the override. */
+  pub(crate) fn binding(&self) -> &VariableBindingModel {
+    &self.binding
+  }
+
+  /** Get the pipeline constant id of the override. */
+  pub(crate) fn id(&self) -> Option<u16> {
+    self.id
+  }
+
+  /** Check whether the override has a default value. */
+  pub(crate) fn has_default(&self) -> bool {
+    self.binding.initial_value().is_some()
+  }
+
+  /**
+   * Get the key of the override's value in the pipeline constants: its id
+   * if it has one, and its name otherwise.
+   */
+  pub(crate) fn pipeline_constant_key(&self) -> String {
+    match self.id {
+      Some(id) => id.to_string(),
+      None => self.name().as_str().to_string(),
+    }
+  }
+
+  // Get the default value of a `u32` override.
+  fn default_u32(&self) -> Option<u32> {
+    match self.binding.initial_value().as_deref() {
+      Some(ExpressionModel::Literal(literal)) => match literal.value() {
+        LiteralDataValue::U32(value) => Some(*value),
+        _ => None,
+      },
+      _ => None,
+    }
+  }
+}
+
+/**
+ * Resolve the values of the `u32` overrides used as workgroup sizes from
+ * the pipeline constants set by name, falling back to their default
+ * values.
+ *
+ * Fails if any of them is not a non-zero integer which fits in a `u32`.
+ */
+pub(crate) fn resolve_workgroup_sizes(
+  model: &ShaderModel,
+  values: &HashMap<String, f64>,
+) -> Result<HashMap<String, u32>, BlacklightError> {
+  let is_workgroup_size = |name: &str| {
+    model.entrypoints().iter()
+      .flat_map(|entrypoint| entrypoint.workgroup_size())
+      .any(|dim| matches!(dim, WorkgroupDim::Named(dim_name) if dim_name == name))
+  };
+  let mut sizes = HashMap::new();
+  let mut errors = Vec::new();
+  for override_model in model.overrides() {
+    let name = override_model.name().as_str();
+    if override_model.binding().data_type() != &DataTypeRepr::new_u32()
+      || !is_workgroup_size(name)
+    {
+      continue;
+    }
+    let value = match values.get(name) {
+      Some(value) => *value,
+      None => match override_model.default_u32() {
+        Some(value) => value as f64,
+        None => continue,
+      },
+    };
+    if value.fract() == 0.0 && value >= 1.0 && value <= u32::MAX as f64 {
+      sizes.insert(name.to_string(), value as u32);
+    } else {
+      errors.push(BlacklightError::InvalidWorkgroupSize {
+        name: name.to_string(),
+        value: value.to_string(),
+      });
+    }
+  }
+  match BlacklightError::from_errors(errors) {
+    Some(error) => Err(error),
+    None => Ok(sizes),
+  }
+}
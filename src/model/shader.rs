@@ -1,14 +1,85 @@
 use crate::{
-  api::data_type::{ DataTypeRepr,StructDataTypeRepr, StructFieldRepr },
+  api::{
+    buffer_attributes::{ BufferDispositionRepr, BufferMemorySpaceRepr },
+    data_type::{ DataTypeRepr, StructDataTypeRepr, StructFieldRepr },
+  },
   model::{
     BufferBindingModel,
+    DataTypeCollector,
     EntryPointModel,
+    ExpressionModel,
     FunctionModel,
+    IdentifierExprModel,
     IdentifierModel,
+    OverrideModel,
+    StructFieldReadModel,
     VariableBindingModel,
   },
 };
 
+/** The bind group of the implicit uniforms buffer binding. */
+pub(crate) const UNIFORMS_GROUP: u32 = 0;
+
+/** The binding index of the implicit uniforms buffer binding. */
+pub(crate) const UNIFORMS_INDEX: u32 = 0;
+
+/** The name of the implicit uniforms buffer binding. */
+const UNIFORMS_BINDING_NAME: &str = "uniforms";
+
+/** The name of the generated struct holding all uniforms. */
+pub(crate) const UNIFORMS_STRUCT_NAME: &str = "BlacklightUniforms";
+
+/** The name of the generated struct holding buffer lengths. */
+pub(crate) const LENGTHS_STRUCT_NAME: &str = "BlacklightBufferLengths";
+
+// Field names within the generated uniforms struct.
+const USER_UNIFORMS_FIELD_NAME: &str = "uniforms";
+pub(crate) const LENGTHS_FIELD_NAME: &str = "lengths";
+
+/**
+ * Build an expression reading a field of the generated uniforms struct.
+ *
+ * The full uniforms struct is only known once the shader is built, so
+ * the uniforms binding is referenced with an opaque struct type.  The
+ * generated struct names are excluded from struct data type collection.
+ */
+fn uniforms_field_expr(field_name: &str, data_type: DataTypeRepr)
+  -> ExpressionModel
+{
+  let uniforms_data_type = DataTypeRepr::new_struct(
+    StructDataTypeRepr::new(IdentifierModel::new(UNIFORMS_STRUCT_NAME), vec![])
+  );
+  let uniforms_expr = ExpressionModel::Identifier(IdentifierExprModel::new(
+    IdentifierModel::new(UNIFORMS_BINDING_NAME),
+    uniforms_data_type,
+  ));
+  ExpressionModel::StructFieldRead(StructFieldReadModel::new(
+    Box::new(uniforms_expr),
+    IdentifierModel::new(field_name),
+    data_type,
+  ))
+}
+
+/** Build an expression reading the user-defined uniforms struct. */
+pub(crate) fn user_uniforms_expr(data_type: DataTypeRepr) -> ExpressionModel {
+  uniforms_field_expr(USER_UNIFORMS_FIELD_NAME, data_type)
+}
+
+/** Build an expression reading the length of a buffer binding. */
+pub(crate) fn buffer_length_expr(buffer_name: IdentifierModel)
+  -> ExpressionModel
+{
+  let lengths_data_type = DataTypeRepr::new_struct(
+    StructDataTypeRepr::new(IdentifierModel::new(LENGTHS_STRUCT_NAME), vec![])
+  );
+  let lengths_expr = uniforms_field_expr(LENGTHS_FIELD_NAME, lengths_data_type);
+  ExpressionModel::StructFieldRead(StructFieldReadModel::new(
+    Box::new(lengths_expr),
+    buffer_name,
+    DataTypeRepr::new_u32(),
+  ))
+}
+
 /**
  * Model of a shader.
  */
@@ -17,17 +88,24 @@ pub(crate) struct ShaderModel {
   struct_data_types: Vec<StructDataTypeRepr>,
   uniform_data_type: StructDataTypeRepr,
   buffer_bindings: Vec<BufferBindingModel>,
+  workgroup_variables: Vec<VariableBindingModel>,
+  private_variables: Vec<VariableBindingModel>,
   const_definitions: Vec<VariableBindingModel>,
+  overrides: Vec<OverrideModel>,
   functions: Vec<FunctionModel>,
   entrypoints: Vec<EntryPointModel>,
 }
 impl ShaderModel {
   /** Create a new shader model. */
+  #[allow(clippy::too_many_arguments)]
   pub(crate) fn new(
     struct_data_types: Vec<StructDataTypeRepr>,
     uniform_data_type: StructDataTypeRepr,
     buffer_bindings: Vec<BufferBindingModel>,
+    workgroup_variables: Vec<VariableBindingModel>,
+    private_variables: Vec<VariableBindingModel>,
     const_definitions: Vec<VariableBindingModel>,
+    overrides: Vec<OverrideModel>,
     functions: Vec<FunctionModel>,
     entrypoints: Vec<EntryPointModel>,
   ) -> Self {
@@ -35,7 +113,10 @@ impl ShaderModel {
       uniform_data_type,
       struct_data_types,
       buffer_bindings,
+      workgroup_variables,
+      private_variables,
       const_definitions,
+      overrides,
       functions,
       entrypoints,
     }
@@ -51,6 +132,16 @@ impl ShaderModel {
     &self.buffer_bindings
   }
 
+  /** Get the workgroup variables. */
+  pub(crate) fn workgroup_variables(&self) -> &[VariableBindingModel] {
+    &self.workgroup_variables
+  }
+
+  /** Get the private variables. */
+  pub(crate) fn private_variables(&self) -> &[VariableBindingModel] {
+    &self.private_variables
+  }
+
   /** Get the functions */
   pub(crate) fn functions(&self) -> &[FunctionModel] {
     &self.functions
@@ -66,6 +157,46 @@ impl ShaderModel {
     &self.const_definitions
   }
 
+  /** Get the pipeline overrides. */
+  pub(crate) fn overrides(&self) -> &[OverrideModel] {
+    &self.overrides
+  }
+
+  /**
+   * Get the user struct data types laid out in the uniform space: the
+   * uniforms struct and the structs it transitively references.
+   */
+  pub(crate) fn uniform_struct_data_types(&self) -> Vec<StructDataTypeRepr> {
+    let mut collector = DataTypeCollector::new();
+    collector.add_struct_data_type(self.uniform_data_type.clone());
+    collector.take_data_types()
+  }
+
+  /**
+   * Get the struct data types laid out in the storage space: those
+   * transitively referenced by the buffer bindings.
+   */
+  pub(crate) fn storage_struct_data_types(&self) -> Vec<StructDataTypeRepr> {
+    let mut collector = DataTypeCollector::new();
+    for buffer_binding in &self.buffer_bindings {
+      buffer_binding.collect_struct_data_types_into(&mut collector);
+    }
+    collector.take_data_types()
+  }
+
+  /** Get the memory space a struct data type is laid out for. */
+  pub(crate) fn struct_memory_space(&self,
+    struct_data_type: &StructDataTypeRepr,
+  ) -> BufferMemorySpaceRepr {
+    let is_uniform = self.uniform_struct_data_types().iter()
+      .any(|uniform_struct| uniform_struct.name() == struct_data_type.name());
+    if is_uniform {
+      BufferMemorySpaceRepr::Uniform
+    } else {
+      BufferMemorySpaceRepr::Storage
+    }
+  }
+
   /** Generate the additional buffer-length fields to add to the uniforms. */
   pub(crate) fn get_length_fields(&self) -> impl Iterator<Item = &IdentifierModel> {
     self.buffer_bindings.iter().map(|binding| binding.name())
@@ -76,9 +207,9 @@ impl ShaderModel {
     let mut result = Vec::new();
 
     let length_fields = self.get_length_fields().collect::<Vec<_>>();
-    let maybe_lengths_struct = if length_fields.len() > 0 {
+    let maybe_lengths_struct = if !length_fields.is_empty() {
       let lengths_struct = StructDataTypeRepr::new(
-        IdentifierModel::new("BlacklightBufferLengths"),
+        IdentifierModel::new(LENGTHS_STRUCT_NAME),
         length_fields
           .iter()
           .map(|name| StructFieldRepr::new(
@@ -95,21 +226,35 @@ impl ShaderModel {
 
     let mut fields = vec![
       StructFieldRepr::new(
-        IdentifierModel::new("uniforms"),
+        IdentifierModel::new(USER_UNIFORMS_FIELD_NAME),
         DataTypeRepr::Struct(self.uniform_data_type.clone()),
       )
     ];
     if let Some(lengths_struct) = maybe_lengths_struct {
       fields.push(StructFieldRepr::new(
-        IdentifierModel::new("lengths"),
+        IdentifierModel::new(LENGTHS_FIELD_NAME),
         DataTypeRepr::Struct(lengths_struct)
       ));
     }
 
     let full_uniforms_struct =
-      StructDataTypeRepr::new(IdentifierModel::new("BlacklightUniforms"), fields);
+      StructDataTypeRepr::new(IdentifierModel::new(UNIFORMS_STRUCT_NAME), fields);
     result.push(full_uniforms_struct);
     
     result
   }
+
+  /** Generate the implicit buffer binding holding the full uniforms struct. */
+  pub(crate) fn uniform_buffer_binding(&self) -> BufferBindingModel {
+    let uniform_struct = self.full_uniform_structs().pop().unwrap();
+    BufferBindingModel::new(
+      IdentifierModel::new(UNIFORMS_BINDING_NAME),
+      BufferMemorySpaceRepr::Uniform,
+      BufferDispositionRepr::Read,
+      UNIFORMS_GROUP,
+      UNIFORMS_INDEX,
+      DataTypeRepr::Struct(uniform_struct),
+      /* is_singleton */ true,
+    )
+  }
 }
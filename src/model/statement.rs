@@ -1,9 +1,13 @@
-use crate::model::{
-  CodeBlockModel,
-  DataTypeCollector,
-  ExpressionModel,
-  LvalueModel,
-  VariableBindingModel,
+use crate::{
+  api::data_type::LiteralDataValue,
+  model::{
+    CodeBlockModel,
+    DataTypeCollector,
+    ExpressionModel,
+    FunctionCallExprModel,
+    LvalueModel,
+    VariableBindingModel,
+  },
 };
 
 /**
@@ -15,9 +19,73 @@ pub(crate) enum StatementModel {
   Assign(AssignStmtModel),
   IfElse(IfElseStmtModel),
   Expr(ExprStmtModel),
+  Call(CallStmtModel),
+  PhonyAssign(PhonyAssignStmtModel),
   Return(ReturnStmtModel),
+  Loop(LoopStmtModel),
+  While(WhileStmtModel),
+  For(ForStmtModel),
+  Switch(SwitchStmtModel),
+  Break,
+  Continue,
+  WorkgroupBarrier,
+  StorageBarrier,
 }
 impl StatementModel {
+  /**
+   * Check whether any expression within the statement, including those
+   * of nested blocks and assignment targets, satisfies a predicate.
+   */
+  pub(crate) fn any_expression(&self,
+    pred: &mut impl FnMut(&ExpressionModel) -> bool,
+  ) -> bool {
+    match self {
+      StatementModel::VarDecl(var_decl_stmt) =>
+        binding_any_expression(&var_decl_stmt.binding, pred),
+      StatementModel::Assign(assign_stmt) =>
+        assign_any_expression(assign_stmt, pred),
+      StatementModel::IfElse(if_else_stmt) => {
+        if_else_stmt.condition.any(pred) ||
+          if_else_stmt.if_block.any_expression(pred) ||
+          if_else_stmt.else_block.as_ref()
+            .is_some_and(|else_block| else_block.any_expression(pred))
+      },
+      StatementModel::Expr(expr_stmt) => expr_stmt.expression.any(pred),
+      StatementModel::Call(call_stmt) => call_stmt.call.arguments().iter()
+        .any(|arg| arg.as_ref().any(pred)),
+      StatementModel::PhonyAssign(phony_assign_stmt) =>
+        phony_assign_stmt.expression.any(pred),
+      StatementModel::Return(return_stmt) => return_stmt.expression.as_ref()
+        .is_some_and(|expression| expression.any(pred)),
+      StatementModel::Loop(loop_stmt) => {
+        loop_stmt.body.any_expression(pred) ||
+          loop_stmt.continuing.as_ref().is_some_and(|continuing| {
+            continuing.block.any_expression(pred) ||
+              continuing.break_if.as_ref()
+                .is_some_and(|break_if| break_if.any(pred))
+          })
+      },
+      StatementModel::While(while_stmt) => {
+        while_stmt.condition.any(pred) || while_stmt.body.any_expression(pred)
+      },
+      StatementModel::For(for_stmt) => {
+        binding_any_expression(&for_stmt.init, pred) ||
+          for_stmt.condition.any(pred) ||
+          assign_any_expression(&for_stmt.update, pred) ||
+          for_stmt.body.any_expression(pred)
+      },
+      StatementModel::Switch(switch_stmt) => {
+        switch_stmt.selector.any(pred) ||
+          switch_stmt.clauses.iter()
+            .any(|clause| clause.body.any_expression(pred))
+      },
+      StatementModel::Break |
+      StatementModel::Continue |
+      StatementModel::WorkgroupBarrier |
+      StatementModel::StorageBarrier => false,
+    }
+  }
+
   /** Collect struct data types reference by this buffer into a vector. */
   pub(crate) fn collect_struct_data_types_into(&self,
     collector: &mut DataTypeCollector,
@@ -27,6 +95,7 @@ impl StatementModel {
         var_decl_stmt.binding.collect_struct_data_types_into(collector);
       },
       StatementModel::Assign(assign_stmt) => {
+        collector.add_data_type(assign_stmt.target.data_type().clone());
         assign_stmt.expression.collect_struct_data_types_into(collector);
       },
       StatementModel::IfElse(if_else_stmt) => {
@@ -39,15 +108,70 @@ impl StatementModel {
       StatementModel::Expr(expr_stmt) => {
         expr_stmt.expression.collect_struct_data_types_into(collector);
       },
+      StatementModel::Call(call_stmt) => {
+        for arg in call_stmt.call.arguments() {
+          arg.as_ref().collect_struct_data_types_into(collector);
+        }
+      },
+      StatementModel::PhonyAssign(phony_assign_stmt) => {
+        phony_assign_stmt.expression.collect_struct_data_types_into(collector);
+      },
       StatementModel::Return(return_stmt) => {
         if let Some(expression) = &return_stmt.expression {
           expression.collect_struct_data_types_into(collector);
         }
-      }
+      },
+      StatementModel::Loop(loop_stmt) => {
+        loop_stmt.body.collect_struct_data_types_into(collector);
+        if let Some(continuing) = &loop_stmt.continuing {
+          continuing.block.collect_struct_data_types_into(collector);
+          if let Some(break_if) = &continuing.break_if {
+            break_if.collect_struct_data_types_into(collector);
+          }
+        }
+      },
+      StatementModel::While(while_stmt) => {
+        while_stmt.condition.collect_struct_data_types_into(collector);
+        while_stmt.body.collect_struct_data_types_into(collector);
+      },
+      StatementModel::For(for_stmt) => {
+        for_stmt.init.collect_struct_data_types_into(collector);
+        for_stmt.condition.collect_struct_data_types_into(collector);
+        for_stmt.update.expression.collect_struct_data_types_into(collector);
+        for_stmt.body.collect_struct_data_types_into(collector);
+      },
+      StatementModel::Switch(switch_stmt) => {
+        switch_stmt.selector.collect_struct_data_types_into(collector);
+        for clause in &switch_stmt.clauses {
+          clause.body.collect_struct_data_types_into(collector);
+        }
+      },
+      StatementModel::Break |
+      StatementModel::Continue |
+      StatementModel::WorkgroupBarrier |
+      StatementModel::StorageBarrier => {},
     }
   }
 }
 
+// Check the initial value of a variable binding against a predicate.
+fn binding_any_expression(
+  binding: &VariableBindingModel,
+  pred: &mut impl FnMut(&ExpressionModel) -> bool,
+) -> bool {
+  binding.initial_value().as_ref()
+    .is_some_and(|initial_value| initial_value.any(pred))
+}
+
+// Check both sides of an assignment against a predicate.
+fn assign_any_expression(
+  assign_stmt: &AssignStmtModel,
+  pred: &mut impl FnMut(&ExpressionModel) -> bool,
+) -> bool {
+  assign_stmt.target.operands().into_iter().any(|operand| operand.any(pred)) ||
+    assign_stmt.expression.any(pred)
+}
+
 /**
  * Represents a variable declaration statement.
  */
@@ -91,7 +215,7 @@ impl AssignStmtModel {
   }
 
   /** Get the expression being assigned to the variable. */
-  pub(crate) fn expression(&self) -> &Box<ExpressionModel> {
+  pub(crate) fn expression(&self) -> &ExpressionModel {
     &self.expression
   }
 }
@@ -125,7 +249,7 @@ impl IfElseStmtModel {
   }
 
   /** Get the condition expression. */
-  pub(crate) fn condition(&self) -> &Box<ExpressionModel> {
+  pub(crate) fn condition(&self) -> &ExpressionModel {
     &self.condition
   }
 
@@ -154,7 +278,47 @@ impl ExprStmtModel {
   }
 
   /** Get the expression. */
-  pub(crate) fn expression(&self) -> &Box<ExpressionModel> {
+  pub(crate) fn expression(&self) -> &ExpressionModel {
+    &self.expression
+  }
+}
+
+/**
+ * Represents a function call statement, calling a function for its
+ * effects alone.
+ */
+#[derive(Clone, Debug)]
+pub(crate) struct CallStmtModel {
+  call: FunctionCallExprModel,
+}
+impl CallStmtModel {
+  /** Create a new call statement. */
+  pub(crate) fn new(call: FunctionCallExprModel) -> Self {
+    CallStmtModel { call }
+  }
+
+  /** Get the function call. */
+  pub(crate) fn call(&self) -> &FunctionCallExprModel {
+    &self.call
+  }
+}
+
+/**
+ * Represents a phony assignment statement, evaluating an expression and
+ * discarding its value.
+ */
+#[derive(Clone, Debug)]
+pub(crate) struct PhonyAssignStmtModel {
+  expression: Box<ExpressionModel>,
+}
+impl PhonyAssignStmtModel {
+  /** Create a new phony assignment statement. */
+  pub(crate) fn new(expression: Box<ExpressionModel>) -> Self {
+    PhonyAssignStmtModel { expression }
+  }
+
+  /** Get the expression being discarded. */
+  pub(crate) fn expression(&self) -> &ExpressionModel {
     &self.expression
   }
 }
@@ -174,7 +338,221 @@ impl ReturnStmtModel {
   }
 
   /** Get the expression being returned. */
-  pub(crate) fn expression(&self) -> Option<&Box<ExpressionModel>> {
-    self.expression.as_ref()
+  pub(crate) fn expression(&self) -> Option<&ExpressionModel> {
+    self.expression.as_deref()
+  }
+}
+
+/**
+ * Represents a loop statement, which runs until broken out of.
+ */
+#[derive(Clone, Debug)]
+pub(crate) struct LoopStmtModel {
+  // The loop body.
+  body: CodeBlockModel,
+
+  // The continuing block run at the end of each iteration.
+  continuing: Option<ContinuingModel>,
+}
+impl LoopStmtModel {
+  /** Create a new loop statement. */
+  pub(crate) fn new(
+    body: CodeBlockModel,
+    continuing: Option<ContinuingModel>,
+  ) -> Self {
+    LoopStmtModel { body, continuing }
+  }
+
+  /** Get the loop body. */
+  pub(crate) fn body(&self) -> &CodeBlockModel {
+    &self.body
+  }
+
+  /** Get the continuing block. */
+  pub(crate) fn continuing(&self) -> Option<&ContinuingModel> {
+    self.continuing.as_ref()
+  }
+}
+
+/**
+ * Represents the continuing block of a loop statement, along with the
+ * optional `break if` condition ending it.
+ */
+#[derive(Clone, Debug)]
+pub(crate) struct ContinuingModel {
+  // The statements of the continuing block.
+  block: CodeBlockModel,
+
+  // The condition on which to break out of the loop.
+  break_if: Option<Box<ExpressionModel>>,
+}
+impl ContinuingModel {
+  /** Create a new continuing block. */
+  pub(crate) fn new(
+    block: CodeBlockModel,
+    break_if: Option<Box<ExpressionModel>>,
+  ) -> Self {
+    ContinuingModel { block, break_if }
+  }
+
+  /** Get the statements of the continuing block. */
+  pub(crate) fn block(&self) -> &CodeBlockModel {
+    &self.block
+  }
+
+  /** Get the break-if condition. */
+  pub(crate) fn break_if(&self) -> Option<&ExpressionModel> {
+    self.break_if.as_deref()
+  }
+}
+
+/**
+ * Represents a while statement.
+ */
+#[derive(Clone, Debug)]
+pub(crate) struct WhileStmtModel {
+  // The condition checked before each iteration.
+  condition: Box<ExpressionModel>,
+
+  // The loop body.
+  body: CodeBlockModel,
+}
+impl WhileStmtModel {
+  /** Create a new while statement. */
+  pub(crate) fn new(condition: Box<ExpressionModel>, body: CodeBlockModel)
+    -> Self
+  {
+    WhileStmtModel { condition, body }
+  }
+
+  /** Get the condition expression. */
+  pub(crate) fn condition(&self) -> &ExpressionModel {
+    &self.condition
+  }
+
+  /** Get the loop body. */
+  pub(crate) fn body(&self) -> &CodeBlockModel {
+    &self.body
+  }
+}
+
+/**
+ * Represents a for statement, with a loop variable declared in its header.
+ */
+#[derive(Clone, Debug)]
+pub(crate) struct ForStmtModel {
+  // The declaration of the loop variable.
+  init: VariableBindingModel,
+
+  // The condition checked before each iteration.
+  condition: Box<ExpressionModel>,
+
+  // The assignment run at the end of each iteration.
+  update: AssignStmtModel,
+
+  // The loop body.
+  body: CodeBlockModel,
+}
+impl ForStmtModel {
+  /** Create a new for statement. */
+  pub(crate) fn new(
+    init: VariableBindingModel,
+    condition: Box<ExpressionModel>,
+    update: AssignStmtModel,
+    body: CodeBlockModel,
+  ) -> Self {
+    ForStmtModel { init, condition, update, body }
+  }
+
+  /** Get the declaration of the loop variable. */
+  pub(crate) fn init(&self) -> &VariableBindingModel {
+    &self.init
+  }
+
+  /** Get the condition expression. */
+  pub(crate) fn condition(&self) -> &ExpressionModel {
+    &self.condition
+  }
+
+  /** Get the update assignment. */
+  pub(crate) fn update(&self) -> &AssignStmtModel {
+    &self.update
+  }
+
+  /** Get the loop body. */
+  pub(crate) fn body(&self) -> &CodeBlockModel {
+    &self.body
+  }
+}
+
+/**
+ * Represents a switch statement over an integer selector.
+ */
+#[derive(Clone, Debug)]
+pub(crate) struct SwitchStmtModel {
+  // The selector expression.
+  selector: Box<ExpressionModel>,
+
+  // The clauses, in order of definition.
+  clauses: Vec<SwitchClauseModel>,
+}
+impl SwitchStmtModel {
+  /** Create a new switch statement. */
+  pub(crate) fn new(
+    selector: Box<ExpressionModel>,
+    clauses: Vec<SwitchClauseModel>,
+  ) -> Self {
+    SwitchStmtModel { selector, clauses }
+  }
+
+  /** Get the selector expression. */
+  pub(crate) fn selector(&self) -> &ExpressionModel {
+    &self.selector
+  }
+
+  /** Get the clauses. */
+  pub(crate) fn clauses(&self) -> &[SwitchClauseModel] {
+    &self.clauses
+  }
+}
+
+/**
+ * Represents a clause of a switch statement, selected by any of its case
+ * values, or by default.
+ */
+#[derive(Clone, Debug)]
+pub(crate) struct SwitchClauseModel {
+  // The case values selecting this clause.
+  case_values: Vec<LiteralDataValue>,
+
+  // Whether this is the default clause.
+  is_default: bool,
+
+  // The clause body.
+  body: CodeBlockModel,
+}
+impl SwitchClauseModel {
+  /** Create a new switch clause. */
+  pub(crate) fn new(
+    case_values: Vec<LiteralDataValue>,
+    is_default: bool,
+    body: CodeBlockModel,
+  ) -> Self {
+    SwitchClauseModel { case_values, is_default, body }
+  }
+
+  /** Get the case values selecting this clause. */
+  pub(crate) fn case_values(&self) -> &[LiteralDataValue] {
+    &self.case_values
+  }
+
+  /** Check whether this is the default clause. */
+  pub(crate) fn is_default(&self) -> bool {
+    self.is_default
+  }
+
+  /** Get the clause body. */
+  pub(crate) fn body(&self) -> &CodeBlockModel {
+    &self.body
   }
 }
@@ -4,8 +4,9 @@ use crate::{
 };
 
 /**
- * A variable binding represents a `const`, `let`, or `var` declaration within
- * a shader module - either at the top level or within a function.
+ * A variable binding represents a `const`, `override`, `let`, `var`,
+ * `var<workgroup>` or `var<private>` declaration within a shader module -
+ * either at the top level or within a function.
  */
 #[derive(Clone, Debug)]
 pub(crate) struct VariableBindingModel {
@@ -74,19 +75,31 @@ pub(crate) enum VariableBindingDisposition {
   // A constant value.
   Const,
 
+  // A constant value which pipelines may override.
+  Override,
+
   // A mutable value.
   Var,
 
   // An immutable value.
   Let,
+
+  // A mutable value shared by the invocations of a workgroup.
+  Workgroup,
+
+  // A mutable module-scope value private to each invocation.
+  Private,
 }
 impl VariableBindingDisposition {
   /** Get the string representation of the variable binding disposition. */
   pub(crate) fn wgsl_source(&self) -> &'static str {
     match self {
       VariableBindingDisposition::Const => "const",
+      VariableBindingDisposition::Override => "override",
       VariableBindingDisposition::Var => "var",
       VariableBindingDisposition::Let => "let",
+      VariableBindingDisposition::Workgroup => "var<workgroup>",
+      VariableBindingDisposition::Private => "var<private>",
     }
   }
 }
@@ -0,0 +1,93 @@
+use crate::{
+  api::buffer_attributes::BufferMemorySpaceRepr,
+  model::{ BufferBindingModel, ShaderModel },
+};
+
+/**
+ * Generate the bind group layout entries for every buffer binding in the
+ * shader, including the implicit uniforms binding.
+ *
+ * The result is indexed by group.  Groups with no bindings are left empty.
+ */
+pub(crate) fn bind_group_layout_entries(model: &ShaderModel)
+  -> Vec<Vec<wgpu::BindGroupLayoutEntry>>
+{
+  let uniform_buffer_binding = model.uniform_buffer_binding();
+  let buffer_bindings =
+    Some(&uniform_buffer_binding).into_iter()
+      .chain(model.buffer_bindings().iter());
+
+  let mut groups: Vec<Vec<wgpu::BindGroupLayoutEntry>> = Vec::new();
+  for buffer_binding in buffer_bindings {
+    let group = buffer_binding.group() as usize;
+    if groups.len() <= group {
+      groups.resize_with(group + 1, Vec::new);
+    }
+    groups[group].push(bind_group_layout_entry(buffer_binding));
+  }
+  for entries in &mut groups {
+    entries.sort_by_key(|entry| entry.binding);
+  }
+  groups
+}
+
+fn bind_group_layout_entry(buffer_binding: &BufferBindingModel)
+  -> wgpu::BindGroupLayoutEntry
+{
+  let buffer_binding_type = match buffer_binding.memory_space() {
+    BufferMemorySpaceRepr::Uniform => wgpu::BufferBindingType::Uniform,
+    BufferMemorySpaceRepr::Storage => wgpu::BufferBindingType::Storage {
+      read_only: buffer_binding.disposition().is_read_only(),
+    },
+  };
+  wgpu::BindGroupLayoutEntry {
+    binding: buffer_binding.index(),
+    visibility: wgpu::ShaderStages::COMPUTE,
+    ty: wgpu::BindingType::Buffer {
+      ty: buffer_binding_type,
+      has_dynamic_offset: false,
+      min_binding_size: None,
+    },
+    count: None,
+  }
+}
+
+/**
+ * The bind group layouts for a shader, along with the pipeline layout
+ * composed from them.
+ */
+pub(crate) struct PipelineLayoutSet {
+  bind_group_layouts: Vec<wgpu::BindGroupLayout>,
+  pipeline_layout: wgpu::PipelineLayout,
+}
+impl PipelineLayoutSet {
+  /** Create the layouts for the given shader on a device. */
+  pub(crate) fn new(device: &wgpu::Device, model: &ShaderModel) -> Self {
+    let bind_group_layouts = bind_group_layout_entries(model).iter()
+      .enumerate()
+      .map(|(group, entries)| {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+          label: Some(&format!("blacklight group {}", group)),
+          entries,
+        })
+      })
+      .collect::<Vec<_>>();
+    let pipeline_layout =
+      device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: None,
+        bind_group_layouts: &bind_group_layouts.iter().collect::<Vec<_>>(),
+        push_constant_ranges: &[],
+      });
+    PipelineLayoutSet { bind_group_layouts, pipeline_layout }
+  }
+
+  /** Get the bind group layouts, indexed by group. */
+  pub(crate) fn bind_group_layouts(&self) -> &[wgpu::BindGroupLayout] {
+    &self.bind_group_layouts
+  }
+
+  /** Get the pipeline layout. */
+  pub(crate) fn pipeline_layout(&self) -> &wgpu::PipelineLayout {
+    &self.pipeline_layout
+  }
+}
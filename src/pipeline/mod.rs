@@ -0,0 +1,8 @@
+mod layout;
+mod uniforms;
+
+pub(crate) use self::layout::{
+  bind_group_layout_entries,
+  PipelineLayoutSet,
+};
+pub(crate) use self::uniforms::UniformsLayout;
@@ -0,0 +1,101 @@
+use crate::{
+  api::{
+    buffer_attributes::BufferMemorySpaceRepr,
+    data_type::{ HostShareableDataType, Struct, StructMappedDataType },
+    ShaderBindings,
+  },
+  model::{ ShaderModel, LENGTHS_FIELD_NAME },
+};
+
+/**
+ * The layout of a shader's implicit uniforms binding.
+ *
+ * The packed `BlacklightUniforms` struct holds the user uniforms followed
+ * by the lengths of the bound buffers.  Each set of bindings gets its own
+ * uniforms buffer, with the lengths written once when it is created.  The
+ * user uniforms are copied in by a dispatch's commands when they differ
+ * from those last copied, so that every dispatch recorded into an encoder
+ * sees its own uniforms.
+ */
+pub(crate) struct UniformsLayout {
+  size: usize,
+  lengths_offset: usize,
+  num_lengths: usize,
+}
+impl UniformsLayout {
+  /** Get the uniforms layout of a shader. */
+  pub(crate) fn new(model: &ShaderModel) -> Self {
+    let layout = model.full_uniform_structs().pop().unwrap()
+      .layout(BufferMemorySpaceRepr::Uniform);
+    let lengths_offset = layout.get_field(LENGTHS_FIELD_NAME)
+      .map_or(layout.size(), |field| field.offset());
+    let num_lengths = model.buffer_bindings().len();
+    UniformsLayout { size: layout.size(), lengths_offset, num_lengths }
+  }
+
+  /**
+   * Create a uniforms buffer holding the given buffer lengths, in the
+   * order of the shader's buffer bindings.
+   */
+  pub(crate) fn create_buffer(&self, device: &wgpu::Device, lengths: &[u32])
+    -> wgpu::Buffer
+  {
+    assert_eq!(lengths.len(), self.num_lengths);
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+      label: Some("BlacklightUniforms"),
+      size: self.size as u64,
+      usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+      mapped_at_creation: true,
+    });
+    {
+      let mut bytes = buffer.slice(..).get_mapped_range_mut();
+      for (i, length) in lengths.iter().enumerate() {
+        length.write_bytes(&mut bytes[self.lengths_offset + i * 4 ..]);
+      }
+    }
+    buffer.unmap();
+    buffer
+  }
+
+  /**
+   * Record a copy of the user uniforms into the uniforms buffer of a set
+   * of bindings, to be seen by the commands recorded after it.
+   *
+   * Nothing is recorded if the uniforms encode the same as those last
+   * copied into the buffer, which assumes that encoders are submitted in
+   * the order they were recorded.
+   */
+  pub(crate) fn encode_update<UDT>(&self,
+    device: &wgpu::Device,
+    encoder: &mut wgpu::CommandEncoder,
+    bindings: &ShaderBindings,
+    uniforms: &UDT,
+  )
+    where UDT: StructMappedDataType
+  {
+    if self.lengths_offset == 0 {
+      return;
+    }
+    let mut bytes = vec![0_u8; self.lengths_offset];
+    Struct::from(*uniforms).write_bytes_in(
+      BufferMemorySpaceRepr::Uniform,
+      &mut bytes,
+    );
+    let mut uploaded = bindings.uploaded_uniforms().lock().unwrap();
+    if uploaded.as_ref() == Some(&bytes) {
+      return;
+    }
+    let staging = device.create_buffer(&wgpu::BufferDescriptor {
+      label: Some("BlacklightUniforms staging"),
+      size: self.lengths_offset as u64,
+      usage: wgpu::BufferUsages::COPY_SRC,
+      mapped_at_creation: true,
+    });
+    staging.slice(..).get_mapped_range_mut().copy_from_slice(&bytes);
+    staging.unmap();
+    encoder.copy_buffer_to_buffer(
+      &staging, 0, bindings.uniforms_buffer(), 0, self.lengths_offset as u64
+    );
+    *uploaded = Some(bytes);
+  }
+}
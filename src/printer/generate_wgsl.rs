@@ -1,26 +1,36 @@
+use std::collections::HashMap;
 use crate::{
   api::{
-    block_dims::BlockDims,
-    data_type::{ DataTypeRepr, LiteralDataValue, StructDataTypeRepr },
-    buffer_attributes::{ BufferDispositionRepr, BufferMemorySpaceRepr },
+    block_dims::WorkgroupDim,
+    data_type::{ LiteralDataValue, StructDataTypeRepr },
+    buffer_attributes::BufferMemorySpaceRepr,
   },
   model::{
     AssignStmtModel,
+    CallStmtModel,
     BufferBindingModel,
+    CastKind,
     CodeBlockModel,
     CmpOpExprModel,
     EntryPointModel,
     ExprStmtModel,
     ExpressionModel,
-    IdentifierModel,
+    ForStmtModel,
+    FunctionCallExprModel,
     IfElseStmtModel,
+    LoopStmtModel,
     LvalueModel,
+    OverrideModel,
+    PhonyAssignStmtModel,
     ReturnStmtModel,
     ShaderModel,
     StatementModel,
+    SwitchStmtModel,
     VarDeclStmtModel,
+    WhileStmtModel,
     FunctionModel,
     VariableBindingModel,
+    vector_components_str,
   },
   printer::GeneratorBuffer
 };
@@ -29,14 +39,29 @@ use crate::{
  * Generate the WebGPU Shading Language (WGSL) code for the given AST.
  */
 pub(crate) fn generate_wgsl(model: &ShaderModel) -> String {
-  const LONG_COMMENT_BAR: &'static str =
+  generate_pipeline_wgsl(model, &HashMap::new())
+}
+
+/**
+ * Generate the wgsl code for the given AST as compiled into pipelines,
+ * with the workgroup sizes held by overrides replaced by their values.
+ *
+ * Naga only accepts constant workgroup sizes, so these are resolved
+ * before the shader module is created.  Overrides missing from the map
+ * are left as they are.
+ */
+pub(crate) fn generate_pipeline_wgsl(
+  model: &ShaderModel,
+  workgroup_sizes: &HashMap<String, u32>,
+) -> String {
+  const LONG_COMMENT_BAR: &str =
     "////////////////////////////////////////////////////////////////////////";
 
   let mut gen = GeneratorBuffer::new();
 
   // Write out codegen header.
   gen.write_line(
-    &format!("/// !!! Generated by blacklight (v{}) !!!", env!("CARGO_PKG_VERSION"))
+    format!("/// !!! Generated by blacklight (v{}) !!!", env!("CARGO_PKG_VERSION"))
   );
   gen.newline();
   gen.newline();
@@ -46,7 +71,8 @@ pub(crate) fn generate_wgsl(model: &ShaderModel) -> String {
   gen.write_line("/// Type bindings.");
   gen.newline();
   for struct_data_type in model.struct_data_types() {
-    gen_struct_data_type(&mut gen, struct_data_type);
+    let memory_space = model.struct_memory_space(struct_data_type);
+    gen_struct_data_type(&mut gen, struct_data_type, memory_space);
     gen.newline();
   }
 
@@ -54,22 +80,10 @@ pub(crate) fn generate_wgsl(model: &ShaderModel) -> String {
   gen.write_line(LONG_COMMENT_BAR);
   gen.write_line("/// Uniforms.");
   gen.newline();
-  let mut full_uniform_structs = model.full_uniform_structs();
-  for uniform_struct in &full_uniform_structs {
-    gen_struct_data_type(&mut gen, uniform_struct);
+  for uniform_struct in &model.full_uniform_structs() {
+    gen_struct_data_type(&mut gen, uniform_struct, BufferMemorySpaceRepr::Uniform);
   }
-  let uniform_struct = full_uniform_structs.pop().unwrap();
-  let uniform_buffer_binding =
-    BufferBindingModel::new(
-      IdentifierModel::new("uniforms"),
-      BufferMemorySpaceRepr::Uniform,
-      BufferDispositionRepr::Read,
-      0,
-      0,
-      DataTypeRepr::Struct(uniform_struct),
-      /* is_singleton */ true,
-    );
-  gen_buffer_binding(&mut gen, &uniform_buffer_binding);
+  gen_buffer_binding(&mut gen, &model.uniform_buffer_binding());
   gen.newline();
 
   // Write out buffer bindings.
@@ -81,6 +95,24 @@ pub(crate) fn generate_wgsl(model: &ShaderModel) -> String {
     gen.newline();
   }
 
+  // Write out workgroup variables.
+  gen.write_line(LONG_COMMENT_BAR);
+  gen.write_line("/// Workgroup variables.");
+  gen.newline();
+  for workgroup_variable in model.workgroup_variables() {
+    gen_variable_binding(&mut gen, workgroup_variable);
+  }
+  gen.newline();
+
+  // Write out private variables.
+  gen.write_line(LONG_COMMENT_BAR);
+  gen.write_line("/// Private variables.");
+  gen.newline();
+  for private_variable in model.private_variables() {
+    gen_variable_binding(&mut gen, private_variable);
+  }
+  gen.newline();
+
   // Write out constant definitions.
   gen.write_line(LONG_COMMENT_BAR);
   gen.write_line("/// Constant definitions.");
@@ -90,6 +122,15 @@ pub(crate) fn generate_wgsl(model: &ShaderModel) -> String {
   }
   gen.newline();
 
+  // Write out pipeline overrides.
+  gen.write_line(LONG_COMMENT_BAR);
+  gen.write_line("/// Pipeline overrides.");
+  gen.newline();
+  for override_model in model.overrides() {
+    gen_override(&mut gen, override_model);
+  }
+  gen.newline();
+
   // Write out function definitions.
   gen.write_line(LONG_COMMENT_BAR);
   gen.write_line("/// Function definitions.");
@@ -104,20 +145,39 @@ pub(crate) fn generate_wgsl(model: &ShaderModel) -> String {
   gen.write_line("/// Entrypoints.");
   gen.newline();
   for entrypoint in model.entrypoints() {
-    gen_entrypoint(&mut gen, entrypoint);
+    gen_entrypoint(&mut gen, entrypoint, workgroup_sizes);
     gen.newline();
   }
 
   gen.to_string()
 }
 
-fn gen_struct_data_type(gen: &mut GeneratorBuffer, struct_data_type: &StructDataTypeRepr) {
+/**
+ * Struct fields are annotated with `@align` and `@size` attributes wherever
+ * the layout for the memory space differs from the default wgsl layout of
+ * the field type.
+ */
+fn gen_struct_data_type(
+  gen: &mut GeneratorBuffer,
+  struct_data_type: &StructDataTypeRepr,
+  memory_space: BufferMemorySpaceRepr,
+) {
+  let layout = struct_data_type.layout(memory_space);
   gen.write_line(format!("struct {} {{", struct_data_type.name().as_str()));
   gen.with_indent(|gen| {
-    for field in struct_data_type.fields() {
-      gen.write_line(format!("{}: {},",
+    for (field, field_layout) in struct_data_type.fields().iter().zip(layout.fields()) {
+      let data_type = field.data_type();
+      let mut attrs = String::new();
+      if field_layout.alignment() != data_type.alignment(memory_space) {
+        attrs.push_str(&format!("@align({}) ", field_layout.alignment()));
+      }
+      if field_layout.size() != data_type.size(memory_space) {
+        attrs.push_str(&format!("@size({}) ", field_layout.size()));
+      }
+      gen.write_line(format!("{}{}: {},",
+        attrs,
         field.name(),
-        field.data_type().wgsl_source(),
+        data_type.wgsl_source(),
       ));
     }
   });
@@ -137,24 +197,49 @@ fn gen_buffer_binding(gen: &mut GeneratorBuffer, buffer_binding: &BufferBindingM
   } else {
     format!("array<{}>", type_string)
   };
-  gen.write_line(format!("var<{}, {}> {}: {};",
-    memory_space.as_str(),
-    disposition.as_str(),
+  // The uniform address space only permits read access, and does not
+  // accept an access mode.
+  let address_space = match memory_space {
+    BufferMemorySpaceRepr::Uniform => memory_space.as_str().to_string(),
+    BufferMemorySpaceRepr::Storage =>
+      format!("{}, {}", memory_space.as_str(), disposition.wgsl_access_mode()),
+  };
+  gen.write_line(format!("var<{}> {}: {};",
+    address_space,
     buffer_binding.name().as_str(),
     type_string,
   ));
 }
 
 fn gen_variable_binding(gen: &mut GeneratorBuffer, variable_binding: &VariableBindingModel) {
+  gen.write_start("");
+  gen_variable_binding_decl(gen, variable_binding);
+  gen.write_end(";");
+}
+
+fn gen_variable_binding_decl(gen: &mut GeneratorBuffer, variable_binding: &VariableBindingModel) {
   let initial_value = variable_binding.initial_value();
   let name_str = variable_binding.name().as_str();
   let disp_str = variable_binding.disposition().wgsl_source();
-  let data_type_str = variable_binding.data_type().wgsl_source();
-  gen.write_start(format!("{} {}: {}", disp_str, name_str, data_type_str));
+  let data_type = variable_binding.data_type();
+  if data_type.is_predeclared() {
+    gen.write(format!("{} {}", disp_str, name_str));
+  } else {
+    let data_type_str = data_type.wgsl_source();
+    gen.write(format!("{} {}: {}", disp_str, name_str, data_type_str));
+  }
   if let Some(initial_value) = initial_value {
     gen.write(" = ");
     gen_expression(gen, initial_value);
   }
+}
+
+fn gen_override(gen: &mut GeneratorBuffer, override_model: &OverrideModel) {
+  match override_model.id() {
+    Some(id) => gen.write_start(format!("@id({}) ", id)),
+    None => gen.write_start(""),
+  }
+  gen_variable_binding_decl(gen, override_model.binding());
   gen.write_end(";");
 }
 
@@ -179,22 +264,47 @@ fn gen_function_binding(gen: &mut GeneratorBuffer, function: &FunctionModel) {
   gen.write_line("}");
 }
 
-fn gen_entrypoint(gen: &mut GeneratorBuffer, entrypoint: &EntryPointModel) {
+fn gen_entrypoint(
+  gen: &mut GeneratorBuffer,
+  entrypoint: &EntryPointModel,
+  workgroup_sizes: &HashMap<String, u32>,
+) {
+  let dims = entrypoint.workgroup_size().iter()
+    .map(|dim| match dim {
+      WorkgroupDim::Fixed(size) => size.to_string(),
+      WorkgroupDim::Named(name) => match workgroup_sizes.get(name) {
+        Some(size) => size.to_string(),
+        None => name.clone(),
+      },
+    })
+    .collect::<Vec<_>>();
   gen.write_line("@compute");
-  gen.write_line(format!("@workgroup_size{}",
-    match entrypoint.block_dims() {
-      BlockDims::OneD(x) => format!("({})", x),
-      BlockDims::TwoD([x, y]) => format!("({}, {})", x, y),
-      BlockDims::ThreeD([x, y, z]) => format!("({}, {}, {})", x, y, z),
-    }
-  ));
+  gen.write_line(format!("@workgroup_size({})", dims.join(", ")));
+  // Only the builtins read by the entrypoint are declared.  Vector ids
+  // are taken as `bl_` parameters, and bound truncated to the dimensions
+  // of the entrypoint.
+  let builtins = entrypoint.used_builtins();
   gen.write_line(format!("fn {}(", entrypoint.name()));
   gen.with_indent(|gen| {
-    gen.write_line("@builtin(global_invocation_id)");
-    gen.write_line("bl_global_id: vec3<u32>");
+    for builtin in &builtins {
+      gen.write_line(format!("@builtin({})", builtin.wgsl_source()));
+      if builtin.is_vector() {
+        gen.write_line(format!("bl_{}: vec3<u32>,", builtin.name()));
+      } else {
+        gen.write_line(format!("{}: u32,", builtin.name()));
+      }
+    }
   });
   gen.write_line(") {");
   gen.with_indent(|gen| {
+    for builtin in builtins.iter().filter(|builtin| builtin.is_vector()) {
+      let name = builtin.name();
+      gen.write_line(match dims.len() {
+        1 => format!("let {}: u32 = bl_{}.x;", name, name),
+        2 => format!("let {}: vec2<u32> = bl_{}.xy;", name, name),
+        _ => format!("let {}: vec3<u32> = bl_{};", name, name),
+      });
+    }
     gen_code_block(gen, entrypoint.code_block());
   });
   gen.write_line("}");
@@ -220,9 +330,39 @@ fn gen_statement(gen: &mut GeneratorBuffer, stmt: &StatementModel) {
     StatementModel::Expr(expr_stmt) => {
       gen_expr_stmt(gen, expr_stmt);
     },
+    StatementModel::Call(call_stmt) => {
+      gen_call_stmt(gen, call_stmt);
+    },
+    StatementModel::PhonyAssign(phony_assign_stmt) => {
+      gen_phony_assign_stmt(gen, phony_assign_stmt);
+    },
     StatementModel::Return(return_stmt) => {
       gen_return_stmt(gen, return_stmt);
     },
+    StatementModel::Loop(loop_stmt) => {
+      gen_loop_stmt(gen, loop_stmt);
+    },
+    StatementModel::While(while_stmt) => {
+      gen_while_stmt(gen, while_stmt);
+    },
+    StatementModel::For(for_stmt) => {
+      gen_for_stmt(gen, for_stmt);
+    },
+    StatementModel::Switch(switch_stmt) => {
+      gen_switch_stmt(gen, switch_stmt);
+    },
+    StatementModel::Break => {
+      gen.write_line("break;");
+    },
+    StatementModel::Continue => {
+      gen.write_line("continue;");
+    },
+    StatementModel::WorkgroupBarrier => {
+      gen.write_line("workgroupBarrier();");
+    },
+    StatementModel::StorageBarrier => {
+      gen.write_line("storageBarrier();");
+    },
   }
 }
 
@@ -251,7 +391,17 @@ fn gen_lvalue_expr(gen: &mut GeneratorBuffer, lvalue: &LvalueModel) {
     LvalueModel::StructField(struct_expr, field_name, _) => {
       gen_expression(gen, struct_expr);
       gen.write(format!(".{}", field_name.as_str()));
-    }
+    },
+    LvalueModel::Component(base, component, _) => {
+      gen_lvalue_expr(gen, base);
+      gen.write(format!(".{}", vector_components_str(&[*component])));
+    },
+    LvalueModel::Index(base, index_expr, _) => {
+      gen_lvalue_expr(gen, base);
+      gen.write("[");
+      gen_expression(gen, index_expr);
+      gen.write("]");
+    },
   }
 }
 
@@ -271,12 +421,106 @@ fn gen_if_else_stmt(gen: &mut GeneratorBuffer, if_else_stmt: &IfElseStmtModel) {
   gen.write_line("}");
 }
 
+fn gen_switch_stmt(gen: &mut GeneratorBuffer, switch_stmt: &SwitchStmtModel) {
+  gen.write_start("switch (");
+  gen_expression(gen, switch_stmt.selector());
+  gen.write_end(") {");
+  gen.with_indent(|gen| {
+    for clause in switch_stmt.clauses() {
+      let mut selectors = clause.case_values().iter()
+        .map(|value| value.wgsl_source())
+        .collect::<Vec<_>>();
+      if clause.is_default() {
+        selectors.push("default".to_string());
+      }
+      if clause.case_values().is_empty() {
+        gen.write_line("default: {");
+      } else {
+        gen.write_line(format!("case {}: {{", selectors.join(", ")));
+      }
+      gen.with_indent(|gen| {
+        gen_code_block(gen, clause.body());
+      });
+      gen.write_line("}");
+    }
+  });
+  gen.write_line("}");
+}
+
+fn gen_loop_stmt(gen: &mut GeneratorBuffer, loop_stmt: &LoopStmtModel) {
+  gen.write_line("loop {");
+  gen.with_indent(|gen| {
+    gen_code_block(gen, loop_stmt.body());
+    if let Some(continuing) = loop_stmt.continuing() {
+      gen.write_line("continuing {");
+      gen.with_indent(|gen| {
+        gen_code_block(gen, continuing.block());
+        if let Some(break_if) = continuing.break_if() {
+          gen.write_start("break if ");
+          gen_expression(gen, break_if);
+          gen.write_end(";");
+        }
+      });
+      gen.write_line("}");
+    }
+  });
+  gen.write_line("}");
+}
+
+fn gen_while_stmt(gen: &mut GeneratorBuffer, while_stmt: &WhileStmtModel) {
+  gen.write_start("while (");
+  gen_expression(gen, while_stmt.condition());
+  gen.write_end(") {");
+  gen.with_indent(|gen| {
+    gen_code_block(gen, while_stmt.body());
+  });
+  gen.write_line("}");
+}
+
+fn gen_for_stmt(gen: &mut GeneratorBuffer, for_stmt: &ForStmtModel) {
+  let init = for_stmt.init();
+  gen.write_start(format!("for ({} {}: {}",
+    init.disposition().wgsl_source(),
+    init.name().as_str(),
+    init.data_type().wgsl_source(),
+  ));
+  if let Some(initial_value) = init.initial_value() {
+    gen.write(" = ");
+    gen_expression(gen, initial_value);
+  }
+  gen.write("; ");
+  gen_expression(gen, for_stmt.condition());
+  gen.write("; ");
+  gen_lvalue_expr(gen, for_stmt.update().target());
+  gen.write(" = ");
+  gen_expression(gen, for_stmt.update().expression());
+  gen.write_end(") {");
+  gen.with_indent(|gen| {
+    gen_code_block(gen, for_stmt.body());
+  });
+  gen.write_line("}");
+}
+
 fn gen_expr_stmt(gen: &mut GeneratorBuffer, expr_stmt: &ExprStmtModel) {
   gen.write_start("");
   gen_expression(gen, expr_stmt.expression());
   gen.write_end(";");
 }
 
+fn gen_call_stmt(gen: &mut GeneratorBuffer, call_stmt: &CallStmtModel) {
+  gen.write_start("");
+  gen_function_call(gen, call_stmt.call());
+  gen.write_end(";");
+}
+
+fn gen_phony_assign_stmt(gen: &mut GeneratorBuffer,
+  phony_assign_stmt: &PhonyAssignStmtModel,
+) {
+  gen.write_start("_ = ");
+  gen_expression(gen, phony_assign_stmt.expression());
+  gen.write_end(";");
+}
+
 fn gen_return_stmt(gen: &mut GeneratorBuffer, return_stmt: &ReturnStmtModel) {
   gen.write_start("return");
   if let Some(expr) = return_stmt.expression() {
@@ -294,6 +538,9 @@ fn gen_expression(gen: &mut GeneratorBuffer, expr: &ExpressionModel) {
     ExpressionModel::Literal(literal_expr) => {
       gen_literal_data_value(gen, literal_expr.value());
     },
+    ExpressionModel::ZeroValue(zero_value_expr) => {
+      gen.write(format!("{}()", zero_value_expr.data_type().wgsl_source()));
+    },
     ExpressionModel::CmpOp(cmp_op_expr) => {
       gen_cmp_op_expr(gen, cmp_op_expr);
     },
@@ -304,6 +551,22 @@ fn gen_expression(gen: &mut GeneratorBuffer, expr: &ExpressionModel) {
       gen_expression(gen, bin_op_expr.rhs());
       gen.write(")");
     },
+    ExpressionModel::UnaryOp(unary_op_expr) => {
+      // Literals may start with a sign, which must not merge with the
+      // operator (e.g. into the `--` token), so they are parenthesized.
+      let operand = unary_op_expr.operand();
+      let wrap_operand = matches!(operand, ExpressionModel::Literal(_));
+      gen.write("(");
+      gen.write(unary_op_expr.op().operator_str());
+      if wrap_operand {
+        gen.write("(");
+      }
+      gen_expression(gen, operand);
+      if wrap_operand {
+        gen.write(")");
+      }
+      gen.write(")");
+    },
     ExpressionModel::BufferRead(buffer_read_expr) => {
       gen.write(buffer_read_expr.buffer_name().as_str());
       gen.write("[");
@@ -315,16 +578,38 @@ fn gen_expression(gen: &mut GeneratorBuffer, expr: &ExpressionModel) {
       gen.write(".");
       gen.write(struct_field_read.field_name().as_str());
     },
-    ExpressionModel::FunctionCall(fucntion_call) => {
-      gen.write(fucntion_call.function_name().as_str());
+    ExpressionModel::FunctionCall(function_call) => {
+      gen_function_call(gen, function_call);
+    },
+    ExpressionModel::Swizzle(swizzle_expr) => {
+      gen_expression(gen, swizzle_expr.base());
+      gen.write(".");
+      gen.write(vector_components_str(swizzle_expr.components()));
+    },
+    ExpressionModel::Index(index_expr) => {
+      gen_expression(gen, index_expr.base());
+      gen.write("[");
+      gen_expression(gen, index_expr.index());
+      gen.write("]");
+    },
+    ExpressionModel::Cast(cast_expr) => {
+      let data_type = cast_expr.data_type().wgsl_source();
+      match cast_expr.kind() {
+        CastKind::Value => gen.write(format!("{}(", data_type)),
+        CastKind::Bitcast => gen.write(format!("bitcast<{}>(", data_type)),
+      }
+      gen_expression(gen, cast_expr.operand());
+      gen.write(")");
+    },
+    ExpressionModel::BuiltinCall(builtin_call) => {
+      gen.write(builtin_call.function().name());
       gen.write("(");
-      let arguments = fucntion_call.arguments();
-      let argcount = arguments.len();
+      let arguments = builtin_call.arguments();
       for (i, arg_expr) in arguments.iter().enumerate() {
-        gen_expression(gen, arg_expr.as_ref());
-        if i < argcount - 1 {
+        if i > 0 {
           gen.write(", ");
         }
+        gen_expression(gen, arg_expr);
       }
       gen.write(")");
     },
@@ -339,9 +624,28 @@ fn gen_expression(gen: &mut GeneratorBuffer, expr: &ExpressionModel) {
       }
       gen.write(")");
     },
+    ExpressionModel::Reference(reference_expr) => {
+      gen_lvalue_expr(gen, reference_expr.lvalue());
+    },
   }
 }
 
+fn gen_function_call(gen: &mut GeneratorBuffer,
+  function_call: &FunctionCallExprModel,
+) {
+  gen.write(function_call.function_name().as_str());
+  gen.write("(");
+  let arguments = function_call.arguments();
+  let argcount = arguments.len();
+  for (i, arg_expr) in arguments.iter().enumerate() {
+    gen_expression(gen, arg_expr.as_ref());
+    if i < argcount - 1 {
+      gen.write(", ");
+    }
+  }
+  gen.write(")");
+}
+
 fn gen_literal_data_value(gen: &mut GeneratorBuffer, literal_data_value: &LiteralDataValue) {
   gen.write(literal_data_value.wgsl_source());
 }
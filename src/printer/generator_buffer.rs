@@ -1,3 +1,5 @@
+use std::fmt;
+
 /**
  * Provides a simple code-generation API for printing text in a structured
  * way to a buffer.
@@ -58,9 +60,12 @@ impl GeneratorBuffer {
   pub(crate) fn newline(&mut self) {
     self.buffer.push("\n".to_string());
   }
-
-  /** Get the buffer as a string. */
-  pub(crate) fn to_string(&self) -> String {
-    self.buffer.join("")
+}
+impl fmt::Display for GeneratorBuffer {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for part in &self.buffer {
+      f.write_str(part)?;
+    }
+    Ok(())
   }
 }
@@ -3,5 +3,5 @@ mod generate_wgsl;
 
 pub(crate) use self::{
   generator_buffer::GeneratorBuffer,
-  generate_wgsl::generate_wgsl,
+  generate_wgsl::{ generate_pipeline_wgsl, generate_wgsl },
 };
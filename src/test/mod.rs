@@ -1,4 +1,3 @@
-
 mod smoke;
 
 mod util;
@@ -1,2 +1,27 @@
 mod smoketest_project;
 mod smoketest_perlin;
+mod smoketest_compile;
+mod smoketest_layout;
+mod smoketest_buffer;
+mod smoketest_uniforms;
+mod smoketest_memory_layout;
+mod smoketest_derive;
+mod smoketest_errors;
+mod smoketest_validate;
+mod smoketest_loops;
+mod smoketest_switch;
+mod smoketest_call;
+mod smoketest_builtins;
+mod smoketest_bits;
+mod smoketest_unary;
+mod smoketest_logic;
+mod smoketest_cast;
+mod smoketest_vectors;
+mod smoketest_matrix;
+mod smoketest_arrays;
+mod smoketest_atomics;
+mod smoketest_workgroup;
+mod smoketest_private;
+mod smoketest_overrides;
+mod smoketest_const_expr;
+mod smoketest_invocation;
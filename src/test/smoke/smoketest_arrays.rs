@@ -0,0 +1,177 @@
+use crate::{
+  api::{
+    BlacklightError,
+    Project,
+    buffer_attributes::BufferMemorySpaceRepr,
+    data_type::{
+      Array,
+      HostShareableDataType,
+      RuntimeArray,
+      Struct,
+      StructMappedDataType,
+    },
+    builder::literal,
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, Default, StructMappedDataType)]
+struct Uniforms {
+  offset: u32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, StructMappedDataType)]
+struct Particle {
+  id: u32,
+  corners: Array<[f32; 3], 2>,
+  weights: Array<f32, 4>,
+}
+
+#[derive(Clone, Copy, Default, StructMappedDataType)]
+struct Histogram {
+  scale: u32,
+  bins: RuntimeArray<u32>,
+}
+
+#[derive(Clone, Copy, Default, StructMappedDataType)]
+struct Misplaced {
+  bins: RuntimeArray<u32>,
+  scale: u32,
+}
+
+#[test]
+fn smoketest_arrays() {
+  // Arrays are laid out at the stride of their elements.
+  assert_eq!(<Array<[f32; 3], 2>>::size(), 32);
+  let layout = Struct::<Particle>::make_struct_repr()
+    .layout(BufferMemorySpaceRepr::Storage);
+  let offsets = layout.fields().iter()
+    .map(|field| field.offset())
+    .collect::<Vec<_>>();
+  assert_eq!(offsets, [0, 16, 48]);
+  assert_eq!(layout.size(), 64);
+
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut particles_binding = None;
+  let mut histogram_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let particles =
+      shb.define_read_write_buffer_binding::<Struct<Particle>>("particles", 0, 1);
+    particles_binding = Some(particles.binding());
+    let histogram =
+      shb.define_read_write_buffer_binding::<Struct<Histogram>>("histogram", 0, 2);
+    histogram_binding = Some(histogram.binding());
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      let offset = uniforms.get::<u32>("offset");
+
+      // Fill a local array, then patch an element at a runtime index.
+      let table = cbb.add_var_decl_statement(
+        "table",
+        literal(Array::new([1.0f32, 2.0, 3.0, 4.0]))
+      );
+      cbb.add_assignment_statement(
+        &table.lvalue().index(&(id.clone() % literal(4))),
+        literal(10.0)
+      );
+
+      let particle = particles.read(id.clone());
+      cbb.add_assignment_statement(
+        &particle.field::<Array<f32, 4>>("weights"),
+        table.read()
+      );
+      cbb.add_assignment_statement(
+        &particle.field::<Array<[f32; 3], 2>>("corners").index(&literal(1)),
+        particle.get::<Array<[f32; 3], 2>>("corners").index(&literal(0))
+          * particle.get::<Array<f32, 4>>("weights").index(&literal(0))
+      );
+
+      let bins = histogram.singleton().get::<RuntimeArray<u32>>("bins");
+      let scale = histogram.singleton().get::<u32>("scale");
+      cbb.add_if_statement(id.lt(&bins.length()), |cbb| {
+        cbb.add_assignment_statement(
+          &histogram.singleton()
+            .field::<RuntimeArray<u32>>("bins")
+            .index(&id),
+          id.clone() * scale + bins.length() + offset
+        );
+      });
+    }));
+  }).unwrap();
+  let particles_binding = particles_binding.unwrap();
+  let histogram_binding = histogram_binding.unwrap();
+  let wgsl = shader.generate_wgsl();
+  assert!(wgsl.contains("corners: array<vec3<f32>, 2>,"), "{}", wgsl);
+  assert!(wgsl.contains("bins: array<u32>,"), "{}", wgsl);
+  assert!(wgsl.contains("var<storage, read_write> histogram: Histogram;"), "{}", wgsl);
+  assert!(wgsl.contains("arrayLength((&histogram.bins))"), "{}", wgsl);
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let particles = (0..64)
+    .map(|i| {
+      let f = i as f32;
+      Particle {
+        id: i,
+        corners: Array::new([[f, f + 1.0, f + 2.0], [0.0; 3]]),
+        weights: Array::new([0.0; 4]),
+      }.into()
+    })
+    .collect::<Vec<Struct<Particle>>>();
+  let particle_buffer = project.create_buffer::<Struct<Particle>>(64);
+  particle_buffer.write_from_slice(&particles);
+  let histogram_buffer = project.create_buffer::<Struct<Histogram>>(50);
+  histogram_buffer.write_singleton(&Histogram { scale: 3, ..Default::default() }.into());
+  histogram_buffer.write_runtime_array(&[7u32; 50]);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&particles_binding, &particle_buffer);
+    bb.bind_buffer(&histogram_binding, &histogram_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { offset: 1000 }, &bindings, 1);
+
+  let out = futures::executor::block_on(particle_buffer.read_to_vec())
+    .unwrap()
+    .into_iter()
+    .map(|particle| *particle.data())
+    .collect::<Vec<Particle>>();
+  let expected = particles.iter()
+    .map(|particle| {
+      let mut particle = *particle.data();
+      let mut table = [1.0, 2.0, 3.0, 4.0];
+      table[particle.id as usize % 4] = 10.0;
+      let corner = particle.corners.elements()[0];
+      particle.corners.elements_mut()[1] = corner.map(|c| c * table[0]);
+      particle.weights = Array::new(table);
+      particle
+    })
+    .collect::<Vec<Particle>>();
+  assert_eq!(out, expected);
+
+  let scale = futures::executor::block_on(histogram_buffer.read_singleton())
+    .unwrap()
+    .data()
+    .scale;
+  assert_eq!(scale, 3);
+  let bins =
+    futures::executor::block_on(histogram_buffer.read_runtime_array::<u32>())
+      .unwrap();
+  let expected_bins = (0..50).map(|i| i * 3 + 50 + 1000).collect::<Vec<u32>>();
+  assert_eq!(bins, expected_bins);
+
+  // Runtime-sized arrays may only end a struct.
+  let result = project.define_shader::<Uniforms, _>(|shb| {
+    shb.define_read_write_buffer_binding::<Struct<Misplaced>>("misplaced", 0, 1);
+  });
+  assert_eq!(
+    result.err(),
+    Some(BlacklightError::MisplacedRuntimeArray {
+      struct_name: "Misplaced".to_string(),
+      field: "bins".to_string(),
+    })
+  );
+}
@@ -0,0 +1,222 @@
+use crate::{
+  api::{
+    BlacklightError,
+    Project,
+    data_type::{ Atomic, HostShareableDataType, Struct, StructMappedDataType },
+    builder::{ literal, zero_value },
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, Default, StructMappedDataType)]
+struct Uniforms {
+  bias: i32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, StructMappedDataType)]
+struct Stats {
+  total: Atomic<u32>,
+  max: Atomic<i32>,
+  min: Atomic<i32>,
+  bits: Atomic<u32>,
+}
+
+#[test]
+fn smoketest_atomics() {
+  assert_eq!(<Atomic<u32>>::size(), 4);
+  assert_eq!(<Struct<Stats>>::size(), 16);
+
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut bins_binding = None;
+  let mut stats_binding = None;
+  let mut cells_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let bins = shb.define_read_write_buffer_binding::<Atomic<u32>>("bins", 0, 1);
+    bins_binding = Some(bins.binding());
+    let stats = shb.define_read_write_buffer_binding::<Struct<Stats>>("stats", 0, 2);
+    stats_binding = Some(stats.binding());
+    let cells = shb.define_read_write_buffer_binding::<Atomic<u32>>("cells", 0, 3);
+    cells_binding = Some(cells.binding());
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      // Histogram, discarding the previous bin counts.
+      cbb.add_expr_statement(bins.atomic_add(id.clone() % literal(8), literal(1)));
+
+      // Reductions into the fields of a single struct.
+      let signed = id.cast::<i32>() + uniforms.get::<i32>("bias");
+      let stat = stats.read(literal(0));
+      cbb.add_phony_assignment_statement(
+        stat.field::<Atomic<u32>>("total").atomic_add(id.clone())
+      );
+      cbb.add_expr_statement(stat.field::<Atomic<i32>>("max").atomic_max(signed.clone()));
+      cbb.add_expr_statement(stat.field::<Atomic<i32>>("min").atomic_min(signed));
+      cbb.add_expr_statement(
+        stat.field::<Atomic<u32>>("bits").atomic_or(literal(1u32) << (id.clone() % literal(32)))
+      );
+
+      // A chain of read-modify-write operations on one cell per invocation.
+      let a = cbb.add_let_decl_statement("a", cells.atomic_sub(id.clone(), literal(1)));
+      let b = cbb.add_let_decl_statement("b", cells.atomic_and(id.clone(), literal(0xF0)));
+      let c = cbb.add_let_decl_statement("c", cells.atomic_xor(id.clone(), literal(0x0F)));
+      let d = cbb.add_let_decl_statement(
+        "d",
+        cells.atomic_exchange(id.clone(), a.read() + b.read() + c.read())
+      );
+      cbb.add_expr_statement(
+        cells.atomic_store(id.clone(), cells.atomic_load(id.clone()) + d.read())
+      );
+    }));
+  }).unwrap();
+  let bins_binding = bins_binding.unwrap();
+  let stats_binding = stats_binding.unwrap();
+  let cells_binding = cells_binding.unwrap();
+  let wgsl = shader.generate_wgsl();
+  assert!(wgsl.contains("var<storage, read_write> bins: array<atomic<u32>>;"), "{}", wgsl);
+  assert!(wgsl.contains("max: atomic<i32>,"), "{}", wgsl);
+  assert!(wgsl.contains("atomicMax((&stats[0u].max), "), "{}", wgsl);
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let initial_cells = (0..64).map(|i| 0x100 + i * 7).collect::<Vec<u32>>();
+  let bins_buffer = project.create_buffer::<Atomic<u32>>(8);
+  bins_buffer.write_from_slice(&[Atomic::new(100); 8]);
+  let stats_buffer = project.create_buffer::<Struct<Stats>>(1);
+  stats_buffer.write_from_slice(&[Stats {
+    total: Atomic::new(0),
+    max: Atomic::new(i32::MIN),
+    min: Atomic::new(i32::MAX),
+    bits: Atomic::new(0),
+  }.into()]);
+  let cells_buffer = project.create_buffer::<Atomic<u32>>(64);
+  cells_buffer.write_from_slice(
+    &initial_cells.iter().map(|&cell| cell.into()).collect::<Vec<_>>()
+  );
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&bins_binding, &bins_buffer);
+    bb.bind_buffer(&stats_binding, &stats_buffer);
+    bb.bind_buffer(&cells_binding, &cells_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { bias: -10 }, &bindings, 1);
+
+  let bins = futures::executor::block_on(bins_buffer.read_to_vec())
+    .unwrap()
+    .iter()
+    .map(Atomic::value)
+    .collect::<Vec<u32>>();
+  assert_eq!(bins, [108; 8]);
+
+  let stats =
+    *futures::executor::block_on(stats_buffer.read_to_vec()).unwrap()[0].data();
+  assert_eq!(stats, Stats {
+    total: Atomic::new((0..64).sum()),
+    max: Atomic::new(53),
+    min: Atomic::new(-10),
+    bits: Atomic::new(u32::MAX),
+  });
+
+  let cells = futures::executor::block_on(cells_buffer.read_to_vec())
+    .unwrap()
+    .iter()
+    .map(Atomic::value)
+    .collect::<Vec<u32>>();
+  let expected_cells = initial_cells.iter()
+    .map(|&a| {
+      let b = a - 1;
+      let c = b & 0xF0;
+      let d = c ^ 0x0F;
+      (a + b + c) + d
+    })
+    .collect::<Vec<u32>>();
+  assert_eq!(cells, expected_cells);
+
+  // The compare-exchange result type is predeclared, and left inferred.
+  // It is only validated, as naga's GLSL backend cannot yet write it.
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let flag = shb.define_read_write_buffer_binding::<Atomic<u32>>("flag", 0, 1);
+    let out = shb.define_read_write_buffer_binding::<u32>("out", 0, 2);
+    shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      let result = cbb.add_let_decl_statement(
+        "result",
+        flag.atomic_compare_exchange_weak(literal(0), literal(0), id.clone() + literal(1))
+      );
+      cbb.add_if_statement(result.read().exchanged(), |cbb| {
+        cbb.add_expr_statement(flag.atomic_add(literal(1), literal(1)));
+      });
+      cbb.add_assignment_statement(&out.elem(id), result.read().old_value());
+    });
+  }).unwrap();
+  let wgsl = shader.generate_wgsl();
+  assert!(wgsl.contains("let result = atomicCompareExchangeWeak((&flag[0u]), 0u, "), "{}", wgsl);
+  assert!(wgsl.contains("if (result.exchanged) {"), "{}", wgsl);
+  assert_eq!(shader.validate(), Ok(()));
+}
+
+#[derive(Clone, Copy, Default, StructMappedDataType)]
+struct AtomicUniforms {
+  count: Atomic<u32>,
+}
+
+#[test]
+fn smoketest_atomics_misplaced() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  // Atomics are only allowed in read-write buffers and workgroup variables.
+  let result = project.define_shader::<Uniforms, _>(|shb| {
+    let bins = shb.try_define_read_buffer_binding::<Atomic<u32>>("bins", 0, 1);
+    assert_eq!(bins.err(), Some(BlacklightError::MisplacedAtomic {
+      name: "bins".to_string(),
+      memory_space: "read-only storage".to_string(),
+    }));
+    shb.define_read_buffer_binding::<Struct<Stats>>("stats", 0, 2);
+    shb.define_private_variable("count", zero_value::<Atomic<u32>>());
+    shb.define_workgroup_variable::<Atomic<u32>>("shared_count");
+    shb.define_entrypoint::<u32, _>("main", 64, |cbb, _| {
+      cbb.add_var_decl_statement("local", zero_value::<Atomic<u32>>());
+    });
+  });
+  assert_eq!(result.err(), Some(BlacklightError::Multiple(vec![
+    BlacklightError::MisplacedAtomic {
+      name: "stats".to_string(),
+      memory_space: "read-only storage".to_string(),
+    },
+    BlacklightError::MisplacedAtomic {
+      name: "count".to_string(),
+      memory_space: "private".to_string(),
+    },
+    BlacklightError::MisplacedAtomic {
+      name: "local".to_string(),
+      memory_space: "function".to_string(),
+    },
+  ])));
+
+  // Nor in the uniforms.
+  let result = project.define_shader::<AtomicUniforms, _>(|_| {});
+  let error = result.err().unwrap();
+  assert_eq!(error, BlacklightError::MisplacedAtomic {
+    name: "AtomicUniforms".to_string(),
+    memory_space: "uniform".to_string(),
+  });
+  assert_eq!(error.to_string(), "'AtomicUniforms' holds atomics in the \
+                                 uniform memory space, but atomics are only \
+                                 allowed in read-write storage buffers and \
+                                 workgroup variables");
+
+  // Structs holding atomics cannot be assigned as a whole.
+  let result = project.define_shader::<Uniforms, _>(|shb| {
+    let stats = shb.define_read_write_buffer_binding::<Struct<Stats>>("stats", 0, 1);
+    shb.define_entrypoint::<u32, _>("main", 64, |cbb, _| {
+      cbb.add_assignment_statement(&stats.elem(literal(0)), stats.read(literal(1)));
+    });
+  });
+  assert_eq!(result.err(), Some(BlacklightError::MisplacedStatement {
+    statement: "assignment".to_string(),
+    reason: "on values holding atomics".to_string(),
+  }));
+}
@@ -0,0 +1,123 @@
+use crate::{
+  api::{
+    Project,
+    data_type::StructMappedDataType,
+    builder::literal,
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct Uniforms {
+  seed: u32,
+}
+
+fn first_leading_bit_i32(x: i32) -> i32 {
+  match x {
+    0 | -1 => -1,
+    x if x > 0 => 31 - x.leading_zeros() as i32,
+    x => 31 - x.leading_ones() as i32,
+  }
+}
+
+fn expected_u32(x: u32) -> [u32; 8] {
+  [
+    x.count_ones(),
+    x.leading_zeros(),
+    x.trailing_zeros(),
+    if x == 0 { !0 } else { 31 - x.leading_zeros() },
+    if x == 0 { !0 } else { x.trailing_zeros() },
+    x.reverse_bits(),
+    (x >> 4) & 0xff,
+    (x & !(0xf << 8)) | (0xa << 8),
+  ]
+}
+
+fn expected_i32(x: i32) -> [i32; 2] {
+  [first_leading_bit_i32(x), (x << 20) >> 24]
+}
+
+#[test]
+fn smoketest_bits() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut signed_in_binding = None;
+  let mut out_binding = None;
+  let mut signed_res_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let signed_buf = shb.define_read_buffer_binding::<i32>("signed_in", 0, 1);
+    signed_in_binding = Some(signed_buf.binding());
+    let out_buf = shb.define_read_write_buffer_binding::<u32>("out", 0, 2);
+    out_binding = Some(out_buf.binding());
+    let signed_out_buf =
+      shb.define_read_write_buffer_binding::<i32>("signed_res", 0, 3);
+    signed_res_binding = Some(signed_out_buf.binding());
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      let x = cbb.add_let_decl_statement(
+        "x",
+        id.clone() * uniforms.get::<u32>("seed")
+      ).read();
+      let results = [
+        x.count_one_bits(),
+        x.count_leading_zeros(),
+        x.count_trailing_zeros(),
+        x.first_leading_bit(),
+        x.first_trailing_bit(),
+        x.reverse_bits(),
+        x.extract_bits(&literal(4), &literal(8)),
+        x.insert_bits(&literal(0xa), &literal(8), &literal(4)),
+      ];
+      for (i, result) in results.into_iter().enumerate() {
+        let index = id.clone() * literal(8) + literal(i as u32);
+        cbb.add_assignment_statement(&out_buf.elem(index), result);
+      }
+
+      let s = signed_buf.read(id.clone());
+      let signed_results = [
+        s.first_leading_bit(),
+        s.extract_bits(&literal(4), &literal(8)),
+      ];
+      for (i, result) in signed_results.into_iter().enumerate() {
+        let index = id.clone() * literal(2) + literal(i as u32);
+        cbb.add_assignment_statement(&signed_out_buf.elem(index), result);
+      }
+    }));
+  }).unwrap();
+  let signed_in_binding = signed_in_binding.unwrap();
+  let out_binding = out_binding.unwrap();
+  let signed_res_binding = signed_res_binding.unwrap();
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let seed = 0x9e3779b9u32;
+  let signed = (0..64u32)
+    .map(|i| i.wrapping_mul(seed) as i32)
+    .collect::<Vec<i32>>();
+  let signed_buffer = project.create_buffer::<i32>(64);
+  signed_buffer.write_from_slice(&signed);
+  let out_buffer = project.create_buffer::<u32>(64 * 8);
+  let signed_out_buffer = project.create_buffer::<i32>(64 * 2);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&signed_in_binding, &signed_buffer);
+    bb.bind_buffer(&out_binding, &out_buffer);
+    bb.bind_buffer(&signed_res_binding, &signed_out_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { seed }, &bindings, 1);
+
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  let expected = (0..64u32)
+    .flat_map(|i| expected_u32(i.wrapping_mul(seed)))
+    .collect::<Vec<u32>>();
+  assert_eq!(out, expected);
+  let signed_out =
+    futures::executor::block_on(signed_out_buffer.read_to_vec()).unwrap();
+  let signed_expected = signed.iter()
+    .flat_map(|s| expected_i32(*s))
+    .collect::<Vec<i32>>();
+  assert_eq!(signed_out, signed_expected);
+}
@@ -0,0 +1,138 @@
+use crate::{
+  api::{
+    BlacklightError,
+    Project,
+    data_type::{ HostShareableDataType, Struct, StructMappedDataType, StructFieldVisitor },
+    builder::literal,
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy)]
+struct Uniforms {
+  scale: f32,
+}
+impl StructMappedDataType for Uniforms {
+  const NAME: &'static str = "Uniforms";
+  fn visit_fields<FV>(fv: &mut FV)
+    where FV: StructFieldVisitor<Uniforms>
+  {
+    fv.visit_field::<f32, _, _>("scale", |u| u.scale, |u, v| u.scale = v);
+  }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Particle {
+  pos: [f32; 3],
+  mass: f32,
+  id: u32,
+}
+impl StructMappedDataType for Particle {
+  const NAME: &'static str = "Particle";
+  fn visit_fields<FV>(fv: &mut FV)
+    where FV: StructFieldVisitor<Particle>
+  {
+    fv.visit_field::<[f32; 3], _, _>("pos", |p| p.pos, |p, v| p.pos = v);
+    fv.visit_field::<f32, _, _>("mass", |p| p.mass, |p, v| p.mass = v);
+    fv.visit_field::<u32, _, _>("id", |p| p.id, |p, v| p.id = v);
+  }
+}
+
+#[test]
+fn smoketest_buffer() {
+  // `mass` is packed after the 12 bytes of `pos`, and the 20 bytes of
+  // fields are rounded up to the 16-byte alignment of `pos`.
+  assert_eq!(Struct::<Particle>::alignment(), 16);
+  assert_eq!(Struct::<Particle>::size(), 32);
+  assert_eq!(Struct::<Particle>::array_stride(), 32);
+
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut particles_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let particles_buf =
+      shb.define_read_write_buffer_binding::<Struct<Particle>>("particles", 0, 1);
+    particles_binding = Some(particles_buf.binding());
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 8, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      let particle = particles_buf.read(id.clone());
+      cbb.add_assignment_statement(
+        &particle.field::<f32>("mass"),
+        particle.get::<f32>("mass") * literal(2.0_f32)
+      );
+      cbb.add_assignment_statement(&particle.field::<u32>("id"), id);
+    }));
+  }).unwrap();
+  let main_ep = main_ep.unwrap();
+  let particles_binding = particles_binding.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let particles = (0..16)
+    .map(|i| Particle {
+      pos: [i as f32, 1.0, -(i as f32)],
+      mass: i as f32 + 0.5,
+      id: 1000,
+    })
+    .collect::<Vec<_>>();
+  let particles_buffer = project.create_buffer::<Struct<Particle>>(particles.len());
+  assert_eq!(particles_buffer.len(), 16);
+  particles_buffer.write_from_slice(
+    &particles.iter().map(|p| (*p).into()).collect::<Vec<_>>()
+  );
+
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&particles_binding, &particles_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { scale: 1.0 }, &bindings, 2);
+
+  let result =
+    futures::executor::block_on(particles_buffer.read_to_vec()).unwrap();
+  let result = result.iter().map(|p| *p.data()).collect::<Vec<_>>();
+  let expected = particles.iter().enumerate()
+    .map(|(i, p)| Particle { mass: p.mass * 2.0, id: i as u32, ..*p })
+    .collect::<Vec<_>>();
+  assert_eq!(result, expected);
+}
+
+#[test]
+fn smoketest_buffer_binding_errors() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+  let mut particles_binding = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let particles =
+      shb.define_read_buffer_binding::<Struct<Particle>>("particles", 0, 1);
+    particles_binding = Some(particles.binding());
+    shb.define_read_buffer_binding::<u32>("ids", 0, 2);
+  }).unwrap();
+  let particles_binding = particles_binding.unwrap();
+
+  // A binding of another shader with the same name but a different type.
+  let mut other_binding = None;
+  project.define_shader::<Uniforms, _>(|shb| {
+    let other = shb.define_read_buffer_binding::<f32>("ids", 0, 1);
+    other_binding = Some(other.binding());
+  }).unwrap();
+  let other_binding = other_binding.unwrap();
+
+  let compiled = shader.compile(&project).unwrap();
+  let particles_buffer = project.create_buffer::<Struct<Particle>>(4);
+  let floats_buffer = project.create_buffer::<f32>(4);
+  let result = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&particles_binding, &particles_buffer);
+    bb.bind_buffer(&other_binding, &floats_buffer);
+    assert_eq!(
+      bb.try_bind_buffer(&particles_binding, &particles_buffer),
+      Err(BlacklightError::BufferBindingAlreadyBound {
+        binding: "particles".to_string(),
+      })
+    );
+  });
+  let error = result.unwrap_err();
+  assert_eq!(error.errors(), vec![
+    &BlacklightError::UnknownBufferBinding { binding: "ids".to_string() },
+    &BlacklightError::UnboundBufferBinding { binding: "ids".to_string() },
+  ]);
+}
@@ -0,0 +1,111 @@
+use crate::{
+  api::{
+    Project,
+    data_type::StructMappedDataType,
+    builder::{ literal, mkvec },
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct Uniforms {
+  scale: f32,
+}
+
+fn smoothstep(low: f32, high: f32, x: f32) -> f32 {
+  let t = ((x - low) / (high - low)).clamp(0.0, 1.0);
+  t * t * (3.0 - 2.0 * t)
+}
+
+fn scalar_expected(x: f32) -> f32 {
+  x.floor() + x.fract() * 2.0 + x.sqrt() + x.powf(2.0) + x.exp().ln()
+    + x.sin() * x.sin() + x.cos() * x.cos()
+    + x.atan2(1.0)
+    + (x * 0.75 + 4.0 * 0.25)
+    + smoothstep(0.0, 8.0, x)
+    + (x - 3.0).abs().clamp(0.5, 2.0).clamp(0.75, 1.5)
+}
+
+fn vector_expected(x: f32) -> f32 {
+  let v = [x, 1.0, 2.0];
+  let w = [1.0, 0.0, 0.0];
+  let dot = v[0] * w[0] + v[1] * w[1] + v[2] * w[2];
+  let cross = [
+    v[1] * w[2] - v[2] * w[1],
+    v[2] * w[0] - v[0] * w[2],
+    v[0] * w[1] - v[1] * w[0],
+  ];
+  let length = |a: [f32; 3]| (a[0] * a[0] + a[1] * a[1] + a[2] * a[2]).sqrt();
+  let distance = length([v[0] - w[0], v[1] - w[1], v[2] - w[2]]);
+  dot + length(cross) + 1.0 + distance
+}
+
+#[test]
+fn smoketest_builtins() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut in_binding = None;
+  let mut out_binding = None;
+  let mut ints_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let in_buf = shb.define_read_buffer_binding::<f32>("in", 0, 1);
+    in_binding = Some(in_buf.binding());
+    let out_buf = shb.define_read_write_buffer_binding::<f32>("out", 0, 2);
+    out_binding = Some(out_buf.binding());
+    let int_buf = shb.define_read_write_buffer_binding::<u32>("ints", 0, 3);
+    ints_binding = Some(int_buf.binding());
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      let x = cbb.add_let_decl_statement("x", in_buf.read(id.clone())).read();
+      let scalar =
+        x.floor() + x.fract() * literal(2.0) + x.sqrt() + x.pow(&literal(2.0))
+          + x.exp().log()
+          + x.sin() * x.sin() + x.cos() * x.cos()
+          + x.atan2(&literal(1.0))
+          + x.mix(&literal(4.0), &literal(0.25))
+          + x.smoothstep(&literal(0.0), &literal(8.0))
+          + (x.clone() - literal(3.0)).abs()
+              .min(&literal(2.0))
+              .max(&literal(0.5))
+              .clamp(&literal(0.75), &literal(1.5));
+      let v = mkvec::<[f32; 3], _>((x, literal(1.0), literal(2.0)));
+      let w = literal([1.0, 0.0, 0.0]);
+      let vector = v.dot(&w) + v.cross(&w).length() + v.normalize().length()
+        + v.distance(&w);
+      cbb.add_assignment_statement(&out_buf.elem(id.clone()), scalar + vector);
+      cbb.add_assignment_statement(
+        &int_buf.elem(id.clone()),
+        id.max(&literal(5)).min(&literal(40)).clamp(&literal(10), &literal(30))
+      );
+    }));
+  }).unwrap();
+  let in_binding = in_binding.unwrap();
+  let out_binding = out_binding.unwrap();
+  let ints_binding = ints_binding.unwrap();
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let inputs = (0..64).map(|i| i as f32 / 8.0 + 0.5).collect::<Vec<f32>>();
+  let in_buffer = project.create_buffer::<f32>(64);
+  in_buffer.write_from_slice(&inputs);
+  let out_buffer = project.create_buffer::<f32>(64);
+  let int_buffer = project.create_buffer::<u32>(64);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&in_binding, &in_buffer);
+    bb.bind_buffer(&out_binding, &out_buffer);
+    bb.bind_buffer(&ints_binding, &int_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { scale: 1.0 }, &bindings, 1);
+
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  for (x, actual) in inputs.iter().zip(out) {
+    let expected = scalar_expected(*x) + vector_expected(*x);
+    assert!((actual - expected).abs() < 1e-3 * expected.abs().max(1.0),
+            "x = {}: expected {}, got {}", x, expected, actual);
+  }
+  let ints = futures::executor::block_on(int_buffer.read_to_vec()).unwrap();
+  assert_eq!(ints, (0..64).map(|i: u32| i.clamp(10, 30)).collect::<Vec<u32>>());
+}
@@ -0,0 +1,55 @@
+use crate::{
+  api::{
+    Project,
+    data_type::StructMappedDataType,
+    builder::literal,
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct Uniforms {
+  scale: u32,
+}
+
+#[test]
+fn smoketest_call() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut out_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let out_buf = shb.define_read_write_buffer_binding::<u32>("out", 0, 1);
+    out_binding = Some(out_buf.binding());
+    let store = shb.define_function::<(u32, u32), (), _>(
+      "store", ("index", "value"),
+      |cbb, (index, value)| {
+        cbb.add_assignment_statement(&out_buf.elem(index), value);
+      }
+    );
+    let triple = shb.define_function::<(u32,), u32, _>("triple", ("x",), |cbb, args| {
+      cbb.add_return_statement(args.0 * literal(3));
+    });
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      cbb.add_call_statement(&store, (id.clone(), id.clone() * literal(2)));
+      cbb.add_phony_assignment_statement(triple.call((id,)));
+    }));
+  }).unwrap();
+  let out_binding = out_binding.unwrap();
+  let wgsl = shader.generate_wgsl();
+  assert!(wgsl.contains("store(global_id, (global_id * 2u));"));
+  assert!(wgsl.contains("_ = triple(global_id);"));
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let out_buffer = project.create_buffer::<u32>(64);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&out_binding, &out_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { scale: 1 }, &bindings, 1);
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  assert_eq!(out, (0..64).map(|i| i * 2).collect::<Vec<u32>>());
+}
@@ -0,0 +1,124 @@
+use crate::{
+  api::{
+    Project,
+    data_type::StructMappedDataType,
+    builder::literal,
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct Uniforms {
+  seed: u32,
+}
+
+#[test]
+fn smoketest_cast() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut in_binding = None;
+  let mut floats_binding = None;
+  let mut ints_binding = None;
+  let mut vecs_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let in_buf = shb.define_read_buffer_binding::<f32>("in", 0, 1);
+    in_binding = Some(in_buf.binding());
+    let floats_buf = shb.define_read_write_buffer_binding::<f32>("floats", 0, 2);
+    floats_binding = Some(floats_buf.binding());
+    let ints_buf = shb.define_read_write_buffer_binding::<u32>("ints", 0, 3);
+    ints_binding = Some(ints_buf.binding());
+    let vecs_buf =
+      shb.define_read_write_buffer_binding::<[i32; 2]>("vecs", 0, 4);
+    vecs_binding = Some(vecs_buf.binding());
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      let hash = cbb.add_let_decl_statement(
+        "hash",
+        id.clone() * uniforms.get::<u32>("seed")
+      ).read();
+      let f = in_buf.read(id.clone());
+
+      // Hash to a float in [0, 1).
+      cbb.add_assignment_statement(
+        &floats_buf.elem(id.clone()),
+        (hash.clone() >> literal(8)).cast::<f32>() / literal(16777216.0)
+      );
+
+      let base = id.clone() * literal(4);
+      cbb.add_assignment_statement(
+        &ints_buf.elem(base.clone()),
+        f.bitcast::<u32>()
+      );
+      cbb.add_assignment_statement(
+        &ints_buf.elem(base.clone() + literal(1)),
+        (f.clone() * literal(-3.0)).cast::<i32>().as_unsigned()
+      );
+      cbb.add_assignment_statement(
+        &ints_buf.elem(base.clone() + literal(2)),
+        (id.clone() % literal(3)).cast::<bool>().cast::<u32>()
+      );
+      cbb.add_assignment_statement(
+        &ints_buf.elem(base + literal(3)),
+        hash.as_signed().cast::<f32>().bitcast::<u32>()
+      );
+
+      let v = literal([1.5f32, -2.5]) * f;
+      cbb.add_assignment_statement(&vecs_buf.elem(id), v.cast::<[i32; 2]>());
+    }));
+  }).unwrap();
+  let in_binding = in_binding.unwrap();
+  let floats_binding = floats_binding.unwrap();
+  let ints_binding = ints_binding.unwrap();
+  let vecs_binding = vecs_binding.unwrap();
+  let wgsl = shader.generate_wgsl();
+  assert!(wgsl.contains("bitcast<u32>("));
+  assert!(wgsl.contains("vec2<i32>("));
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let seed = 0x9e3779b9u32;
+  let inputs = (0..64).map(|i| i as f32 * 0.75 - 10.0).collect::<Vec<f32>>();
+  let in_buffer = project.create_buffer::<f32>(64);
+  in_buffer.write_from_slice(&inputs);
+  let floats_buffer = project.create_buffer::<f32>(64);
+  let ints_buffer = project.create_buffer::<u32>(64 * 4);
+  let vecs_buffer = project.create_buffer::<[i32; 2]>(64);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&in_binding, &in_buffer);
+    bb.bind_buffer(&floats_binding, &floats_buffer);
+    bb.bind_buffer(&ints_binding, &ints_buffer);
+    bb.bind_buffer(&vecs_binding, &vecs_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { seed }, &bindings, 1);
+
+  let floats =
+    futures::executor::block_on(floats_buffer.read_to_vec()).unwrap();
+  let expected_floats = (0..64u32)
+    .map(|i| (i.wrapping_mul(seed) >> 8) as f32 / 16777216.0)
+    .collect::<Vec<f32>>();
+  assert_eq!(floats, expected_floats);
+
+  let ints = futures::executor::block_on(ints_buffer.read_to_vec()).unwrap();
+  let expected_ints = (0..64u32)
+    .flat_map(|i| {
+      let f = inputs[i as usize];
+      [
+        f.to_bits(),
+        ((f * -3.0) as i32) as u32,
+        (i % 3 != 0) as u32,
+        (i.wrapping_mul(seed) as i32 as f32).to_bits(),
+      ]
+    })
+    .collect::<Vec<u32>>();
+  assert_eq!(ints, expected_ints);
+
+  let vecs = futures::executor::block_on(vecs_buffer.read_to_vec()).unwrap();
+  let expected_vecs = inputs.iter()
+    .map(|f| [(1.5 * f) as i32, (-2.5 * f) as i32])
+    .collect::<Vec<[i32; 2]>>();
+  assert_eq!(vecs, expected_vecs);
+}
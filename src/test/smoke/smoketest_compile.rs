@@ -0,0 +1,53 @@
+use crate::{
+  api::{
+    Project,
+    data_type::{ StructMappedDataType, StructFieldVisitor },
+    builder::literal,
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy)]
+struct Uniforms {
+  scale: u32,
+}
+impl StructMappedDataType for Uniforms {
+  const NAME: &'static str = "Uniforms";
+  fn visit_fields<FV>(fv: &mut FV)
+    where FV: StructFieldVisitor<Uniforms>
+  {
+    fv.visit_field::<u32, _, _>("scale", |u| u.scale, |u, v| u.scale = v);
+  }
+}
+
+#[test]
+fn smoketest_compile() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut out_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let out_buf = shb.define_read_write_buffer_binding::<u32>("out", 0, 1);
+    out_binding = Some(out_buf.binding());
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      cbb.add_assignment_statement(
+        &out_buf.elem(id.clone()),
+        id * literal(2)
+      );
+    }));
+  }).unwrap();
+  let main_ep = main_ep.unwrap();
+  let out_binding = out_binding.unwrap();
+
+  let compiled = shader.compile(&project).unwrap();
+  let out_buffer = project.create_buffer::<u32>(128);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&out_binding, &out_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { scale: 1 }, &bindings, 2);
+
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  assert_eq!(out, (0..128).map(|i| i * 2).collect::<Vec<u32>>());
+}
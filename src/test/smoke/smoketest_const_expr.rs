@@ -0,0 +1,100 @@
+use crate::{
+  api::{
+    BlacklightError,
+    Project,
+    data_type::StructMappedDataType,
+    builder::literal,
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, Default, StructMappedDataType)]
+struct Uniforms {
+  bias: u32,
+}
+
+#[test]
+fn smoketest_const_expr() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  // Constants derived from other constants.
+  let mut out_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let out = shb.define_read_write_buffer_binding::<u32>("out", 0, 1);
+    out_binding = Some(out.binding());
+    let tile_w = shb.define_constant("TILE_W", 8u32);
+    let tile_h = shb.define_constant("TILE_H", 4u32);
+    let tile_area = shb.define_constant("TILE_AREA", tile_w.read() * tile_h.read());
+    let bits = shb.define_constant("BITS", 5u32);
+    let mask = shb.define_constant(
+      "MASK",
+      (literal(1u32) << bits.read()) - literal(1)
+    );
+    let limit = shb.define_constant(
+      "LIMIT",
+      tile_area.read().max(&mask.read()).count_one_bits()
+    );
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      cbb.add_assignment_statement(
+        &out.elem(id.clone()),
+        (id & mask.read()) + tile_area.read() * limit.read()
+      );
+    }));
+  }).unwrap();
+  let out_binding = out_binding.unwrap();
+  let wgsl = shader.generate_wgsl();
+  assert!(wgsl.contains("const TILE_AREA: u32 = (TILE_W * TILE_H);"), "{}", wgsl);
+  assert!(wgsl.contains("const MASK: u32 = ((1u << BITS) - 1u);"), "{}", wgsl);
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let out_buffer = project.create_buffer::<u32>(64);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&out_binding, &out_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { bias: 0 }, &bindings, 1);
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  // LIMIT = countOneBits(max(32u, 31u)) = 1.
+  let expected = (0..64)
+    .map(|id| (id & 31) + 32)
+    .collect::<Vec<u32>>();
+  assert_eq!(out, expected);
+
+  // Buffer reads, uniforms, variables, overrides and function calls are
+  // not const-expressions, as constants and private variables require.
+  let result = project.define_shader::<Uniforms, _>(|shb| {
+    let input = shb.define_read_buffer_binding::<u32>("input", 0, 1);
+    let state = shb.define_private_variable::<u32>("state", 0);
+    let width = shb.define_override::<u32>("width", Some(8), None);
+    let one = shb.define_function::<(), u32, _>("one", (), |cbb, ()| {
+      cbb.add_return_statement(literal(1));
+    });
+    let uniforms = shb.uniforms();
+    shb.define_constant("A", input.read(literal(0)));
+    shb.define_constant("B", uniforms.get::<u32>("bias") + literal(1));
+    shb.define_constant("C", state.read());
+    shb.define_constant("D", width.read() * literal(2));
+    shb.define_constant("E", one.call(()));
+    shb.define_private_variable("copy", state.read());
+    assert!(shb.try_define_constant("F", literal(2u32) * literal(3)).is_ok());
+  });
+  let error = result.err().unwrap();
+  let non_const = |name: &str, reason: &str| {
+    BlacklightError::NonConstExpression {
+      name: name.to_string(),
+      reason: reason.to_string(),
+    }
+  };
+  assert_eq!(error.errors(), vec![
+    &non_const("A", "it reads buffer 'input'"),
+    &non_const("B", "'uniforms' is not a constant"),
+    &non_const("C", "'state' is not a constant"),
+    &non_const("D", "'width' is not a constant"),
+    &non_const("E", "it calls function 'one'"),
+    &non_const("copy", "'state' is not a constant"),
+  ]);
+}
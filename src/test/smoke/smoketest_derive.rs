@@ -0,0 +1,90 @@
+use crate::{
+  api::{
+    Project,
+    data_type::{ ExprDataType, Struct, StructMappedDataType },
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, StructMappedDataType)]
+struct Point {
+  x: f32,
+  y: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, StructMappedDataType)]
+#[blacklight(rename = "Box2")]
+struct Rect {
+  #[blacklight(rename = "min")]
+  top_left: Point,
+  #[blacklight(rename = "max")]
+  bottom_right: Point,
+  color: [f32; 4],
+}
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct Uniforms {
+  scale: f32,
+}
+
+#[test]
+fn smoketest_derive() {
+  assert_eq!(Rect::NAME, "Box2");
+  let repr = Struct::<Rect>::make_struct_repr();
+  let field_names = repr.fields().iter().map(|f| f.name()).collect::<Vec<_>>();
+  assert_eq!(field_names, vec!["min", "max", "color"]);
+  assert_eq!(repr.get_field("min").unwrap().data_type(), &Struct::<Point>::repr());
+
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut rects_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let rects_buf = shb.define_read_write_buffer_binding::<Struct<Rect>>("rects", 0, 1);
+    rects_binding = Some(rects_buf.binding());
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 4, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      let rect = rects_buf.read(id);
+      cbb.add_assignment_statement(
+        &rect.field::<Struct<Point>>("max"),
+        rect.get::<Struct<Point>>("min")
+      );
+      cbb.add_assignment_statement(
+        &rect.field::<[f32; 4]>("color"),
+        rect.get::<[f32; 4]>("color") * rect.get::<[f32; 4]>("color")
+      );
+    }));
+  }).unwrap();
+  let main_ep = main_ep.unwrap();
+  let rects_binding = rects_binding.unwrap();
+  assert!(shader.generate_wgsl().contains("struct Box2 {"));
+  let compiled = shader.compile(&project).unwrap();
+
+  let rects = (0..4)
+    .map(|i| Rect {
+      top_left: Point { x: i as f32, y: 1.0 },
+      bottom_right: Point { x: 10.0, y: 10.0 },
+      color: [0.5; 4],
+    })
+    .collect::<Vec<_>>();
+  let rects_buffer = project.create_buffer::<Struct<Rect>>(rects.len());
+  rects_buffer.write_from_slice(
+    &rects.iter().map(|r| (*r).into()).collect::<Vec<_>>()
+  );
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&rects_binding, &rects_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { scale: 2.0 }, &bindings, 1);
+
+  let result = futures::executor::block_on(rects_buffer.read_to_vec()).unwrap();
+  let result = result.iter().map(|r| *r.data()).collect::<Vec<_>>();
+  let expected = rects.iter()
+    .map(|r| Rect {
+      bottom_right: r.top_left,
+      color: [0.25; 4],
+      ..*r
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(result, expected);
+}
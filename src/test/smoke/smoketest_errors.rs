@@ -0,0 +1,74 @@
+use crate::{
+  api::{
+    BlacklightError,
+    Project,
+    data_type::{ Struct, StructMappedDataType },
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct Uniforms {
+  scale: f32,
+}
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct Particle {
+  mass: f32,
+  id: u32,
+}
+
+#[test]
+fn smoketest_errors() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let result = project.define_shader::<Uniforms, _>(|shb| {
+    let particles_buf =
+      shb.define_read_write_buffer_binding::<Struct<Particle>>("particles", 0, 1);
+    shb.define_read_buffer_binding::<u32>("reserved", 0, 0);
+    shb.define_read_buffer_binding::<u32>("duplicate", 0, 1);
+    let error = shb.try_define_read_buffer_binding::<u32>("particles", 1, 0).err();
+    assert_eq!(error, Some(BlacklightError::DuplicateBufferBindingName {
+      binding: "particles".to_string(),
+    }));
+
+    shb.define_entrypoint::<u32, _>("main", 8, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      let particle = particles_buf.read(id);
+      if let Err(error) = particle.try_get::<f32>("charge") {
+        cbb.report_error(error);
+      }
+      match particle.try_field::<f32>("id") {
+        Ok(id_field) => cbb.add_assignment_statement(&id_field, particle.get("mass")),
+        Err(error) => cbb.report_error(error),
+      }
+    });
+  });
+
+  let error = result.unwrap_err();
+  assert_eq!(error.errors(), vec![
+    &BlacklightError::ReservedBufferBinding {
+      binding: "reserved".to_string(),
+      group: 0,
+      index: 0,
+    },
+    &BlacklightError::DuplicateBufferBinding {
+      binding: "duplicate".to_string(),
+      existing_binding: "particles".to_string(),
+      group: 0,
+      index: 1,
+    },
+    &BlacklightError::UnknownStructField {
+      struct_name: "Particle".to_string(),
+      field: "charge".to_string(),
+    },
+    &BlacklightError::StructFieldTypeMismatch {
+      struct_name: "Particle".to_string(),
+      field: "id".to_string(),
+      field_type: "u32".to_string(),
+      accessed_type: "f32".to_string(),
+    },
+  ]);
+  assert!(error.to_string().starts_with("4 errors defining shader"));
+}
@@ -0,0 +1,103 @@
+use crate::{
+  api::{
+    BlacklightError,
+    Project,
+    ShaderItem,
+    data_type::StructMappedDataType,
+    builder::literal,
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, Default, StructMappedDataType)]
+struct Uniforms {
+  stride: u32,
+}
+
+#[test]
+fn smoketest_invocation() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  // Record every builtin of a two-dimensional dispatch.
+  let mut out_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let out = shb.define_read_write_buffer_binding::<[u32; 4]>("out", 0, 1);
+    out_binding = Some(out.binding());
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<[u32; 2], _>("main", [8, 4], |cbb, ctx| {
+      let global_id = ctx.global_invocation_id();
+      let local_id = ctx.local_invocation_id();
+      let workgroup_id = ctx.workgroup_id();
+      let num_workgroups = ctx.num_workgroups();
+      let index = cbb.add_let_decl_statement(
+        "index",
+        global_id.y() * uniforms.get::<u32>("stride") + global_id.x()
+      );
+      cbb.add_assignment_statement(&out.elem(index.read()).x(), ctx.local_invocation_index());
+      cbb.add_assignment_statement(
+        &out.elem(index.read()).y(),
+        local_id.y() * literal(8) + local_id.x()
+      );
+      cbb.add_assignment_statement(
+        &out.elem(index.read()).z(),
+        workgroup_id.y() * num_workgroups.x() + workgroup_id.x()
+      );
+      cbb.add_assignment_statement(
+        &out.elem(index.read()).w(),
+        num_workgroups.x() * num_workgroups.y()
+      );
+    }));
+  }).unwrap();
+  let out_binding = out_binding.unwrap();
+  let wgsl = shader.generate_wgsl();
+  assert!(wgsl.contains("@builtin(local_invocation_index)\n  local_index: u32,"), "{}", wgsl);
+  assert!(wgsl.contains("let workgroup_id: vec2<u32> = bl_workgroup_id.xy;"), "{}", wgsl);
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let out_buffer = project.create_buffer::<[u32; 4]>(16 * 12);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&out_binding, &out_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { stride: 16 }, &bindings, [2, 3]);
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  let expected = (0..12)
+    .flat_map(|y| (0..16).map(move |x| {
+      let local_index = (y % 4) * 8 + x % 8;
+      [local_index, local_index, (y / 4) * 2 + x / 8, 6]
+    }))
+    .collect::<Vec<[u32; 4]>>();
+  assert_eq!(out, expected);
+
+  // Only the builtins read are declared as parameters.
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let out = shb.define_read_write_buffer_binding::<u32>("out", 0, 1);
+    shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      cbb.add_assignment_statement(&out.elem(ctx.local_invocation_index()), literal(1));
+    });
+  }).unwrap();
+  let wgsl = shader.generate_wgsl();
+  assert!(wgsl.contains("@builtin(local_invocation_index)"), "{}", wgsl);
+  assert!(!wgsl.contains("global_invocation_id"), "{}", wgsl);
+  assert!(!wgsl.contains("@builtin(workgroup_id)"), "{}", wgsl);
+  assert_eq!(shader.validate(), Ok(()));
+
+  // Workgroup ids are uniform within a workgroup; local ids are not.
+  let result = project.define_shader::<Uniforms, _>(|shb| {
+    shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      cbb.add_if_statement(ctx.workgroup_id().eq(&literal(0)), |cbb| {
+        cbb.add_workgroup_barrier_statement();
+      });
+      cbb.add_if_statement(ctx.local_invocation_index().eq(&literal(0)), |cbb| {
+        cbb.add_workgroup_barrier_statement();
+      });
+    });
+  });
+  assert_eq!(result.err(), Some(BlacklightError::NonUniformBarrier {
+    item: ShaderItem::EntryPoint("main".to_string()),
+    barrier: "workgroupBarrier()".to_string(),
+  }));
+}
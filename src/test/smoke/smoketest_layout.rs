@@ -0,0 +1,66 @@
+use crate::{
+  api::{
+    Project,
+    data_type::{ StructMappedDataType, StructFieldVisitor },
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy)]
+struct Uniforms {
+  scale: u32,
+}
+impl StructMappedDataType for Uniforms {
+  const NAME: &'static str = "Uniforms";
+  fn visit_fields<FV>(fv: &mut FV)
+    where FV: StructFieldVisitor<Uniforms>
+  {
+    fv.visit_field::<u32, _, _>("scale", |u| u.scale, |u, v| u.scale = v);
+  }
+}
+
+fn buffer_binding_type(entry: &wgpu::BindGroupLayoutEntry)
+  -> wgpu::BufferBindingType
+{
+  match entry.ty {
+    wgpu::BindingType::Buffer { ty, .. } => ty,
+    _ => panic!("Expected a buffer binding"),
+  }
+}
+
+#[test]
+fn smoketest_layout() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    shb.define_read_buffer_binding::<u32>("reads", 0, 2);
+    shb.define_write_buffer_binding::<f32>("writes", 1, 0);
+    shb.define_read_write_buffer_binding::<i32>("updates", 0, 1);
+  }).unwrap();
+
+  let groups = shader.bind_group_layout_entries();
+  assert_eq!(groups.len(), 2);
+
+  let bindings = groups[0].iter().map(|e| e.binding).collect::<Vec<_>>();
+  assert_eq!(bindings, vec![0, 1, 2]);
+  assert_eq!(buffer_binding_type(&groups[0][0]),
+             wgpu::BufferBindingType::Uniform);
+  assert_eq!(buffer_binding_type(&groups[0][1]),
+             wgpu::BufferBindingType::Storage { read_only: false });
+  assert_eq!(buffer_binding_type(&groups[0][2]),
+             wgpu::BufferBindingType::Storage { read_only: true });
+
+  assert_eq!(groups[1].len(), 1);
+  assert_eq!(buffer_binding_type(&groups[1][0]),
+             wgpu::BufferBindingType::Storage { read_only: false });
+
+  for entry in groups.iter().flatten() {
+    assert_eq!(entry.visibility, wgpu::ShaderStages::COMPUTE);
+  }
+
+  // The write-only binding must be declared `read_write` to match.
+  assert!(shader.generate_wgsl().contains("var<storage, read_write> writes"));
+
+  let compiled = shader.compile(&project).unwrap();
+  assert_eq!(compiled.bind_group_layouts().len(), 2);
+}
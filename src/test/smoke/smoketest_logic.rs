@@ -0,0 +1,93 @@
+use crate::{
+  api::{
+    Project,
+    data_type::StructMappedDataType,
+    builder::literal,
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct Uniforms {
+  threshold: i32,
+}
+
+#[test]
+fn smoketest_logic() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut in_binding = None;
+  let mut out_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let in_buf = shb.define_read_buffer_binding::<[i32; 3]>("in", 0, 1);
+    in_binding = Some(in_buf.binding());
+    let out_buf = shb.define_read_write_buffer_binding::<u32>("out", 0, 2);
+    out_binding = Some(out_buf.binding());
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      let v = cbb.add_let_decl_statement("v", in_buf.read(id.clone())).read();
+      let threshold = uniforms.get::<i32>("threshold");
+      let below = v.lt(&literal([0, 0, 0]));
+      let above = v.gt(&literal([5, 5, 5]));
+      let conditions = [
+        below.any(),
+        below.all(),
+        (!above.clone()).all(),
+        below.any().and(&above.any()),
+        below.all().or(&above.all()),
+        below.any().eq(&above.any()),
+        v.eq(&literal([3, 3, 3])).all().or(&threshold.gt(&literal(100))),
+        below.eq(&literal([true, false, true])).all(),
+      ];
+      let base = id * literal(conditions.len() as u32);
+      for (i, condition) in conditions.into_iter().enumerate() {
+        let index = base.clone() + literal(i as u32);
+        cbb.add_if_statement(condition, |cbb| {
+          cbb.add_assignment_statement(&out_buf.elem(index), literal(1));
+        });
+      }
+    }));
+  }).unwrap();
+  let in_binding = in_binding.unwrap();
+  let out_binding = out_binding.unwrap();
+  let wgsl = shader.generate_wgsl();
+  assert!(wgsl.contains(" && "));
+  assert!(wgsl.contains(" || "));
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let inputs = (0..64)
+    .map(|i| [i % 4 - 1, i % 9 - 2, (i * 7) % 11 - 3])
+    .collect::<Vec<[i32; 3]>>();
+  let in_buffer = project.create_buffer::<[i32; 3]>(64);
+  in_buffer.write_from_slice(&inputs);
+  let out_buffer = project.create_buffer::<u32>(64 * 8);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&in_binding, &in_buffer);
+    bb.bind_buffer(&out_binding, &out_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { threshold: 0 }, &bindings, 1);
+
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  let expected = inputs.iter()
+    .flat_map(|v| {
+      let below = v.map(|c| c < 0);
+      let above = v.map(|c| c > 5);
+      [
+        below.iter().any(|b| *b),
+        below.iter().all(|b| *b),
+        above.iter().all(|b| !b),
+        below.iter().any(|b| *b) && above.iter().any(|b| *b),
+        below.iter().all(|b| *b) || above.iter().all(|b| *b),
+        below.iter().any(|b| *b) == above.iter().any(|b| *b),
+        v.iter().all(|c| *c == 3),
+        below == [true, false, true],
+      ].map(|b| b as u32)
+    })
+    .collect::<Vec<u32>>();
+  assert_eq!(out, expected);
+}
@@ -0,0 +1,120 @@
+use crate::{
+  api::{
+    BlacklightError,
+    Project,
+    data_type::StructMappedDataType,
+    builder::literal,
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct Uniforms {
+  count: u32,
+}
+
+#[test]
+fn smoketest_loops() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut out_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let out_buf = shb.define_read_write_buffer_binding::<u32>("out", 0, 1);
+    out_binding = Some(out_buf.binding());
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      let count = uniforms.get::<u32>("count");
+
+      // Sum 0..id, skipping multiples of 4.
+      let for_sum = cbb.add_var_decl_statement("for_sum", literal(0u32));
+      cbb.add_for_statement("i", literal(0u32),
+        |_, i| i.lt(&id),
+        |_, i| i + literal(1),
+        |cbb, i| {
+          cbb.add_if_statement((i.clone() % literal(4)).eq(&literal(0)), |cbb| {
+            cbb.add_continue_statement();
+          });
+          cbb.add_assignment_statement(&for_sum.lvalue(), for_sum.read() + i);
+        }
+      );
+
+      // Double until reaching count.
+      let while_val = cbb.add_var_decl_statement("while_val", literal(1u32));
+      cbb.add_while_statement(while_val.read().lt(&count), |cbb| {
+        cbb.add_assignment_statement(
+          &while_val.lvalue(),
+          while_val.read() * literal(2)
+        );
+      });
+
+      // Count steps of 3 until exceeding id, or breaking at 10 steps.
+      let steps = cbb.add_var_decl_statement("steps", literal(0u32));
+      let pos = cbb.add_var_decl_statement("pos", literal(0u32));
+      cbb.add_loop_statement(|cbb| {
+        cbb.add_if_statement(steps.read().ge(&literal(10)), |cbb| {
+          cbb.add_break_statement();
+        });
+        cbb.add_assignment_statement(&steps.lvalue(), steps.read() + literal(1));
+        cbb.add_continuing_block(|cbb| {
+          cbb.add_assignment_statement(&pos.lvalue(), pos.read() + literal(3));
+          cbb.add_break_if_statement(pos.read().gt(&id));
+        });
+      });
+
+      cbb.add_assignment_statement(
+        &out_buf.elem(id.clone()),
+        for_sum.read() * literal(1000) + while_val.read() * literal(100)
+          + steps.read()
+      );
+    }));
+  }).unwrap();
+  let out_binding = out_binding.unwrap();
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let out_buffer = project.create_buffer::<u32>(64);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&out_binding, &out_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { count: 5 }, &bindings, 1);
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  let expected = (0..64u32)
+    .map(|id| {
+      let for_sum = (0..id).filter(|i| i % 4 != 0).sum::<u32>();
+      let steps = (id / 3 + 1).min(10);
+      for_sum * 1000 + 8 * 100 + steps
+    })
+    .collect::<Vec<u32>>();
+  assert_eq!(out, expected);
+
+  // Control flow statements outside of where wgsl allows them.
+  let result = project.define_shader::<Uniforms, _>(|shb| {
+    shb.define_entrypoint::<u32, _>("main", 64, |cbb, _ctx| {
+      cbb.add_break_statement();
+      cbb.add_continuing_block(|_| {});
+      cbb.add_loop_statement(|cbb| {
+        cbb.add_break_if_statement(literal(true));
+        cbb.add_continuing_block(|cbb| {
+          cbb.add_continue_statement();
+          cbb.add_break_if_statement(literal(true));
+        });
+        cbb.add_continuing_block(|_| {});
+      });
+    });
+  });
+  let error = result.err().unwrap();
+  let misplaced = error.errors().into_iter()
+    .map(|error| match error {
+      BlacklightError::MisplacedStatement { statement, .. } => statement.as_str(),
+      other => panic!("Unexpected error {:?}", other),
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(
+    misplaced,
+    ["break", "continuing", "break if", "continue", "continuing"]
+  );
+}
@@ -0,0 +1,175 @@
+use crate::{
+  api::{
+    Project,
+    buffer_attributes::BufferMemorySpaceRepr,
+    data_type::{ DataTypeRepr, HostShareableDataType, StructMappedDataType },
+    builder::literal,
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct Uniforms {
+  scale: f32,
+}
+
+const A: [[f32; 3]; 2] = [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+const B: [[f32; 2]; 3] = [[1.0, 0.0], [0.0, 1.0], [2.0, 2.0]];
+const V: [f32; 3] = [1.0, 2.0, 3.0];
+
+// Column-major matrix product, `matCxR * matKxC`.
+fn mat_mul<const R: usize, const C: usize, const K: usize>(
+  a: [[f32; R]; C],
+  b: [[f32; C]; K],
+) -> [[f32; R]; K] {
+  let mut result = [[0.0; R]; K];
+  for k in 0..K {
+    for r in 0..R {
+      result[k][r] = (0..C).map(|c| a[c][r] * b[k][c]).sum();
+    }
+  }
+  result
+}
+
+fn transpose<const R: usize, const C: usize>(m: [[f32; R]; C]) -> [[f32; C]; R] {
+  let mut result = [[0.0; C]; R];
+  for (c, column) in m.iter().enumerate() {
+    for (r, component) in column.iter().enumerate() {
+      result[r][c] = *component;
+    }
+  }
+  result
+}
+
+#[test]
+fn smoketest_matrix() {
+  // Matrices align as their columns, with vec3 columns padded to 16 bytes.
+  assert_eq!(<[[f32; 3]; 3]>::alignment(), 16);
+  assert_eq!(<[[f32; 3]; 3]>::size(), 48);
+  assert_eq!(<[[f32; 2]; 4]>::alignment(), 8);
+  assert_eq!(<[[f32; 2]; 4]>::size(), 32);
+  assert_eq!(<[[f32; 3]; 2]>::size(), 32);
+  assert_eq!(DataTypeRepr::new_mat3x3_f32().size(BufferMemorySpaceRepr::Uniform), 48);
+  assert_eq!(DataTypeRepr::new_mat4x2_f32().alignment(BufferMemorySpaceRepr::Storage), 8);
+
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut mats_binding = None;
+  let mut out_mat_binding = None;
+  let mut out_vec_binding = None;
+  let mut out_row_binding = None;
+  let mut out_det_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let in_buf = shb.define_read_buffer_binding::<[[f32; 3]; 3]>("mats", 0, 1);
+    mats_binding = Some(in_buf.binding());
+    let mat_buf =
+      shb.define_read_write_buffer_binding::<[[f32; 3]; 3]>("out_mat", 0, 2);
+    out_mat_binding = Some(mat_buf.binding());
+    let vec_buf =
+      shb.define_read_write_buffer_binding::<[f32; 3]>("out_vec", 0, 3);
+    out_vec_binding = Some(vec_buf.binding());
+    let row_buf =
+      shb.define_read_write_buffer_binding::<[f32; 3]>("out_row", 0, 4);
+    out_row_binding = Some(row_buf.binding());
+    let det_buf = shb.define_read_write_buffer_binding::<f32>("out_det", 0, 5);
+    out_det_binding = Some(det_buf.binding());
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      // Held in a var, as naga only allows runtime indexing of matrices
+      // through references.
+      let m = cbb.add_var_decl_statement("m", in_buf.read(id.clone())).read();
+      let scale = uniforms.get::<f32>("scale");
+
+      let result = cbb.add_var_decl_statement(
+        "result",
+        m.clone() * m.transpose() + literal(A) * literal(B) * scale
+      );
+      cbb.add_assignment_statement(
+        &result.lvalue().column(&literal(0)),
+        m.column(&(id.clone() % literal(3))) + literal(V)
+      );
+      cbb.add_assignment_statement(&mat_buf.elem(id.clone()), result.read());
+      cbb.add_assignment_statement(
+        &vec_buf.elem(id.clone()),
+        m.clone() * literal(V)
+      );
+      cbb.add_assignment_statement(
+        &row_buf.elem(id.clone()),
+        literal(V) * m.clone()
+      );
+      cbb.add_assignment_statement(&det_buf.elem(id), m.determinant());
+    }));
+  }).unwrap();
+  let mats_binding = mats_binding.unwrap();
+  let out_mat_binding = out_mat_binding.unwrap();
+  let out_vec_binding = out_vec_binding.unwrap();
+  let out_row_binding = out_row_binding.unwrap();
+  let out_det_binding = out_det_binding.unwrap();
+  let wgsl = shader.generate_wgsl();
+  assert!(wgsl.contains("mat2x3<f32>(1f, 2f, 3f, 4f, 5f, 6f)"), "{}", wgsl);
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let inputs = (0..64)
+    .map(|i| {
+      let f = i as f32;
+      [[f, 1.0, 0.0], [0.0, 2.0, (i % 3) as f32], [1.0, 0.0, 1.0]]
+    })
+    .collect::<Vec<[[f32; 3]; 3]>>();
+  let in_buffer = project.create_buffer::<[[f32; 3]; 3]>(64);
+  in_buffer.write_from_slice(&inputs);
+  let mat_buffer = project.create_buffer::<[[f32; 3]; 3]>(64);
+  let vec_buffer = project.create_buffer::<[f32; 3]>(64);
+  let row_buffer = project.create_buffer::<[f32; 3]>(64);
+  let det_buffer = project.create_buffer::<f32>(64);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&mats_binding, &in_buffer);
+    bb.bind_buffer(&out_mat_binding, &mat_buffer);
+    bb.bind_buffer(&out_vec_binding, &vec_buffer);
+    bb.bind_buffer(&out_row_binding, &row_buffer);
+    bb.bind_buffer(&out_det_binding, &det_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { scale: 2.0 }, &bindings, 1);
+
+  let out_mat = futures::executor::block_on(mat_buffer.read_to_vec()).unwrap();
+  let out_vec = futures::executor::block_on(vec_buffer.read_to_vec()).unwrap();
+  let out_row = futures::executor::block_on(row_buffer.read_to_vec()).unwrap();
+  let out_det = futures::executor::block_on(det_buffer.read_to_vec()).unwrap();
+
+  let ab = mat_mul(A, B);
+  let expected_mat = inputs.iter().enumerate()
+    .map(|(i, &m)| {
+      let mut result = mat_mul(m, transpose(m));
+      for (column, ab_column) in result.iter_mut().zip(ab) {
+        for (component, ab_component) in column.iter_mut().zip(ab_column) {
+          *component += ab_component * 2.0;
+        }
+      }
+      let m_column = m[i % 3];
+      result[0] = [m_column[0] + V[0], m_column[1] + V[1], m_column[2] + V[2]];
+      result
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(out_mat, expected_mat);
+
+  let expected_vec = inputs.iter()
+    .map(|&m| mat_mul(m, [V])[0])
+    .collect::<Vec<_>>();
+  assert_eq!(out_vec, expected_vec);
+
+  let expected_row = inputs.iter()
+    .map(|&m| mat_mul(transpose(m), [V])[0])
+    .collect::<Vec<_>>();
+  assert_eq!(out_row, expected_row);
+
+  let expected_det = inputs.iter()
+    .map(|&[[a, b, c], [d, e, f], [g, h, i]]| {
+      a * (e * i - f * h) - d * (b * i - c * h) + g * (b * f - c * e)
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(out_det, expected_det);
+}
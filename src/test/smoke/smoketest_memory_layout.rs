@@ -0,0 +1,108 @@
+use crate::{
+  api::{
+    Project,
+    buffer_attributes::BufferMemorySpaceRepr,
+    data_type::{ Struct, StructMappedDataType, StructFieldVisitor },
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy)]
+struct Inner {
+  a: f32,
+}
+impl StructMappedDataType for Inner {
+  const NAME: &'static str = "Inner";
+  fn visit_fields<FV>(fv: &mut FV)
+    where FV: StructFieldVisitor<Inner>
+  {
+    fv.visit_field::<f32, _, _>("a", |i| i.a, |i, v| i.a = v);
+  }
+}
+
+#[derive(Clone, Copy)]
+struct Uniforms {
+  scale: f32,
+  inner: Inner,
+  dir: [f32; 3],
+  count: u32,
+}
+impl StructMappedDataType for Uniforms {
+  const NAME: &'static str = "Uniforms";
+  fn visit_fields<FV>(fv: &mut FV)
+    where FV: StructFieldVisitor<Uniforms>
+  {
+    fv.visit_field::<f32, _, _>("scale", |u| u.scale, |u, v| u.scale = v);
+    fv.visit_field::<Struct<Inner>, _, _>("inner",
+      |u| u.inner.into(), |u, v| u.inner = *v.data());
+    fv.visit_field::<[f32; 3], _, _>("dir", |u| u.dir, |u, v| u.dir = v);
+    fv.visit_field::<u32, _, _>("count", |u| u.count, |u, v| u.count = v);
+  }
+}
+
+fn offsets(memory_space: BufferMemorySpaceRepr) -> (Vec<usize>, usize) {
+  let layout = Struct::<Uniforms>::make_struct_repr().layout(memory_space);
+  let offsets = layout.fields().iter().map(|field| field.offset()).collect();
+  (offsets, layout.size())
+}
+
+#[test]
+fn smoketest_memory_layout() {
+  // In the uniform space, `inner` is aligned and padded to 16 bytes.
+  assert_eq!(offsets(BufferMemorySpaceRepr::Storage), (vec![0, 4, 16, 28], 32));
+  assert_eq!(offsets(BufferMemorySpaceRepr::Uniform), (vec![0, 16, 32, 44], 48));
+
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut out_f32_binding = None;
+  let mut out_u32_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let out_f32_buf = shb.define_read_write_buffer_binding::<f32>("out_f32", 0, 1);
+    out_f32_binding = Some(out_f32_buf.binding());
+    let out_u32_buf = shb.define_read_write_buffer_binding::<u32>("out_u32", 0, 2);
+    out_u32_binding = Some(out_u32_buf.binding());
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 1, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      let inner = uniforms.get::<Struct<Inner>>("inner");
+      cbb.add_assignment_statement(
+        &out_f32_buf.elem(id.clone()),
+        inner.get::<f32>("a") * uniforms.get::<f32>("scale")
+      );
+      cbb.add_assignment_statement(
+        &out_u32_buf.elem(id),
+        uniforms.get::<u32>("count")
+      );
+    }));
+  }).unwrap();
+  let main_ep = main_ep.unwrap();
+  let out_f32_binding = out_f32_binding.unwrap();
+  let out_u32_binding = out_u32_binding.unwrap();
+
+  let wgsl = shader.generate_wgsl();
+  assert!(wgsl.contains("@align(16) @size(16) inner: Inner,"), "{}", wgsl);
+
+  let compiled = shader.compile(&project).unwrap();
+  let out_f32_buffer = project.create_buffer::<f32>(1);
+  let out_u32_buffer = project.create_buffer::<u32>(1);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&out_f32_binding, &out_f32_buffer);
+    bb.bind_buffer(&out_u32_binding, &out_u32_buffer);
+  }).unwrap();
+  let uniforms = Uniforms {
+    scale: 3.0,
+    inner: Inner { a: 1.5 },
+    dir: [0.0, 1.0, 0.0],
+    count: 77,
+  };
+  compiled.dispatch(&main_ep, &uniforms, &bindings, 1);
+
+  let out_f32 =
+    futures::executor::block_on(out_f32_buffer.read_to_vec()).unwrap();
+  let out_u32 =
+    futures::executor::block_on(out_u32_buffer.read_to_vec()).unwrap();
+  assert_eq!(out_f32, vec![4.5]);
+  assert_eq!(out_u32, vec![77]);
+}
@@ -0,0 +1,156 @@
+use crate::{
+  api::{
+    BlacklightError,
+    Project,
+    data_type::StructMappedDataType,
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, Default, StructMappedDataType)]
+struct Uniforms {
+  offset: f32,
+}
+
+#[test]
+fn smoketest_overrides() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  // One kernel, specialized for its tile size, scale and sign.
+  let mut out_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let out = shb.define_read_write_buffer_binding::<f32>("out", 0, 1);
+    out_binding = Some(out.binding());
+    let tile = shb.define_override::<u32>("tile", Some(64), Some(0));
+    let scale = shb.define_override::<f32>("scale", None, None);
+    let negate = shb.define_override::<bool>("negate", Some(false), Some(7));
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", &tile, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      let value = cbb.add_var_decl_statement(
+        "value",
+        (id.clone() % tile.read()).cast::<f32>() * scale.read()
+          + uniforms.get::<f32>("offset")
+      );
+      cbb.add_if_statement(negate.read(), |cbb| {
+        cbb.add_assignment_statement(&value.lvalue(), -value.read());
+      });
+      cbb.add_assignment_statement(&out.elem(id), value.read());
+    }));
+  }).unwrap();
+  let out_binding = out_binding.unwrap();
+  let wgsl = shader.generate_wgsl();
+  assert!(wgsl.contains("@id(0) override tile: u32 = 64u;"), "{}", wgsl);
+  assert!(wgsl.contains("override scale: f32;"), "{}", wgsl);
+  assert!(wgsl.contains("@id(7) override negate: bool = false;"), "{}", wgsl);
+  assert!(wgsl.contains("@workgroup_size(tile)"), "{}", wgsl);
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+
+  let out_buffer = project.create_buffer::<f32>(64);
+  let compiled = shader.compile_with_overrides(&project, |ob| {
+    ob.set::<u32>("tile", 16);
+    ob.set::<f32>("scale", 0.5);
+    ob.set::<bool>("negate", true);
+  }).unwrap();
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&out_binding, &out_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { offset: 1.0 }, &bindings, 4);
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  let expected = (0..64)
+    .map(|id| -((id % 16) as f32 * 0.5 + 1.0))
+    .collect::<Vec<f32>>();
+  assert_eq!(out, expected);
+
+  // Defaults apply to the overrides left unset.
+  let compiled = shader.compile_with_overrides(&project, |ob| {
+    ob.set::<f32>("scale", 2.0);
+  }).unwrap();
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&out_binding, &out_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { offset: 1.0 }, &bindings, 1);
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  let expected = (0..64)
+    .map(|id| id as f32 * 2.0 + 1.0)
+    .collect::<Vec<f32>>();
+  assert_eq!(out, expected);
+
+  // Pipeline constant ids must be unique.
+  let result = project.define_shader::<Uniforms, _>(|shb| {
+    shb.define_override::<u32>("width", Some(8), Some(3));
+    shb.define_override::<u32>("height", Some(8), Some(3));
+  });
+  assert_eq!(result.err(), Some(BlacklightError::DuplicateOverrideId {
+    name: "height".to_string(),
+    existing_name: "width".to_string(),
+    id: 3,
+  }));
+}
+
+#[test]
+fn smoketest_override_value_errors() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let tile = shb.define_override::<u32>("tile", Some(64), None);
+    shb.define_override::<f32>("scale", None, None);
+    shb.define_entrypoint::<u32, _>("main", tile, |_, _| {});
+  }).unwrap();
+
+  // Errors are reported from compiling, or directly by `try_set`.
+  let result = shader.compile_with_overrides(&project, |ob| {
+    ob.set::<i32>("tile", 32);
+    ob.set::<f32>("size", 1.0);
+    assert_eq!(ob.try_set::<f32>("scale", 1.0), Ok(()));
+    assert_eq!(ob.try_set::<f32>("scale", 2.0),
+               Err(BlacklightError::OverrideAlreadySet {
+                 name: "scale".to_string(),
+               }));
+  });
+  assert_eq!(result.err(), Some(BlacklightError::Multiple(vec![
+    BlacklightError::OverrideTypeMismatch {
+      name: "tile".to_string(),
+      override_type: "u32".to_string(),
+      value_type: "i32".to_string(),
+    },
+    BlacklightError::UnknownOverride {
+      name: "size".to_string(),
+    },
+  ])));
+
+  // Overrides without a default value must be set.
+  let result = shader.compile(&project);
+  assert_eq!(result.err(), Some(BlacklightError::UnsetOverride {
+    name: "scale".to_string(),
+  }));
+
+  // Overrides used as workgroup sizes must be non-zero.
+  let result = shader.compile_with_overrides(&project, |ob| {
+    ob.set::<u32>("tile", 0);
+    ob.set::<f32>("scale", 1.0);
+  });
+  let error = result.err().unwrap();
+  assert_eq!(error, BlacklightError::InvalidWorkgroupSize {
+    name: "tile".to_string(),
+    value: "0".to_string(),
+  });
+  assert_eq!(error.to_string(), "Override 'tile' is used as a workgroup \
+                                 size, but has value 0, which is not a \
+                                 non-zero u32");
+
+  // Including when their default values are used.
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let tile = shb.define_override::<u32>("tile", Some(0), None);
+    shb.define_entrypoint::<u32, _>("main", tile, |_, _| {});
+  }).unwrap();
+  let expected = Some(BlacklightError::InvalidWorkgroupSize {
+    name: "tile".to_string(),
+    value: "0".to_string(),
+  });
+  assert_eq!(shader.validate().err(), expected);
+  assert_eq!(shader.compile(&project).err(), expected);
+}
@@ -83,10 +83,10 @@ fn smoketest_perlin() {
         );
       }
     );
-  });
+  }).unwrap();
   eprintln!("shader: {:?}", &shader);
-  eprintln!("");
-  eprintln!("");
+  eprintln!();
+  eprintln!();
   let wgsl_code = shader.generate_wgsl();
   eprintln!("wgsl_code:");
   eprintln!("{}", &wgsl_code);
@@ -0,0 +1,142 @@
+use crate::{
+  api::{
+    Project,
+    data_type::{ Struct, StructMappedDataType },
+    builder::{ literal, zero_value },
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, Default, StructMappedDataType)]
+struct Uniforms {
+  seed: u32,
+}
+
+#[derive(Clone, Copy, Default, StructMappedDataType)]
+struct Rng {
+  state: u32,
+  draws: u32,
+}
+
+#[test]
+fn smoketest_private() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  // A random number generator whose state is private to each invocation.
+  let mut out_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let out = shb.define_read_write_buffer_binding::<u32>("out", 0, 1);
+    out_binding = Some(out.binding());
+    let state = shb.define_private_variable::<u32>("state", 7);
+    let uniforms = shb.uniforms();
+    let next = shb.define_function::<(), u32, _>("next", (), |cbb, ()| {
+      cbb.add_assignment_statement(
+        &state.lvalue(),
+        state.read() * literal(1664525) + literal(1013904223)
+      );
+      cbb.add_return_statement(state.read());
+    });
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      cbb.add_assignment_statement(
+        &state.lvalue(),
+        state.read() + id.clone() + uniforms.get::<u32>("seed")
+      );
+      let first = cbb.add_let_decl_statement("first", next.call(()));
+      cbb.add_assignment_statement(&out.elem(id), first.read() ^ next.call(()));
+    }));
+  }).unwrap();
+  let out_binding = out_binding.unwrap();
+  let wgsl = shader.generate_wgsl();
+  assert!(wgsl.contains("var<private> state: u32 = 7u;"), "{}", wgsl);
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let out_buffer = project.create_buffer::<u32>(64);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&out_binding, &out_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { seed: 100 }, &bindings, 1);
+
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  let next = |state: &mut u32| {
+    *state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+    *state
+  };
+  let expected = (0..64)
+    .map(|id| {
+      let mut state = 7 + id + 100;
+      next(&mut state) ^ next(&mut state)
+    })
+    .collect::<Vec<u32>>();
+  assert_eq!(out, expected);
+}
+
+#[test]
+fn smoketest_private_struct() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  // The generator state as a zero-initialized struct, counting its draws.
+  let mut out_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let out = shb.define_read_write_buffer_binding::<u32>("out", 0, 1);
+    out_binding = Some(out.binding());
+    let rng = shb.define_private_variable("rng", zero_value::<Struct<Rng>>());
+    let uniforms = shb.uniforms();
+    let next = shb.define_function::<(), u32, _>("next", (), |cbb, ()| {
+      let state = rng.read().get::<u32>("state");
+      cbb.add_assignment_statement(
+        &rng.read().field::<u32>("state"),
+        state * literal(1664525) + literal(1013904223)
+      );
+      let draws = rng.read().get::<u32>("draws");
+      cbb.add_assignment_statement(
+        &rng.read().field::<u32>("draws"),
+        draws + literal(1)
+      );
+      cbb.add_return_statement(rng.read().get::<u32>("state"));
+    });
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      cbb.add_assignment_statement(
+        &rng.read().field::<u32>("state"),
+        id.clone() + uniforms.get::<u32>("seed")
+      );
+      let first = cbb.add_let_decl_statement("first", next.call(()));
+      cbb.add_assignment_statement(
+        &out.elem(id),
+        (first.read() ^ next.call(())) + rng.read().get::<u32>("draws")
+      );
+    }));
+  }).unwrap();
+  let out_binding = out_binding.unwrap();
+  let wgsl = shader.generate_wgsl();
+  assert!(wgsl.contains("var<private> rng: Rng = Rng();"), "{}", wgsl);
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let out_buffer = project.create_buffer::<u32>(64);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&out_binding, &out_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { seed: 100 }, &bindings, 1);
+
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  let next = |state: &mut u32| {
+    *state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+    *state
+  };
+  let expected = (0..64)
+    .map(|id| {
+      let mut state = id + 100;
+      (next(&mut state) ^ next(&mut state)) + 2
+    })
+    .collect::<Vec<u32>>();
+  assert_eq!(out, expected);
+}
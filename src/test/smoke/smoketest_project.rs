@@ -63,8 +63,8 @@ fn smoketest_project() {
   let project = Project::new(device, queue);
   let shader = project.define_shader::<Uniforms, _>(|shb| {
     // Define some buffer bindings.
-    let ints_buf = shb.define_read_write_buffer_binding::<u32>("ints", 0, 0);
-    let rects_buf = shb.define_read_buffer_binding::<Struct<Rect>>("rects", 0, 1);
+    let ints_buf = shb.define_read_write_buffer_binding::<u32>("ints", 0, 1);
+    let rects_buf = shb.define_read_buffer_binding::<Struct<Rect>>("rects", 0, 2);
 
     // Define a function.
     let foo_func = shb.define_function::<(u32,), u32, _>("foofunc", ("x",), |cbb, args| {
@@ -72,7 +72,8 @@ fn smoketest_project() {
     });
 
     // Define an entrypoint.
-    shb.define_entrypoint::<u32, _>("main", 64, |cbb, id| {
+    shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
       cbb.add_expr_statement(id.clone());
       let var_foo = cbb.add_var_decl_statement("varfoo", id.clone());
       let var_rect = rects_buf.read(literal(0));
@@ -100,10 +101,10 @@ fn smoketest_project() {
       );
       cbb.add_bare_return_statement();
     });
-  });
+  }).unwrap();
   eprintln!("shader: {:?}", &shader);
-  eprintln!("");
-  eprintln!("");
+  eprintln!();
+  eprintln!();
   let wgsl_code = shader.generate_wgsl();
   eprintln!("wgsl_code:");
   eprintln!("{}", &wgsl_code);
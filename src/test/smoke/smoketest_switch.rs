@@ -0,0 +1,92 @@
+use crate::{
+  api::{
+    BlacklightError,
+    Project,
+    data_type::StructMappedDataType,
+    builder::literal,
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct Uniforms {
+  mode: i32,
+}
+
+#[test]
+fn smoketest_switch() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut out_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let out_buf = shb.define_read_write_buffer_binding::<u32>("out", 0, 1);
+    out_binding = Some(out_buf.binding());
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      let value = cbb.add_var_decl_statement("value", literal(0u32));
+      cbb.add_switch_statement(id.clone() % literal(4), |sw| {
+        sw.add_case(&[0], |cbb| {
+          cbb.add_assignment_statement(&value.lvalue(), literal(10));
+        });
+        sw.add_case(&[1, 2], |cbb| {
+          cbb.add_assignment_statement(&value.lvalue(), literal(20));
+          cbb.add_if_statement(id.lt(&literal(32)), |cbb| {
+            cbb.add_break_statement();
+          });
+          cbb.add_assignment_statement(&value.lvalue(), literal(30));
+        });
+        sw.add_default(|cbb| {
+          cbb.add_assignment_statement(&value.lvalue(), literal(40));
+        });
+      });
+      cbb.add_switch_statement(uniforms.get::<i32>("mode"), |sw| {
+        sw.add_case(&[-1], |cbb| {
+          cbb.add_assignment_statement(&value.lvalue(), value.read() + literal(1));
+        });
+        sw.add_default(|_| {});
+      });
+      cbb.add_assignment_statement(&out_buf.elem(id), value.read());
+    }));
+  }).unwrap();
+  let out_binding = out_binding.unwrap();
+  assert!(shader.generate_wgsl().contains("case 1u, 2u: {"));
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let out_buffer = project.create_buffer::<u32>(64);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&out_binding, &out_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { mode: -1 }, &bindings, 1);
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  let expected = (0..64u32)
+    .map(|id| match id % 4 {
+      0 => 11,
+      1 | 2 => if id < 32 { 21 } else { 31 },
+      _ => 41,
+    })
+    .collect::<Vec<u32>>();
+  assert_eq!(out, expected);
+
+  // Duplicate cases and a missing default clause.
+  let result = project.define_shader::<Uniforms, _>(|shb| {
+    shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      cbb.add_switch_statement(id, |sw| {
+        sw.add_case(&[1, 2], |_| {});
+        sw.add_case(&[3, 2], |_| {});
+      });
+    });
+  });
+  assert_eq!(
+    result.err(),
+    Some(BlacklightError::Multiple(vec![
+      BlacklightError::DuplicateSwitchCase { case: "2".to_string() },
+      BlacklightError::MissingSwitchDefault,
+    ]))
+  );
+}
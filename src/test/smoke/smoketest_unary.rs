@@ -0,0 +1,90 @@
+use crate::{
+  api::{
+    Project,
+    data_type::StructMappedDataType,
+    builder::literal,
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct Uniforms {
+  scale: f32,
+}
+
+#[test]
+fn smoketest_unary() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut in_binding = None;
+  let mut out_binding = None;
+  let mut floats_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let in_buf = shb.define_read_buffer_binding::<i32>("in", 0, 1);
+    in_binding = Some(in_buf.binding());
+    let out_buf = shb.define_read_write_buffer_binding::<i32>("out", 0, 2);
+    out_binding = Some(out_buf.binding());
+    let floats_buf =
+      shb.define_read_write_buffer_binding::<[f32; 2]>("floats", 0, 3);
+    floats_binding = Some(floats_buf.binding());
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      let x = cbb.add_let_decl_statement("x", in_buf.read(id.clone())).read();
+      let base = id.clone() * literal(4);
+      cbb.add_assignment_statement(&out_buf.elem(base.clone()), -x.clone());
+      cbb.add_assignment_statement(
+        &out_buf.elem(base.clone() + literal(1)),
+        !x.clone()
+      );
+      cbb.add_assignment_statement(
+        &out_buf.elem(base.clone() + literal(2)),
+        -literal(-5) + x.clone()
+      );
+      let positive = cbb.add_var_decl_statement("positive", literal(1i32));
+      cbb.add_if_statement(!x.gt(&literal(0)), |cbb| {
+        cbb.add_assignment_statement(&positive.lvalue(), literal(0));
+      });
+      cbb.add_assignment_statement(
+        &out_buf.elem(base + literal(3)),
+        positive.read()
+      );
+
+      let scale = uniforms.get::<f32>("scale");
+      cbb.add_assignment_statement(
+        &floats_buf.elem(id),
+        -(literal([1.0, -2.0]) * scale)
+      );
+    }));
+  }).unwrap();
+  let in_binding = in_binding.unwrap();
+  let out_binding = out_binding.unwrap();
+  let floats_binding = floats_binding.unwrap();
+  assert!(shader.generate_wgsl().contains("(-(-5i))"));
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let inputs = (0..64).map(|i| i - 32).collect::<Vec<i32>>();
+  let in_buffer = project.create_buffer::<i32>(64);
+  in_buffer.write_from_slice(&inputs);
+  let out_buffer = project.create_buffer::<i32>(64 * 4);
+  let floats_buffer = project.create_buffer::<[f32; 2]>(64);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&in_binding, &in_buffer);
+    bb.bind_buffer(&out_binding, &out_buffer);
+    bb.bind_buffer(&floats_binding, &floats_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { scale: 1.5 }, &bindings, 1);
+
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  let expected = inputs.iter()
+    .flat_map(|x| [-x, !x, 5 + x, (*x > 0) as i32])
+    .collect::<Vec<i32>>();
+  assert_eq!(out, expected);
+  let floats =
+    futures::executor::block_on(floats_buffer.read_to_vec()).unwrap();
+  assert_eq!(floats, vec![[-1.5, 3.0]; 64]);
+}
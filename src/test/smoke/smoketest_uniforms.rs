@@ -0,0 +1,248 @@
+use crate::{
+  api::{
+    BlacklightError,
+    Project,
+    builder::literal,
+    data_type::{ Array, Struct, StructMappedDataType, StructFieldVisitor },
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy)]
+struct Uniforms {
+  scale: u32,
+  offset: u32,
+}
+impl StructMappedDataType for Uniforms {
+  const NAME: &'static str = "Uniforms";
+  fn visit_fields<FV>(fv: &mut FV)
+    where FV: StructFieldVisitor<Uniforms>
+  {
+    fv.visit_field::<u32, _, _>("scale", |u| u.scale, |u, v| u.scale = v);
+    fv.visit_field::<u32, _, _>("offset", |u| u.offset, |u, v| u.offset = v);
+  }
+}
+
+#[test]
+fn smoketest_uniforms() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut in_binding = None;
+  let mut out_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let in_buf = shb.define_read_buffer_binding::<u32>("in", 0, 1);
+    in_binding = Some(in_buf.binding());
+    let out_buf = shb.define_read_write_buffer_binding::<u32>("out", 0, 2);
+    out_binding = Some(out_buf.binding());
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      cbb.add_assignment_statement(
+        &out_buf.elem(id.clone()),
+        in_buf.read(id) * uniforms.get::<u32>("scale")
+          + uniforms.get::<u32>("offset")
+          + in_buf.length()
+      );
+    }));
+  }).unwrap();
+  let main_ep = main_ep.unwrap();
+  let in_binding = in_binding.unwrap();
+  let out_binding = out_binding.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let in_buffer = project.create_buffer::<u32>(64);
+  in_buffer.write_from_slice(&(0..64).collect::<Vec<u32>>());
+  let out_buffer = project.create_buffer::<u32>(64);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&in_binding, &in_buffer);
+    bb.bind_buffer(&out_binding, &out_buffer);
+  }).unwrap();
+
+  // Dispatch twice to check that changed uniforms are uploaded.
+  for (scale, offset) in [(2, 1), (3, 5)] {
+    compiled.dispatch(&main_ep, &Uniforms { scale, offset }, &bindings, 1);
+    let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+    let expected = (0..64)
+      .map(|i| i * scale + offset + 64)
+      .collect::<Vec<u32>>();
+    assert_eq!(out, expected);
+  }
+}
+
+#[test]
+fn smoketest_uniforms_same_encoder() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut out_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let out_buf = shb.define_read_write_buffer_binding::<u32>("out", 0, 1);
+    out_binding = Some(out_buf.binding());
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      cbb.add_assignment_statement(
+        &out_buf.elem(id.clone() + uniforms.get::<u32>("offset")),
+        id * uniforms.get::<u32>("scale")
+      );
+    }));
+  }).unwrap();
+  let main_ep = main_ep.unwrap();
+  let out_binding = out_binding.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let out_buffer = project.create_buffer::<u32>(128);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&out_binding, &out_buffer);
+  }).unwrap();
+
+  // Each dispatch writes its own half of the buffer with its own scale.
+  let mut encoder = project.device().create_command_encoder(
+    &wgpu::CommandEncoderDescriptor::default()
+  );
+  for (scale, offset) in [(2, 0), (3, 64)] {
+    compiled.encode_dispatch(
+      &mut encoder, &main_ep, &Uniforms { scale, offset }, &bindings, 1
+    );
+  }
+  project.queue().submit(Some(encoder.finish()));
+
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  let expected = (0..64).map(|i| i * 2)
+    .chain((0..64).map(|i| i * 3))
+    .collect::<Vec<u32>>();
+  assert_eq!(out, expected);
+}
+
+#[test]
+fn smoketest_uniforms_unchanged() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut out_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let out_buf = shb.define_read_write_buffer_binding::<u32>("out", 0, 1);
+    out_binding = Some(out_buf.binding());
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      cbb.add_assignment_statement(
+        &out_buf.elem(id.clone()),
+        id * uniforms.get::<u32>("scale") + uniforms.get::<u32>("offset")
+      );
+    }));
+  }).unwrap();
+  let main_ep = main_ep.unwrap();
+  let out_binding = out_binding.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let out_buffer = project.create_buffer::<u32>(64);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&out_binding, &out_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { scale: 2, offset: 0 }, &bindings, 1);
+
+  // Overwrite the scale behind the bindings' back: a dispatch with the
+  // same uniforms does not upload them again, so it sees the new scale.
+  project.queue().write_buffer(
+    bindings.uniforms_buffer(), 0, &5_u32.to_le_bytes()
+  );
+  compiled.dispatch(&main_ep, &Uniforms { scale: 2, offset: 0 }, &bindings, 1);
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  assert_eq!(out, (0..64).map(|i| i * 5).collect::<Vec<u32>>());
+
+  // Changed uniforms are uploaded.
+  compiled.dispatch(&main_ep, &Uniforms { scale: 3, offset: 1 }, &bindings, 1);
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  assert_eq!(out, (0..64).map(|i| i * 3 + 1).collect::<Vec<u32>>());
+}
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct RowUniforms {
+  rows: Array<[u32; 4], 2>,
+}
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct TableUniforms {
+  table: Array<u32, 4>,
+}
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct Weights {
+  weights: Array<f32, 2>,
+}
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct NestedUniforms {
+  grid: Array<Array<u32, 4>, 2>,
+  weights: Struct<Weights>,
+}
+
+#[test]
+fn smoketest_uniforms_arrays() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  // Arrays of vec4s have a stride of 16, as the uniform space requires.
+  let mut out_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<RowUniforms, _>(|shb| {
+    let out_buf = shb.define_read_write_buffer_binding::<u32>("out", 0, 1);
+    out_binding = Some(out_buf.binding());
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 8, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      let row = uniforms.get::<Array<[u32; 4], 2>>("rows")
+        .index(&(id.clone() % literal(2)));
+      cbb.add_assignment_statement(&out_buf.elem(id), row.x() + row.w());
+    }));
+  }).unwrap();
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let out_binding = out_binding.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+  let out_buffer = project.create_buffer::<u32>(8);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&out_binding, &out_buffer);
+  }).unwrap();
+  let uniforms = RowUniforms {
+    rows: Array::new([[1, 2, 3, 4], [10, 20, 30, 40]]),
+  };
+  compiled.dispatch(&main_ep, &uniforms, &bindings, 1);
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  assert_eq!(out, [5, 50, 5, 50, 5, 50, 5, 50]);
+
+  // Arrays of scalars have a stride of 4, which wgsl rejects in uniforms.
+  let result = project.define_shader::<TableUniforms, _>(|_| {});
+  let error = result.err().unwrap();
+  assert_eq!(error, BlacklightError::InvalidUniformArrayStride {
+    struct_name: "TableUniforms".to_string(),
+    field: "table".to_string(),
+    stride: 4,
+  });
+  assert_eq!(error.to_string(), "Array field 'table' of struct TableUniforms \
+                                 has stride 4, but arrays in uniforms need a \
+                                 stride that is a multiple of 16");
+
+  // So are nested arrays, and arrays in structs used by the uniforms.
+  let result = project.define_shader::<NestedUniforms, _>(|_| {});
+  let error = result.err().unwrap();
+  let mut errors = error.errors();
+  errors.sort_by_key(|error| error.to_string());
+  assert_eq!(errors, vec![
+    &BlacklightError::InvalidUniformArrayStride {
+      struct_name: "NestedUniforms".to_string(),
+      field: "grid".to_string(),
+      stride: 4,
+    },
+    &BlacklightError::InvalidUniformArrayStride {
+      struct_name: "Weights".to_string(),
+      field: "weights".to_string(),
+      stride: 4,
+    },
+  ]);
+}
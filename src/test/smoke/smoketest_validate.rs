@@ -0,0 +1,57 @@
+use crate::{
+  api::{
+    BlacklightError,
+    Project,
+    ShaderItem,
+    data_type::StructMappedDataType,
+    builder::literal,
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct Uniforms {
+  scale: u32,
+}
+
+#[test]
+fn smoketest_validate() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let valid = project.define_shader::<Uniforms, _>(|shb| {
+    let out_buf = shb.define_read_write_buffer_binding::<u32>("out", 0, 1);
+    let uniforms = shb.uniforms();
+    let double = shb.define_function::<(u32,), u32, _>("double", ("x",), |cbb, args| {
+      cbb.add_return_statement(args.0 * literal(2));
+    });
+    shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      cbb.add_assignment_statement(
+        &out_buf.elem(id.clone()),
+        double.call((id,)) + uniforms.get::<u32>("scale") + out_buf.length()
+      );
+    });
+  }).unwrap();
+  assert_eq!(valid.validate(), Ok(()));
+
+  // A bare expression statement is not valid wgsl.
+  let invalid = project.define_shader::<Uniforms, _>(|shb| {
+    shb.define_function::<(u32,), u32, _>("double", ("x",), |cbb, args| {
+      cbb.add_return_statement(args.0 * literal(2));
+    });
+    shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      cbb.add_expr_statement(id);
+    });
+  }).unwrap();
+  match invalid.validate() {
+    Err(BlacklightError::InvalidWgsl { item, line, .. }) => {
+      assert_eq!(item, Some(ShaderItem::EntryPoint("main".to_string())));
+      let wgsl = invalid.generate_wgsl();
+      let line_text = wgsl.lines().nth(line.unwrap() as usize - 1).unwrap();
+      assert_eq!(line_text.trim(), "global_id;");
+    },
+    other => panic!("Expected invalid wgsl, got {:?}", other),
+  }
+}
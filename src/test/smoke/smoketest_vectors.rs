@@ -0,0 +1,80 @@
+use crate::{
+  api::{
+    Project,
+    data_type::{ StructMappedDataType, X, Y, Z, W },
+    builder::literal,
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, StructMappedDataType)]
+struct Uniforms {
+  lane: u32,
+}
+
+#[test]
+fn smoketest_vectors() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  let mut in_binding = None;
+  let mut out_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let in_buf = shb.define_read_buffer_binding::<[f32; 4]>("in", 0, 1);
+    in_binding = Some(in_buf.binding());
+    let out_buf = shb.define_read_write_buffer_binding::<[f32; 4]>("out", 0, 2);
+    out_binding = Some(out_buf.binding());
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      let v = cbb.add_let_decl_statement("v", in_buf.read(id.clone())).read();
+      let lane = uniforms.get::<u32>("lane");
+
+      // Build the result from swizzles, then patch single components.
+      let result = cbb.add_var_decl_statement(
+        "result",
+        v.swizzle4::<W, Z, Y, X>() + v.swizzle2::<X, Y>().swizzle4::<X, X, Y, Y>()
+      );
+      cbb.add_assignment_statement(
+        &result.lvalue().x(),
+        v.swizzle3::<Y, Z, W>().dot(&literal([1.0, 1.0, 1.0]))
+      );
+      cbb.add_assignment_statement(
+        &result.lvalue().index(&lane),
+        v.index(&lane) * literal(10.0)
+      );
+      cbb.add_assignment_statement(&out_buf.elem(id.clone()), result.read());
+      cbb.add_assignment_statement(&out_buf.elem(id).w(), v.z());
+    }));
+  }).unwrap();
+  let in_binding = in_binding.unwrap();
+  let out_binding = out_binding.unwrap();
+  let wgsl = shader.generate_wgsl();
+  assert!(wgsl.contains("v.wzyx"));
+  assert!(wgsl.contains("result.x = "));
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let inputs = (0..64)
+    .map(|i| {
+      let f = i as f32;
+      [f, f + 1.0, f * 2.0, f * 3.0]
+    })
+    .collect::<Vec<[f32; 4]>>();
+  let in_buffer = project.create_buffer::<[f32; 4]>(64);
+  in_buffer.write_from_slice(&inputs);
+  let out_buffer = project.create_buffer::<[f32; 4]>(64);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&in_binding, &in_buffer);
+    bb.bind_buffer(&out_binding, &out_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { lane: 2 }, &bindings, 1);
+
+  let out = futures::executor::block_on(out_buffer.read_to_vec()).unwrap();
+  let expected = inputs.iter()
+    .map(|&[x, y, z, w]| [y + z + w, z + x, z * 10.0, z])
+    .collect::<Vec<[f32; 4]>>();
+  assert_eq!(out, expected);
+}
@@ -0,0 +1,174 @@
+use crate::{
+  api::{
+    BlacklightError,
+    Project,
+    ShaderItem,
+    data_type::{ Array, Atomic, StructMappedDataType },
+    builder::literal,
+  },
+  test::util,
+};
+
+#[derive(Clone, Copy, Default, StructMappedDataType)]
+struct Uniforms {
+  scale: u32,
+}
+
+#[test]
+fn smoketest_workgroup() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  // Tree reduction of each workgroup's inputs through a shared tile.
+  let mut input_binding = None;
+  let mut sums_binding = None;
+  let mut main_ep = None;
+  let shader = project.define_shader::<Uniforms, _>(|shb| {
+    let input = shb.define_read_buffer_binding::<u32>("input", 0, 1);
+    input_binding = Some(input.binding());
+    let sums = shb.define_read_write_buffer_binding::<u32>("sums", 0, 2);
+    sums_binding = Some(sums.binding());
+    let tile = shb.define_workgroup_variable::<Array<u32, 64>>("tile");
+    let count = shb.define_workgroup_variable::<Atomic<u32>>("count");
+    let uniforms = shb.uniforms();
+    main_ep = Some(shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      let local = cbb.add_let_decl_statement("local", id.clone() % literal(64));
+      cbb.add_assignment_statement(
+        &tile.lvalue().index(&local.read()),
+        input.read(id.clone()) * uniforms.get::<u32>("scale")
+      );
+      cbb.add_expr_statement(count.lvalue().atomic_add(literal(1)));
+      cbb.add_workgroup_barrier_statement();
+      cbb.add_for_statement("stride", literal(32u32),
+        |_, stride| stride.gt(&literal(0)),
+        |_, stride| stride >> literal(1),
+        |cbb, stride| {
+          cbb.add_if_statement(local.read().lt(&stride), |cbb| {
+            cbb.add_assignment_statement(
+              &tile.lvalue().index(&local.read()),
+              tile.read().index(&local.read())
+                + tile.read().index(&(local.read() + stride))
+            );
+          });
+          cbb.add_workgroup_barrier_statement();
+        }
+      );
+      cbb.add_if_statement(local.read().eq(&literal(0)), |cbb| {
+        cbb.add_assignment_statement(
+          &sums.elem(id.clone() / literal(64)),
+          tile.read().index(&literal(0)) + count.lvalue().atomic_load()
+        );
+      });
+      cbb.add_storage_barrier_statement();
+    }));
+  }).unwrap();
+  let input_binding = input_binding.unwrap();
+  let sums_binding = sums_binding.unwrap();
+  let wgsl = shader.generate_wgsl();
+  assert!(wgsl.contains("var<workgroup> tile: array<u32, 64>;"), "{}", wgsl);
+  assert!(wgsl.contains("var<workgroup> count: atomic<u32>;"), "{}", wgsl);
+  assert!(wgsl.contains("workgroupBarrier();"), "{}", wgsl);
+  assert!(wgsl.contains("storageBarrier();"), "{}", wgsl);
+  assert_eq!(shader.validate(), Ok(()));
+  let main_ep = main_ep.unwrap();
+  let compiled = shader.compile(&project).unwrap();
+
+  let inputs = (0..256).collect::<Vec<u32>>();
+  let input_buffer = project.create_buffer::<u32>(256);
+  input_buffer.write_from_slice(&inputs);
+  let sums_buffer = project.create_buffer::<u32>(4);
+  let bindings = compiled.define_bindings(|bb| {
+    bb.bind_buffer(&input_binding, &input_buffer);
+    bb.bind_buffer(&sums_binding, &sums_buffer);
+  }).unwrap();
+  compiled.dispatch(&main_ep, &Uniforms { scale: 2 }, &bindings, 4);
+
+  let sums = futures::executor::block_on(sums_buffer.read_to_vec()).unwrap();
+  let expected = inputs.chunks(64)
+    .map(|chunk| chunk.iter().map(|input| input * 2).sum::<u32>() + 64)
+    .collect::<Vec<u32>>();
+  assert_eq!(sums, expected);
+
+  // Barriers may not be reached in control flow depending on the id,
+  // directly, through values derived from it, or after an early return.
+  let result = project.define_shader::<Uniforms, _>(|shb| {
+    let sync = shb.define_function::<(), (), _>("sync", (), |cbb, ()| {
+      cbb.add_workgroup_barrier_statement();
+    });
+    shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      cbb.add_if_statement(id.lt(&literal(32)), |cbb| {
+        cbb.add_workgroup_barrier_statement();
+      });
+      let parity = cbb.add_var_decl_statement("parity", literal(0u32));
+      cbb.add_assignment_statement(&parity.lvalue(), id.clone() % literal(2));
+      cbb.add_while_statement(parity.read().eq(&literal(1)), |cbb| {
+        cbb.add_call_statement(&sync, ());
+        cbb.add_break_statement();
+      });
+      cbb.add_if_statement(id.eq(&literal(0)), |cbb| {
+        cbb.add_bare_return_statement();
+      });
+      cbb.add_storage_barrier_statement();
+    });
+  });
+  let error = result.err().unwrap();
+  let entry_point = ShaderItem::EntryPoint("main".to_string());
+  assert_eq!(error.errors(), vec![
+    &BlacklightError::NonUniformBarrier {
+      item: entry_point.clone(),
+      barrier: "workgroupBarrier()".to_string(),
+    },
+    &BlacklightError::NonUniformBarrier {
+      item: entry_point.clone(),
+      barrier: "sync()".to_string(),
+    },
+    &BlacklightError::NonUniformBarrier {
+      item: entry_point,
+      barrier: "storageBarrier()".to_string(),
+    },
+  ]);
+}
+
+#[test]
+fn smoketest_workgroup_barrier_args() {
+  let (device, queue) = util::get_device_and_queue();
+  let project = Project::new(device, queue);
+
+  // A barrier depending on a function argument is only reached in uniform
+  // control flow if the argument is uniform at every call, including calls
+  // passing it on through another function.
+  let result = project.define_shader::<Uniforms, _>(|shb| {
+    let sync_if_zero =
+      shb.define_function::<(u32,), (), _>("sync_if_zero", ("arg",), |cbb, (arg,)| {
+        cbb.add_if_statement(arg.eq(&literal(0)), |cbb| {
+          cbb.add_workgroup_barrier_statement();
+        });
+      });
+    let forward =
+      shb.define_function::<(u32,), (), _>("forward", ("arg",), |cbb, (arg,)| {
+        cbb.add_call_statement(&sync_if_zero, (arg,));
+      });
+    let uniforms = shb.uniforms();
+    shb.define_entrypoint::<u32, _>("main", 64, |cbb, ctx| {
+      let id = ctx.global_invocation_id();
+      cbb.add_call_statement(&sync_if_zero, (literal(1u32),));
+      cbb.add_call_statement(&forward, (uniforms.get::<u32>("scale"),));
+      cbb.add_call_statement(&sync_if_zero, (id.clone(),));
+      cbb.add_call_statement(&forward, (id * literal(2),));
+    });
+  });
+  let error = result.err().unwrap();
+  let entry_point = ShaderItem::EntryPoint("main".to_string());
+  assert_eq!(error.errors(), vec![
+    &BlacklightError::NonUniformBarrier {
+      item: entry_point.clone(),
+      barrier: "sync_if_zero()".to_string(),
+    },
+    &BlacklightError::NonUniformBarrier {
+      item: entry_point,
+      barrier: "forward()".to_string(),
+    },
+  ]);
+}
@@ -1,10 +1,7 @@
-/**
+/*!
  * Utility routines for testing.
  */
 
-use wgpu;
-use futures;
-
 /** Obtain a new wgpu device and queue and return it. */
 pub(crate) fn get_device_and_queue() -> (wgpu::Device, wgpu::Queue) {
     let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
@@ -17,3 +14,4 @@ pub(crate) fn get_device_and_queue() -> (wgpu::Device, wgpu::Queue) {
     )).unwrap();
     (device, queue)
 }
+
@@ -0,0 +1,63 @@
+use crate::model::{ BuiltinFn, ExpressionModel, VariableBindingModel };
+
+/**
+ * Check that an expression is a wgsl const-expression, i.e. that it is
+ * built only from literals, the given module-scope constants and builtins
+ * which may be evaluated at shader creation time.
+ *
+ * Returns the reason the expression is rejected, if it is.
+ */
+pub(crate) fn check_const_expr(
+  expr: &ExpressionModel,
+  constants: &[VariableBindingModel],
+) -> Option<String> {
+  let mut reason = None;
+  expr.any(&mut |expr| {
+    reason = non_const_reason(expr, constants);
+    reason.is_some()
+  });
+  reason
+}
+
+// Get the reason a single expression is not const, ignoring its operands.
+fn non_const_reason(
+  expr: &ExpressionModel,
+  constants: &[VariableBindingModel],
+) -> Option<String> {
+  match expr {
+    ExpressionModel::Identifier(ident_expr) => {
+      let name = ident_expr.identifier();
+      let is_constant = constants.iter()
+        .any(|constant| constant.name() == name);
+      (!is_constant).then(|| {
+        format!("'{}' is not a constant", name.as_str())
+      })
+    },
+    ExpressionModel::BufferRead(buffer_read) =>
+      Some(format!("it reads buffer '{}'", buffer_read.buffer_name().as_str())),
+    ExpressionModel::FunctionCall(function_call) =>
+      Some(format!("it calls function '{}'",
+                   function_call.function_name().as_str())),
+    ExpressionModel::BuiltinCall(builtin_call) => {
+      let function = builtin_call.function();
+      (!is_const_builtin(function)).then(|| {
+        format!("builtin '{}' is not const-evaluable", function.name())
+      })
+    },
+    ExpressionModel::Reference(_) =>
+      Some("it takes a reference".to_string()),
+    _ => None,
+  }
+}
+
+// Check whether a builtin may be called in const-expressions.
+fn is_const_builtin(function: BuiltinFn) -> bool {
+  !matches!(function,
+    BuiltinFn::ArrayLength |
+    BuiltinFn::AtomicLoad | BuiltinFn::AtomicStore |
+    BuiltinFn::AtomicAdd | BuiltinFn::AtomicSub |
+    BuiltinFn::AtomicMax | BuiltinFn::AtomicMin |
+    BuiltinFn::AtomicAnd | BuiltinFn::AtomicOr | BuiltinFn::AtomicXor |
+    BuiltinFn::AtomicExchange | BuiltinFn::AtomicCompareExchangeWeak
+  )
+}
@@ -0,0 +1,9 @@
+mod const_expr;
+mod uniformity;
+mod wgsl;
+
+pub(crate) use self::{
+  const_expr::check_const_expr,
+  uniformity::check_barrier_uniformity,
+  wgsl::validate_wgsl,
+};
@@ -0,0 +1,414 @@
+use std::collections::{ HashMap, HashSet };
+use crate::{
+  api::{
+    buffer_attributes::BufferDispositionRepr,
+    BlacklightError,
+    ShaderItem,
+  },
+  model::{
+    BuiltinFn,
+    CodeBlockModel,
+    ComputeBuiltin,
+    ExpressionModel,
+    FunctionCallExprModel,
+    FunctionModel,
+    IdentifierModel,
+    LvalueModel,
+    ShaderModel,
+    StatementModel,
+  },
+};
+
+/**
+ * Check that barriers are only reached in uniform control flow, as wgsl
+ * requires of `workgroupBarrier()` and `storageBarrier()`.
+ *
+ * Control flow is non-uniform within statements whose condition depends on
+ * a non-uniform value: the invocation ids, reads of read-write buffers,
+ * workgroup and private variables, atomics, and variables derived from
+ * them.  It also stays non-uniform after a `return`, `break` or `continue`
+ * taken in non-uniform control flow.  Calls to functions containing
+ * barriers count as barriers.
+ *
+ * Each function is checked with its arguments taken as uniform, and then
+ * with each argument in turn taken as non-uniform, to find the arguments
+ * its barriers depend on.  Calls passing non-uniform values for those
+ * arguments count as barriers in non-uniform control flow.
+ *
+ * The check approximates wgsl's uniformity analysis, and naga's validation
+ * remains the reference.
+ */
+pub(crate) fn check_barrier_uniformity(model: &ShaderModel) -> Vec<BlacklightError> {
+  let mut non_uniform_globals = HashSet::new();
+  for buffer_binding in model.buffer_bindings() {
+    if matches!(buffer_binding.disposition(), BufferDispositionRepr::ReadWrite) {
+      non_uniform_globals.insert(buffer_binding.name().clone());
+    }
+  }
+  let module_variables = model.workgroup_variables().iter()
+    .chain(model.private_variables());
+  for module_variable in module_variables {
+    non_uniform_globals.insert(module_variable.name().clone());
+  }
+
+  let mut errors = Vec::new();
+  let mut barrier_functions = HashMap::new();
+  for function in model.functions() {
+    let check_function = |non_uniform_arg: Option<&IdentifierModel>| {
+      let mut non_uniform = non_uniform_globals.clone();
+      non_uniform.extend(non_uniform_arg.cloned());
+      let mut checker = UniformityChecker::new(
+        ShaderItem::Function(function.name().as_str().to_string()),
+        non_uniform,
+        &barrier_functions,
+      );
+      checker.check(function.code_block());
+      checker
+    };
+    let checker = check_function(None);
+    let maybe_uniform_args = checker.has_barrier
+      .then(|| uniform_args(function, checker.errors.len(), check_function));
+    errors.extend(checker.errors);
+    if let Some(uniform_args) = maybe_uniform_args {
+      barrier_functions.insert(function.name().clone(), uniform_args);
+    }
+  }
+  for entrypoint in model.entrypoints() {
+    let mut non_uniform = non_uniform_globals.clone();
+    for builtin in ComputeBuiltin::ALL {
+      if builtin.is_non_uniform() {
+        non_uniform.insert(IdentifierModel::new(builtin.name()));
+      }
+    }
+    let mut checker = UniformityChecker::new(
+      ShaderItem::EntryPoint(entrypoint.name().to_string()),
+      non_uniform,
+      &barrier_functions,
+    );
+    checker.check(entrypoint.code_block());
+    errors.extend(checker.errors);
+  }
+  errors
+}
+
+/**
+ * Find the arguments of a function which must be uniform for its barriers
+ * to be reached in uniform control flow: those which, taken as non-uniform,
+ * lead to more barriers in non-uniform control flow than otherwise.
+ */
+fn uniform_args<'a, F>(
+  function: &FunctionModel,
+  num_errors: usize,
+  check_function: F,
+) -> Vec<bool>
+  where F: Fn(Option<&IdentifierModel>) -> UniformityChecker<'a>
+{
+  function.arg_names().iter()
+    .map(|arg_name| check_function(Some(arg_name)).errors.len() > num_errors)
+    .collect()
+}
+
+/** How control flow leaves a block early in non-uniform control flow. */
+#[derive(Clone, Copy, Debug, Default)]
+struct Divergence {
+  // A return was taken, leaving the rest of the procedure non-uniform.
+  returns: bool,
+
+  // A break or continue was taken, leaving the rest of the loop non-uniform.
+  breaks: bool,
+}
+impl Divergence {
+  fn any(self) -> bool {
+    self.returns || self.breaks
+  }
+
+  fn merge(self, other: Divergence) -> Divergence {
+    Divergence {
+      returns: self.returns || other.returns,
+      breaks: self.breaks || other.breaks,
+    }
+  }
+}
+
+/** Uniformity analysis of a single function or entry point. */
+struct UniformityChecker<'a> {
+  // The function or entry point being checked.
+  item: ShaderItem,
+
+  // The names holding non-uniform values.
+  non_uniform: HashSet<IdentifierModel>,
+
+  // The functions containing barriers, directly or through calls, along
+  // with which of their arguments must be uniform.
+  barrier_functions: &'a HashMap<IdentifierModel, Vec<bool>>,
+
+  // Whether a barrier is reached anywhere in the code.
+  has_barrier: bool,
+
+  // Whether to report barriers, which is off while values are analysed.
+  reporting: bool,
+
+  // The barriers found in non-uniform control flow.
+  errors: Vec<BlacklightError>,
+}
+impl<'a> UniformityChecker<'a> {
+  fn new(
+    item: ShaderItem,
+    non_uniform: HashSet<IdentifierModel>,
+    barrier_functions: &'a HashMap<IdentifierModel, Vec<bool>>,
+  ) -> Self {
+    UniformityChecker {
+      item,
+      non_uniform,
+      barrier_functions,
+      has_barrier: false,
+      reporting: false,
+      errors: Vec::new(),
+    }
+  }
+
+  /**
+   * Check a procedure body.  Values assigned later in a loop may flow into
+   * earlier conditions, so the non-uniform names are found by iterating to
+   * a fixed point before barriers are reported.
+   */
+  fn check(&mut self, code_block: &CodeBlockModel) {
+    loop {
+      let known = self.non_uniform.len();
+      self.check_block(code_block, false);
+      if self.non_uniform.len() == known {
+        break;
+      }
+    }
+    self.reporting = true;
+    self.check_block(code_block, false);
+  }
+
+  /** Check whether an expression may differ between invocations. */
+  fn is_non_uniform(&self, expr: &ExpressionModel) -> bool {
+    expr.any(&mut |expr| match expr {
+      ExpressionModel::Identifier(ident_expr) =>
+        self.non_uniform.contains(ident_expr.identifier()),
+      ExpressionModel::BufferRead(buffer_read) =>
+        self.non_uniform.contains(buffer_read.buffer_name()),
+      ExpressionModel::Reference(reference_expr) => reference_expr.lvalue()
+        .root_name()
+        .is_none_or(|name| self.non_uniform.contains(name)),
+      ExpressionModel::BuiltinCall(builtin_call) => matches!(
+        builtin_call.function(),
+        BuiltinFn::AtomicLoad | BuiltinFn::AtomicStore |
+        BuiltinFn::AtomicAdd | BuiltinFn::AtomicSub |
+        BuiltinFn::AtomicMax | BuiltinFn::AtomicMin |
+        BuiltinFn::AtomicAnd | BuiltinFn::AtomicOr | BuiltinFn::AtomicXor |
+        BuiltinFn::AtomicExchange | BuiltinFn::AtomicCompareExchangeWeak
+      ),
+      _ => false,
+    })
+  }
+
+  /** Mark the variable an lvalue belongs to as holding non-uniform values. */
+  fn mark_non_uniform(&mut self, lvalue: &LvalueModel) {
+    if let Some(name) = lvalue.root_name() {
+      self.non_uniform.insert(name.clone());
+    }
+  }
+
+  /** Record a barrier, or a call to a function containing one. */
+  fn check_barrier(&mut self, barrier: &str, non_uniform_flow: bool) {
+    self.has_barrier = true;
+    if self.reporting && non_uniform_flow {
+      self.errors.push(BlacklightError::NonUniformBarrier {
+        item: self.item.clone(),
+        barrier: barrier.to_string(),
+      });
+    }
+  }
+
+  /**
+   * Check whether a call to a function containing barriers passes
+   * non-uniform values for arguments which must be uniform.  Returns `None`
+   * if the function contains no barriers.
+   */
+  fn has_non_uniform_args(&self, call: &FunctionCallExprModel) -> Option<bool> {
+    let uniform_args = self.barrier_functions.get(call.function_name())?;
+    Some(call.arguments().iter()
+      .zip(uniform_args)
+      .any(|(arg, &uniform)| uniform && self.is_non_uniform(arg.as_ref())))
+  }
+
+  /** Check for calls to functions containing barriers within an expression. */
+  fn check_calls(&mut self, expr: &ExpressionModel, non_uniform_flow: bool) {
+    let mut called = Vec::new();
+    expr.any(&mut |expr| {
+      if let ExpressionModel::FunctionCall(function_call) = expr {
+        if let Some(non_uniform_args) = self.has_non_uniform_args(function_call) {
+          called.push((function_call.function_name().clone(), non_uniform_args));
+        }
+      }
+      false
+    });
+    for (name, non_uniform_args) in called {
+      self.check_barrier(
+        &format!("{}()", name.as_str()),
+        non_uniform_flow || non_uniform_args,
+      );
+    }
+  }
+
+  /** Check the statements of a block, returning how it diverges. */
+  fn check_block(&mut self, code_block: &CodeBlockModel, non_uniform_flow: bool)
+    -> Divergence
+  {
+    let mut non_uniform_flow = non_uniform_flow;
+    let mut divergence = Divergence::default();
+    for statement in code_block.statements() {
+      let statement_divergence = self.check_statement(statement, non_uniform_flow);
+      non_uniform_flow |= statement_divergence.any();
+      divergence = divergence.merge(statement_divergence);
+    }
+    divergence
+  }
+
+  /** Check a statement, returning how it diverges. */
+  fn check_statement(&mut self, statement: &StatementModel, non_uniform_flow: bool)
+    -> Divergence
+  {
+    match statement {
+      StatementModel::VarDecl(var_decl_stmt) => {
+        let binding = var_decl_stmt.binding();
+        if let Some(initial_value) = binding.initial_value() {
+          self.check_calls(initial_value, non_uniform_flow);
+          if non_uniform_flow || self.is_non_uniform(initial_value) {
+            self.non_uniform.insert(binding.name().clone());
+          }
+        }
+        Divergence::default()
+      },
+      StatementModel::Assign(assign_stmt) => {
+        let expression = assign_stmt.expression();
+        self.check_calls(expression, non_uniform_flow);
+        let target = assign_stmt.target();
+        let target_non_uniform = target.operands().into_iter()
+          .any(|operand| self.is_non_uniform(operand));
+        if non_uniform_flow || target_non_uniform || self.is_non_uniform(expression) {
+          self.mark_non_uniform(target);
+        }
+        Divergence::default()
+      },
+      StatementModel::IfElse(if_else_stmt) => {
+        let condition = if_else_stmt.condition();
+        self.check_calls(condition, non_uniform_flow);
+        let inner_flow = non_uniform_flow || self.is_non_uniform(condition);
+        let mut divergence = self.check_block(if_else_stmt.if_block(), inner_flow);
+        if let Some(else_block) = if_else_stmt.else_block() {
+          divergence = divergence.merge(self.check_block(else_block, inner_flow));
+        }
+        divergence
+      },
+      StatementModel::Expr(expr_stmt) => {
+        self.check_calls(expr_stmt.expression(), non_uniform_flow);
+        Divergence::default()
+      },
+      StatementModel::Call(call_stmt) => {
+        let call = call_stmt.call();
+        if let Some(non_uniform_args) = self.has_non_uniform_args(call) {
+          let barrier = format!("{}()", call.function_name().as_str());
+          self.check_barrier(&barrier, non_uniform_flow || non_uniform_args);
+        }
+        for arg in call.arguments() {
+          self.check_calls(arg.as_ref(), non_uniform_flow);
+        }
+        Divergence::default()
+      },
+      StatementModel::PhonyAssign(phony_assign_stmt) => {
+        self.check_calls(phony_assign_stmt.expression(), non_uniform_flow);
+        Divergence::default()
+      },
+      StatementModel::Return(return_stmt) => {
+        if let Some(expression) = return_stmt.expression() {
+          self.check_calls(expression, non_uniform_flow);
+        }
+        Divergence { returns: non_uniform_flow, breaks: false }
+      },
+      StatementModel::Loop(loop_stmt) => {
+        let continuing = loop_stmt.continuing();
+        let break_if_non_uniform = continuing
+          .and_then(|continuing| continuing.break_if())
+          .is_some_and(|break_if| self.is_non_uniform(break_if));
+        self.check_loop(non_uniform_flow || break_if_non_uniform, |checker, flow| {
+          let mut divergence = checker.check_block(loop_stmt.body(), flow);
+          if let Some(continuing) = continuing {
+            let flow = flow || divergence.any();
+            divergence = divergence.merge(checker.check_block(continuing.block(), flow));
+          }
+          divergence
+        })
+      },
+      StatementModel::While(while_stmt) => {
+        let condition = while_stmt.condition();
+        self.check_calls(condition, non_uniform_flow);
+        let inner_flow = non_uniform_flow || self.is_non_uniform(condition);
+        self.check_loop(inner_flow, |checker, flow| {
+          checker.check_block(while_stmt.body(), flow)
+        })
+      },
+      StatementModel::For(for_stmt) => {
+        let init = for_stmt.init();
+        if let Some(initial_value) = init.initial_value() {
+          self.check_calls(initial_value, non_uniform_flow);
+          if non_uniform_flow || self.is_non_uniform(initial_value) {
+            self.non_uniform.insert(init.name().clone());
+          }
+        }
+        let condition = for_stmt.condition();
+        self.check_calls(condition, non_uniform_flow);
+        let inner_flow = non_uniform_flow || self.is_non_uniform(condition);
+        self.check_loop(inner_flow, |checker, flow| {
+          let divergence = checker.check_block(for_stmt.body(), flow);
+          let update = for_stmt.update();
+          if flow || divergence.any() || checker.is_non_uniform(update.expression()) {
+            checker.mark_non_uniform(update.target());
+          }
+          divergence
+        })
+      },
+      StatementModel::Switch(switch_stmt) => {
+        let selector = switch_stmt.selector();
+        self.check_calls(selector, non_uniform_flow);
+        let inner_flow = non_uniform_flow || self.is_non_uniform(selector);
+        // Breaks within a clause leave the switch, so only returns persist.
+        let mut divergence = Divergence::default();
+        for clause in switch_stmt.clauses() {
+          divergence = divergence.merge(self.check_block(clause.body(), inner_flow));
+        }
+        Divergence { returns: divergence.returns, breaks: false }
+      },
+      StatementModel::Break | StatementModel::Continue =>
+        Divergence { returns: false, breaks: non_uniform_flow },
+      StatementModel::WorkgroupBarrier => {
+        self.check_barrier("workgroupBarrier()", non_uniform_flow);
+        Divergence::default()
+      },
+      StatementModel::StorageBarrier => {
+        self.check_barrier("storageBarrier()", non_uniform_flow);
+        Divergence::default()
+      },
+    }
+  }
+
+  /**
+   * Check a loop.  A break or continue taken in non-uniform control flow
+   * leaves later iterations non-uniform, so the body is first checked
+   * without reporting to find out, then checked again.
+   */
+  fn check_loop<F>(&mut self, non_uniform_flow: bool, check_body: F) -> Divergence
+    where F: Fn(&mut Self, bool) -> Divergence
+  {
+    let reporting = self.reporting;
+    self.reporting = false;
+    let divergence = check_body(self, non_uniform_flow);
+    self.reporting = reporting;
+    let divergence = check_body(self, non_uniform_flow || divergence.breaks);
+    Divergence { returns: divergence.returns, breaks: false }
+  }
+}
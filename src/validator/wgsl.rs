@@ -0,0 +1,89 @@
+use std::error::Error;
+use naga::{
+  front::wgsl::parse_str,
+  valid::{ Capabilities, ValidationError, ValidationFlags, Validator },
+};
+use crate::{
+  api::{ BlacklightError, ShaderItem },
+  model::ShaderModel,
+};
+
+/**
+ * Parse and validate generated wgsl with naga.
+ *
+ * Diagnostics are attributed to the function or entry point of the model
+ * whose generated code they refer to, where possible.
+ */
+pub(crate) fn validate_wgsl(model: &ShaderModel, source: &str)
+  -> Result<(), BlacklightError>
+{
+  let module = match parse_str(source) {
+    Ok(module) => module,
+    Err(err) => {
+      let line = err.location(source).map(|location| location.line_number);
+      let item = line.and_then(|line| item_at_line(model, source, line));
+      return Err(BlacklightError::InvalidWgsl {
+        item,
+        message: err.message().to_string(),
+        line,
+      });
+    },
+  };
+
+  let mut validator =
+    Validator::new(ValidationFlags::all(), Capabilities::default());
+  if let Err(err) = validator.validate(&module) {
+    let line = err.location(source).map(|location| location.line_number);
+    let item = match err.as_inner() {
+      ValidationError::Function { name, .. } =>
+        Some(ShaderItem::Function(name.clone())),
+      ValidationError::EntryPoint { name, .. } =>
+        Some(ShaderItem::EntryPoint(name.clone())),
+      _ => line.and_then(|line| item_at_line(model, source, line)),
+    };
+    return Err(BlacklightError::InvalidWgsl {
+      item,
+      message: error_chain_message(err.as_inner()),
+      line,
+    });
+  }
+  Ok(())
+}
+
+/**
+ * Find the function or entry point whose generated code contains the given
+ * 1-based line.  Top-level items start with an unindented line and end with
+ * an unindented closing brace.
+ */
+fn item_at_line(model: &ShaderModel, source: &str, line: u32)
+  -> Option<ShaderItem>
+{
+  let mut current_fn = None;
+  for source_line in source.lines().take(line as usize - 1) {
+    if let Some(rest) = source_line.strip_prefix("fn ") {
+      current_fn = rest.split('(').next();
+    } else if source_line == "}" {
+      current_fn = None;
+    }
+  }
+  let name = current_fn?;
+  let is_entrypoint = model.entrypoints().iter()
+    .any(|entrypoint| entrypoint.name() == name);
+  if is_entrypoint {
+    Some(ShaderItem::EntryPoint(name.to_string()))
+  } else {
+    Some(ShaderItem::Function(name.to_string()))
+  }
+}
+
+/** Join an error's message with those of the errors that caused it. */
+fn error_chain_message(err: &dyn Error) -> String {
+  let mut message = err.to_string();
+  let mut maybe_source = err.source();
+  while let Some(source) = maybe_source {
+    message.push_str(": ");
+    message.push_str(&source.to_string());
+    maybe_source = source.source();
+  }
+  message
+}